@@ -44,6 +44,12 @@ pub type Balance = u128;
 /// Type used for expressing timestamp.
 pub type Moment = u64;
 
+/// Identifier of a fungible asset, such as a social token.
+pub type AssetId = u32;
+
+/// Identifier of a social swap exchange.
+pub type ExchangeId = u64;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -33,7 +33,7 @@
 use std::sync::Arc;
 
 use sp_keystore::SyncCryptoStorePtr;
-use node_primitives::{Block, BlockNumber, AccountId, Index, Balance, Hash};
+use node_primitives::{Block, BlockNumber, AccountId, AssetId, Index, Balance, ExchangeId, Hash, Moment};
 use sc_consensus_babe::{Config, Epoch};
 use sc_consensus_babe_rpc::BabeRpcHandler;
 use sc_consensus_epochs::SharedEpochChanges;
@@ -117,6 +117,9 @@ pub fn create_full<C, P, SC, B>(
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_social_swap_rpc::SocialSwapRuntimeApi<Block, AssetId, Balance, ExchangeId>,
+	C::Api: pallet_social_farm_rpc::SocialFarmRuntimeApi<Block, AccountId, Balance, ExchangeId>,
+	C::Api: pallet_social_nft_rpc::SocialNftRuntimeApi<Block, AccountId, AssetId, Balance, Moment>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
@@ -127,6 +130,9 @@ pub fn create_full<C, P, SC, B>(
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_contracts_rpc::{Contracts, ContractsApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use pallet_social_swap_rpc::{SocialSwap, SocialSwapApi};
+	use pallet_social_farm_rpc::{SocialFarm, SocialFarmApi};
+	use pallet_social_nft_rpc::{SocialNft, SocialNftApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
@@ -164,6 +170,15 @@ pub fn create_full<C, P, SC, B>(
 	io.extend_with(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
+	io.extend_with(
+		SocialSwapApi::to_delegate(SocialSwap::new(client.clone()))
+	);
+	io.extend_with(
+		SocialFarmApi::to_delegate(SocialFarm::new(client.clone()))
+	);
+	io.extend_with(
+		SocialNftApi::to_delegate(SocialNft::new(client.clone()))
+	);
 	io.extend_with(
 		sc_consensus_babe_rpc::BabeApi::to_delegate(
 			BabeRpcHandler::new(
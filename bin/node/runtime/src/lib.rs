@@ -48,18 +48,18 @@ use sp_core::{
 	U256,
 };
 pub use node_primitives::{AccountId, Signature};
-use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
+use node_primitives::{AccountIndex, AssetId, Balance, BlockNumber, ExchangeId, Hash, Index, Moment};
 use sp_api::impl_runtime_apis;
 use sp_runtime::{
 	Permill, Perbill, Perquintill, Percent, ApplyExtrinsicResult,
-	impl_opaque_keys, generic, create_runtime_str, ModuleId, FixedPointNumber,
+	impl_opaque_keys, generic, create_runtime_str, ModuleId, FixedPointNumber, FixedU128,
 	MultiSigner, traits::Convert,
 };
 use sp_runtime::curve::PiecewiseLinear;
 use sp_runtime::transaction_validity::{TransactionValidity, TransactionSource, TransactionPriority};
 use sp_runtime::traits::{
 	self, BlakeTwo256, Block as BlockT, StaticLookup, SaturatedConversion,
-	ConvertInto, OpaqueKeys, NumberFor,
+	ConvertInto, OpaqueKeys, NumberFor, AccountIdConversion,
 };
 use sp_version::RuntimeVersion;
 #[cfg(any(feature = "std", test))]
@@ -118,10 +118,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 };
 
 /// Native version.
@@ -994,12 +994,13 @@ parameter_types! {
 	pub const StringLimit: u32 = 50;
 	pub const MetadataDepositBase: Balance = 10 * NET;
 	pub const MetadataDepositPerByte: Balance = 1 * NET;
+	pub const ApprovalDeposit: Balance = 1 * NET;
 }
 
 impl pallet_assets::Config for Runtime {
 	type Event = Event;
 	type Balance = u128;
-	type AssetId = u32;
+	type AssetId = AssetId;
 	type Currency = Balances;
 	type ForceOrigin = EnsureRoot<AccountId>;
 	type AssetDepositBase = AssetDepositBase;
@@ -1007,6 +1008,7 @@ impl pallet_assets::Config for Runtime {
 	type StringLimit = StringLimit;
 	type MetadataDepositBase = MetadataDepositBase;
 	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1053,14 +1055,31 @@ impl pallet_did::Config for Runtime {
 	type Signature = Signature;
 }
 
+parameter_types! {
+	pub const MaxPayoutsPerBlock: u32 = 2;
+}
+
 impl pallet_social_treasury::Config for Runtime {
 	type Event = Event;
 	type FungibleToken = Assets;
+	type MaxPayoutsPerBlock = MaxPayoutsPerBlock;
+	type EmissionOrigin = EnsureRootOrHalfCouncil;
 	type WeightInfo = pallet_social_treasury::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const GuardianDeposit: Balance = 100 * NET;
+	pub const MaxGuardiansPerToken: u32 = 16;
+	pub const MaxPrunedGuardianHistory: u32 = 100;
+}
+
 impl pallet_social_guardians::Config for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type GuardianDeposit = GuardianDeposit;
+	type MaxGuardians = MaxGuardiansPerToken;
+	type MaxPrunedPerBlock = MaxPrunedGuardianHistory;
+	type WeightInfo = pallet_social_guardians::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -1081,6 +1100,11 @@ impl pallet_social_usernames::Config for Runtime {
 parameter_types! {
     pub const ChainId: u8 = 5;
     pub const ProposalLifetime: u32 = 50;
+    pub const BridgeLimitDelay: BlockNumber = 1 * DAYS;
+    pub const RelayerBond: Balance = 100 * NET;
+    pub const MaxRelayerStrikes: u32 = 3;
+    pub const MaxMissedVotes: u32 = 100;
+    pub const MaxPrunedProposalsPerBlock: u32 = 50;
 }
 
 impl pallet_chainbridge::Config for Runtime {
@@ -1089,30 +1113,66 @@ impl pallet_chainbridge::Config for Runtime {
     type Proposal = Call;
     type ChainId = ChainId;
     type ProposalLifetime = ProposalLifetime;
-}
-
-parameter_types! {
-	pub HashId: pallet_chainbridge::ResourceId = pallet_chainbridge::derive_resource_id(ChainId::get(), b"NET_HASH");
-	pub NativeTokenId: pallet_chainbridge::ResourceId = pallet_chainbridge::derive_resource_id(ChainId::get(), b"NET");
-	pub Erc721Id: pallet_chainbridge::ResourceId = pallet_chainbridge::derive_resource_id(ChainId::get(), b"NET_NFT");
+    type ProposalTransfer = SocialBridge;
+    type LimitDelay = BridgeLimitDelay;
+    type OnProposalExecuted = SocialBridge;
+    type OnRelayerRemoved = SocialBridge;
+    type Currency = Balances;
+    type RelayerBond = RelayerBond;
+    type Slash = Treasury;
+    type MaxStrikes = MaxRelayerStrikes;
+    type MaxMissedVotes = MaxMissedVotes;
+    type MaxPrunedPerBlock = MaxPrunedProposalsPerBlock;
 }
 
 impl pallet_social_bridge::Config for Runtime {
 	type Event = Event;
 	type BridgeOrigin = pallet_chainbridge::EnsureBridge<Self>;
 	type Currency = Balances;
-	type HashId = HashId;
-	type NativeTokenId = NativeTokenId;
-	type Erc721Id = Erc721Id;
+}
+
+parameter_types! {
+	pub Erc721Id: pallet_chainbridge::ResourceId = pallet_chainbridge::derive_resource_id(ChainId::get(), b"NET_NFT");
+	pub const NftModuleId: ModuleId = ModuleId(*b"py/nftau");
+	pub const SoftCloseWindow: BlockNumber = 10 * MINUTES;
+	pub const MaxRoyaltyRecipients: u32 = 10;
+	pub const MaxAuctionsSettledPerBlock: u32 = 50;
+	pub const MaxBidsPerToken: u32 = 20;
+	pub const MaxOwnersIndexedPerBlock: u32 = 500;
 }
 
 impl pallet_social_nft::Config for Runtime {
 	type Event = Event;
 	type Identifier = Erc721Id;
+	type ModuleId = NftModuleId;
+	type SoftCloseWindow = SoftCloseWindow;
+	type MaxRoyaltyRecipients = MaxRoyaltyRecipients;
+	type MaxAuctionsSettledPerBlock = MaxAuctionsSettledPerBlock;
+	type MaxBidsPerToken = MaxBidsPerToken;
+	type MaxOwnersIndexedPerBlock = MaxOwnersIndexedPerBlock;
+}
+
+parameter_types! {
+	pub const MultiTokenUriLimit: u32 = 256;
+	pub const MultiTokenMaxBatchSize: u32 = 64;
+	pub const MultiTokenCreateDeposit: Balance = 10 * NET;
+}
+
+impl pallet_social_multi_token::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type UriLimit = MultiTokenUriLimit;
+	type MaxBatchSize = MultiTokenMaxBatchSize;
+	type Currency = Balances;
+	type CreateDeposit = MultiTokenCreateDeposit;
 }
 
 parameter_types! {
 	pub const ExchangeModuleId: ModuleId = ModuleId(*b"exchange");
+	pub const SwapFee: Permill = Permill::from_parts(3_000);
+	pub const ProtocolFeeShare: Permill = Permill::from_parts(166_667);
+	pub SwapFeeTreasury: AccountId = TreasuryModuleId::get().into_account();
+	pub const MaxSwapPathLength: u32 = 5;
 }
 
 pub struct BalanceHandler;
@@ -1127,18 +1187,66 @@ impl pallet_social_swap::Config for Runtime {
 	type Event = Event;
 	type FungibleToken = Assets;
 	type Handler = BalanceHandler;
-	type ExchangeId = u64;
+	type ExchangeId = ExchangeId;
+	type SwapFee = SwapFee;
+	type ProtocolFeeShare = ProtocolFeeShare;
+	type FeeOrigin = EnsureRootOrHalfCouncil;
+	type FeeTreasury = SwapFeeTreasury;
+	type MaxPathLength = MaxSwapPathLength;
 }
 
 parameter_types! {
     pub const MinimumLiquidity: u64 = 1000;
+	pub const Swap2ModuleId: ModuleId = ModuleId(*b"py/swap2");
+	pub const PairMaxZombies: u32 = 100;
+	pub const PairMinBalance: u32 = 1;
+	pub const ObservationCapacity: u32 = 24;
+	// in seconds
+	pub const ObservationPeriod: u32 = 5 * 60;
 }
 
 impl pallet_social_swap2::Config for Runtime {
 	type Currency = Balances;
+	type ModuleId = Swap2ModuleId;
 	type Event = Event;
 	type FungibleToken = Assets;
 	type MinimumLiquidity = MinimumLiquidity;
+	type PairMaxZombies = PairMaxZombies;
+	type PairMinBalance = PairMinBalance;
+	type SwapFee = SwapFee;
+	type ProtocolFeeShare = ProtocolFeeShare;
+	type FeeOrigin = EnsureRootOrHalfCouncil;
+	type FeeTreasury = SwapFeeTreasury;
+	type ObservationCapacity = ObservationCapacity;
+	type ObservationPeriod = ObservationPeriod;
+	type FlashSwapCallee = ();
+}
+
+parameter_types! {
+	pub const OrderbookModuleId: ModuleId = ModuleId(*b"py/ordbk");
+	pub const MaxOrdersPerSide: u32 = 500;
+	pub const MaxFills: u32 = 20;
+	pub const OrderDeposit: Balance = 1 * NET;
+}
+
+impl pallet_social_orderbook::Config for Runtime {
+	type Event = Event;
+	type ModuleId = OrderbookModuleId;
+	type FungibleToken = Assets;
+	type Currency = Balances;
+	type OrderDeposit = OrderDeposit;
+	type MaxOrdersPerSide = MaxOrdersPerSide;
+	type MaxFills = MaxFills;
+}
+
+parameter_types! {
+	pub const FarmModuleId: ModuleId = ModuleId(*b"py/sfarm");
+}
+
+impl pallet_social_farm::Config for Runtime {
+	type Event = Event;
+	type ModuleId = FarmModuleId;
+	type EmissionOrigin = EnsureRootOrHalfCouncil;
 }
 
 construct_runtime!(
@@ -1189,10 +1297,13 @@ construct_runtime!(
 		SocialGuardians: pallet_social_guardians::{Module, Call, Storage, Event<T>},
 		SocialUsernames: pallet_social_usernames::{Module, Call, Storage, Event<T>},
 		ChainBridge: pallet_chainbridge::{Module, Call, Storage, Event<T>},
-		SocialBridge: pallet_social_bridge::{Module, Call, Event<T>},
+		SocialBridge: pallet_social_bridge::{Module, Call, Storage, Event<T>},
 		SocialNFT: pallet_social_nft::{Module, Call, Storage, Event<T>},
+		SocialMultiToken: pallet_social_multi_token::{Module, Call, Storage, Event<T>},
 		SocialSwap: pallet_social_swap::{Module, Call, Storage, Event<T>},
 		SocialSwap2: pallet_social_swap2::{Module, Call, Storage, Event<T>},
+		Orderbook: pallet_social_orderbook::{Module, Call, Storage, Event<T>},
+		SocialFarm: pallet_social_farm::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -1425,6 +1536,97 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_social_swap_rpc_runtime_api::SocialSwapApi<Block, AssetId, Balance, ExchangeId>
+		for Runtime
+	{
+		fn get_input_price(
+			exchange_id: ExchangeId,
+			amount_in: Balance,
+			direction: pallet_social_swap::SwapDirection,
+		) -> Option<Balance> {
+			SocialSwap::get_input_price(exchange_id, amount_in, direction)
+		}
+
+		fn get_output_price(
+			exchange_id: ExchangeId,
+			amount_out: Balance,
+			direction: pallet_social_swap::SwapDirection,
+		) -> Option<Balance> {
+			SocialSwap::get_output_price(exchange_id, amount_out, direction)
+		}
+
+		fn get_exchange(
+			exchange_id: ExchangeId,
+		) -> Option<pallet_social_swap::Exchange<AssetId, Balance, Balance>> {
+			SocialSwap::exchanges(exchange_id)
+		}
+
+		fn get_exchange_by_token(
+			asset_id: AssetId,
+		) -> Option<(ExchangeId, pallet_social_swap::Exchange<AssetId, Balance, Balance>)> {
+			SocialSwap::get_exchange_by_token(asset_id)
+		}
+
+		fn get_liquidity_value(exchange_id: ExchangeId, liquidity: Balance) -> Option<(Balance, Balance)> {
+			SocialSwap::get_liquidity_value(exchange_id, liquidity)
+		}
+	}
+
+	impl pallet_assets_rpc_runtime_api::AssetsApi<Block, AssetId, AccountId> for Runtime {
+		fn token_dossier(asset_id: AssetId) -> Option<(AccountId, pallet_assets::TokenDossier)> {
+			Assets::token_dossier(asset_id)
+		}
+	}
+
+	impl pallet_social_swap2_rpc_runtime_api::SocialSwap2OracleApi<Block, AssetId> for Runtime {
+		fn consult(asset_a: AssetId, asset_b: AssetId, window: u32) -> Option<(u128, u128)> {
+			SocialSwap2::consult((asset_a, asset_b), window)
+		}
+	}
+
+	impl pallet_social_orderbook_rpc_runtime_api::SocialOrderbookApi<Block, AssetId, Balance, FixedU128> for Runtime {
+		fn depth(base: AssetId, quote: AssetId, levels: u32) -> (Vec<(FixedU128, Balance)>, Vec<(FixedU128, Balance)>) {
+			Orderbook::depth(base, quote, levels)
+		}
+	}
+
+	impl pallet_social_farm_rpc_runtime_api::SocialFarmApi<Block, AccountId, Balance, ExchangeId> for Runtime {
+		fn pending_rewards(exchange_id: ExchangeId, who: AccountId) -> Balance {
+			SocialFarm::pending_rewards(exchange_id, who)
+		}
+	}
+
+	impl pallet_social_bridge_rpc_runtime_api::SocialBridgeApi<Block, AccountId, Balance> for Runtime {
+		fn transfer_fee(
+			dest_id: pallet_chainbridge::ChainId,
+			resource_id: pallet_chainbridge::ResourceId,
+		) -> Balance {
+			SocialBridge::transfer_fee(dest_id, resource_id)
+		}
+
+		fn relayer_reward(relayer: AccountId) -> Balance {
+			SocialBridge::relayer_reward(&relayer)
+		}
+	}
+
+	impl pallet_social_nft_rpc_runtime_api::SocialNftApi<Block, AccountId, AssetId, Balance, Moment> for Runtime {
+		fn tokens_of_owner(owner: AccountId, start: u32, limit: u32) -> Vec<pallet_social_nft::NftId> {
+			SocialNFT::tokens_of_owner(owner, start, limit)
+		}
+
+		fn token_details(id: pallet_social_nft::NftId) -> Option<pallet_social_nft::TokenDetails<AccountId>> {
+			SocialNFT::token_details(id)
+		}
+
+		fn asks(id: pallet_social_nft::NftId) -> Vec<(AssetId, Balance)> {
+			SocialNFT::asks(id)
+		}
+
+		fn bids(id: pallet_social_nft::NftId) -> Vec<(AccountId, AssetId, Balance, Moment)> {
+			SocialNFT::bids(id)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
 		Block,
 		Balance,
@@ -1526,6 +1728,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_proxy, Proxy);
 			add_benchmark!(params, batches, pallet_scheduler, Scheduler);
 			add_benchmark!(params, batches, pallet_session, SessionBench::<Runtime>);
+			add_benchmark!(params, batches, pallet_social_guardians, SocialGuardians);
 			add_benchmark!(params, batches, pallet_staking, Staking);
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
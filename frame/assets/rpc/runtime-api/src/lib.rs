@@ -0,0 +1,23 @@
+//! Runtime API definition of the token dossiers of the assets module.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to expose the identity of its tokens
+//! to wallets, explorers and bridges.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_assets::TokenDossier;
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the identity of the tokens of the assets module.
+	pub trait AssetsApi<AssetId, AccountId> where
+		AssetId: Codec,
+		AccountId: Codec,
+	{
+		/// The creator and the dossier of the token `asset_id`.
+		///
+		/// Returns `None` if the token doesn't exist or has no dossier.
+		fn token_dossier(asset_id: AssetId) -> Option<(AccountId, TokenDossier)>;
+	}
+}
@@ -231,6 +231,95 @@ benchmarks! {
 	verify {
 		assert_last_event::<T>(Event::MetadataSet(Default::default(), name, symbol, decimals).into());
 	}
+
+	set_dossier {
+		let n in 0 .. T::StringLimit::get();
+		let s in 0 .. T::StringLimit::get();
+		let l in 0 .. T::StringLimit::get();
+
+		let dossier = TokenDossier {
+			name: vec![0u8; n as usize],
+			symbol: vec![0u8; s as usize],
+			decimals: 12,
+			logo_hash: Some([0u8; 32]),
+			social_link: vec![0u8; l as usize],
+		};
+
+		let (caller, _) = create_default_asset::<T>(10);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	}: _(SystemOrigin::Signed(caller), Default::default(), dossier.clone())
+	verify {
+		assert_last_event::<T>(Event::DossierSet(Default::default(), dossier).into());
+	}
+
+	approve_transfer {
+		let (caller, _) = create_default_asset::<T>(10);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = T::Balance::from(100u32);
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), delegate_lookup, amount)
+	verify {
+		assert_last_event::<T>(Event::ApprovedTransfer(Default::default(), caller, delegate, amount).into());
+	}
+
+	cancel_approval {
+		let (caller, _) = create_default_asset::<T>(10);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = T::Balance::from(100u32);
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		assert!(Assets::<T>::approve_transfer(origin, Default::default(), delegate_lookup.clone(), amount).is_ok());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), delegate_lookup)
+	verify {
+		assert_last_event::<T>(Event::ApprovalCancelled(Default::default(), caller, delegate).into());
+	}
+
+	increase_allowance {
+		let (caller, _) = create_default_asset::<T>(10);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = T::Balance::from(100u32);
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		assert!(Assets::<T>::approve_transfer(origin, Default::default(), delegate_lookup.clone(), amount).is_ok());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), delegate_lookup, amount)
+	verify {
+		let allowance = T::Balance::from(200u32);
+		assert_last_event::<T>(Event::ApprovedTransfer(Default::default(), caller, delegate, allowance).into());
+	}
+
+	decrease_allowance {
+		let (caller, _) = create_default_asset::<T>(10);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = T::Balance::from(100u32);
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		assert!(Assets::<T>::approve_transfer(origin, Default::default(), delegate_lookup.clone(), amount).is_ok());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), delegate_lookup, 40u32.into())
+	verify {
+		let allowance = T::Balance::from(60u32);
+		assert_last_event::<T>(Event::ApprovedTransfer(Default::default(), caller, delegate, allowance).into());
+	}
+
+	transfer_approved {
+		let amount = T::Balance::from(100u32);
+		let (owner, owner_lookup) = create_default_minted_asset::<T>(10, amount);
+		T::Currency::make_free_balance_be(&owner, BalanceOf::<T>::max_value());
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let destination: T::AccountId = account("destination", 0, SEED);
+		let destination_lookup = T::Lookup::unlookup(destination.clone());
+		let origin = SystemOrigin::Signed(owner.clone()).into();
+		assert!(Assets::<T>::approve_transfer(origin, Default::default(), delegate_lookup, amount).is_ok());
+	}: _(SystemOrigin::Signed(delegate.clone()), Default::default(), owner_lookup, destination_lookup, amount)
+	verify {
+		assert_last_event::<T>(
+			Event::TransferredApproved(Default::default(), owner, delegate, destination, amount).into()
+		);
+	}
 }
 
 #[cfg(test)]
@@ -349,4 +438,46 @@ mod tests {
 			assert!(test_benchmark_set_metadata::<Test>().is_ok());
 		});
 	}
+
+	#[test]
+	fn set_dossier() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_set_dossier::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn approve_transfer() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_approve_transfer::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn cancel_approval() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_cancel_approval::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn increase_allowance() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_increase_allowance::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn decrease_allowance() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_decrease_allowance::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn transfer_approved() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_transfer_approved::<Test>().is_ok());
+		});
+	}
 }
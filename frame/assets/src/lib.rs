@@ -74,6 +74,10 @@
 //!
 //! * `create`: Creates a new asset class, taking the required deposit.
 //! * `transfer`: Transfer sender's assets to another account.
+//! * `approve_transfer`: Allow another account to transfer some of sender's assets.
+//! * `cancel_approval`: Revoke the approval of another account to transfer sender's assets.
+//! * `increase_allowance`, `decrease_allowance`: Adjust the assets another account may transfer.
+//! * `transfer_approved`: Transfer assets of another account within its approval.
 //!
 //! ### Permissioned Functions
 //!
@@ -90,6 +94,7 @@
 //! * `transfer_ownership`: Changes an asset class's Owner; called by the asset class's Owner.
 //! * `set_team`: Changes an asset class's Admin, Freezer and Issuer; called by the asset class's
 //!   Owner.
+//! * `set_dossier`: Sets the identity of an asset class; called by the asset class's Owner.
 //!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
@@ -127,6 +132,7 @@ use frame_support::{
 	ensure,
 	traits::{Currency, BalanceStatus::Reserved, Get, Imbalance, ReservableCurrency, TryDrop},
 	dispatch::DispatchError,
+	weights::Weight,
 };
 #[cfg(feature = "std")]
 use frame_support::traits::GenesisBuild;
@@ -145,15 +151,38 @@ pub enum CheckAssetIssuer {
 type Symbol = [u8; 8];
 const NET_V1: Symbol = *b"NETSWAP1";
 
-/// TODO: consider if there need to be more fields
-#[derive(Encode, Decode)]
+/// The identity of a token, as shown by wallets, explorers and the bridge.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, Default)]
 pub struct TokenDossier {
-	pub symbol: Symbol,
+	/// The user friendly name of the token. Limited in length by `StringLimit`.
+	pub name: Vec<u8>,
+	/// The ticker symbol of the token. Limited in length by `StringLimit`.
+	pub symbol: Vec<u8>,
+	/// The number of decimals the token uses to represent one unit.
+	pub decimals: u8,
+	/// The hash of the logo of the token, or of the document its URI points to.
+	pub logo_hash: Option<[u8; 32]>,
+	/// A link to the social profile behind the token. Limited in length by `StringLimit`.
+	pub social_link: Vec<u8>,
 }
 
 impl TokenDossier {
 	pub fn new_lp_token() -> Self {
-		TokenDossier { symbol: NET_V1 }
+		TokenDossier {
+			name: b"Social Swap V1".to_vec(),
+			symbol: NET_V1.to_vec(),
+			..Default::default()
+		}
+	}
+
+	/// The number of bytes a metadata deposit is taken for.
+	fn bytes_used(&self) -> usize {
+		self.name.len() + self.symbol.len() + self.social_link.len()
+			+ self.logo_hash.map_or(0, |hash| hash.len())
+	}
+
+	fn is_empty(&self) -> bool {
+		*self == Default::default()
 	}
 }
 
@@ -205,12 +234,20 @@ pub mod pallet {
 		/// metadata.
 		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
 
+		/// The amount of funds that must be reserved by the owner of assets for every delegate
+		/// approved to transfer them.
+		type ApprovalDeposit: Get<BalanceOf<Self>>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_metadata_to_dossiers()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -771,14 +808,14 @@ pub mod pallet {
 		///
 		/// Funds of sender are reserved according to the formula:
 		/// `MetadataDepositBase + MetadataDepositPerByte * (name.len + symbol.len)` taking into
-		/// account any already reserved funds.
+		/// account any already reserved funds, and the rest of the dossier of the asset.
 		///
 		/// - `id`: The identifier of the asset to update.
 		/// - `name`: The user friendly name of this asset. Limited in length by `StringLimit`.
 		/// - `symbol`: The exchange symbol for this asset. Limited in length by `StringLimit`.
 		/// - `decimals`: The number of decimals this asset uses to represent one unit.
 		///
-		/// Emits `MaxZombiesChanged`.
+		/// Emits `MetadataSet`.
 		///
 		/// Weight: `O(1)`
 		#[pallet::weight(T::WeightInfo::set_metadata(name.len() as u32, symbol.len() as u32))]
@@ -791,47 +828,204 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 
-			ensure!(name.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
-			ensure!(symbol.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+			let dossier = TokenDossier {
+				name: name.clone(),
+				symbol: symbol.clone(),
+				decimals,
+				..Metadata::<T>::get(id).dossier
+			};
+			Self::do_set_dossier(origin, id, dossier)?;
 
-			let d = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
-			ensure!(&origin == &d.owner, Error::<T>::NoPermission);
-
-			Metadata::<T>::try_mutate_exists(id, |metadata| {
-				let bytes_used = name.len() + symbol.len();
-				let old_deposit = match metadata {
-					Some(m) => m.deposit,
-					None => Default::default()
-				};
-
-				// Metadata is being removed
-				if bytes_used.is_zero() && decimals.is_zero() {
-					T::Currency::unreserve(&origin, old_deposit);
-					*metadata = None;
-				} else {
-					let new_deposit = T::MetadataDepositPerByte::get()
-						.saturating_mul(((name.len() + symbol.len()) as u32).into())
-						.saturating_add(T::MetadataDepositBase::get());
+			Self::deposit_event(Event::MetadataSet(id, name, symbol, decimals));
+			Ok(().into())
+		}
 
-					if new_deposit > old_deposit {
-						T::Currency::reserve(&origin, new_deposit - old_deposit)?;
-					} else {
-						T::Currency::unreserve(&origin, old_deposit - new_deposit);
-					}
+		/// Set the whole dossier of an asset: its metadata, logo hash and social link.
+		///
+		/// Origin must be Signed and the sender should be the Owner of the asset `id`.
+		///
+		/// Funds of sender are reserved according to the formula:
+		/// `MetadataDepositBase + MetadataDepositPerByte * (name.len + symbol.len + social_link.len
+		/// + 32 with a logo hash)` taking into account any already reserved funds. An empty
+		/// dossier clears it and returns the deposit.
+		///
+		/// - `id`: The identifier of the asset to update.
+		/// - `dossier`: The new identity of the asset. Its name, symbol and social link are
+		/// limited in length by `StringLimit`.
+		///
+		/// Emits `DossierSet`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::set_dossier(
+			dossier.name.len() as u32,
+			dossier.symbol.len() as u32,
+			dossier.social_link.len() as u32,
+		))]
+		pub(super) fn set_dossier(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			dossier: TokenDossier,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
 
-					*metadata = Some(AssetMetadata {
-						deposit: new_deposit,
-						name: name.clone(),
-						symbol: symbol.clone(),
-						decimals,
-					})
-				}
+			Self::do_set_dossier(origin, id, dossier.clone())?;
 
-				Self::deposit_event(Event::MetadataSet(id, name, symbol, decimals));
-				Ok(().into())
-			})
+			Self::deposit_event(Event::DossierSet(id, dossier));
+			Ok(().into())
 		}
 
+		/// Approve `delegate` to transfer up to `amount` assets `id` of the sender, replacing
+		/// any previous approval.
+		///
+		/// Origin must be Signed and the sender must have sufficient funds free for the
+		/// `ApprovalDeposit` of a new approval. The deposit is returned once the approval is
+		/// cancelled or used up.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `delegate`: The account allowed to transfer the assets with `transfer_approved`.
+		/// - `amount`: The amount of assets `delegate` may transfer. Zero cancels the approval.
+		///
+		/// Emits `ApprovedTransfer`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::approve_transfer())]
+		pub(super) fn approve_transfer(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			ensure!(Asset::<T>::contains_key(id), Error::<T>::Unknown);
+
+			Self::set_allowance(id, &owner, &delegate, amount)?;
+
+			Self::deposit_event(Event::ApprovedTransfer(id, owner, delegate, amount));
+			Ok(().into())
+		}
+
+		/// Cancel the approval of `delegate` to transfer assets `id` of the sender, returning
+		/// its deposit.
+		///
+		/// Origin must be Signed and there must be an approval in place from the sender to
+		/// `delegate`.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `delegate`: The account no longer allowed to transfer the assets.
+		///
+		/// Emits `ApprovalCancelled`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::cancel_approval())]
+		pub(super) fn cancel_approval(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			ensure!(Allowances::<T>::contains_key(id, (&owner, &delegate)), Error::<T>::NotAllowed);
+
+			Self::set_allowance(id, &owner, &delegate, Zero::zero())?;
+
+			Self::deposit_event(Event::ApprovalCancelled(id, owner, delegate));
+			Ok(().into())
+		}
+
+		/// Raise the amount of assets `id` of the sender `delegate` may transfer by `amount`.
+		///
+		/// Origin must be Signed.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `delegate`: The account allowed to transfer the assets with `transfer_approved`.
+		/// - `amount`: The amount added to the approval.
+		///
+		/// Emits `ApprovedTransfer` with the new amount approved.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::increase_allowance())]
+		pub(super) fn increase_allowance(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			ensure!(Asset::<T>::contains_key(id), Error::<T>::Unknown);
+
+			let allowance = Self::allowances(id, (&owner, &delegate))
+				.checked_add(&amount)
+				.ok_or(Error::<T>::Overflow)?;
+			Self::set_allowance(id, &owner, &delegate, allowance)?;
+
+			Self::deposit_event(Event::ApprovedTransfer(id, owner, delegate, allowance));
+			Ok(().into())
+		}
+
+		/// Lower the amount of assets `id` of the sender `delegate` may transfer by `amount`,
+		/// down to zero.
+		///
+		/// Origin must be Signed.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `delegate`: The account allowed to transfer the assets with `transfer_approved`.
+		/// - `amount`: The amount removed from the approval.
+		///
+		/// Emits `ApprovedTransfer` with the new amount approved.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::decrease_allowance())]
+		pub(super) fn decrease_allowance(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			ensure!(Asset::<T>::contains_key(id), Error::<T>::Unknown);
+
+			let allowance = Self::allowances(id, (&owner, &delegate)).saturating_sub(amount);
+			Self::set_allowance(id, &owner, &delegate, allowance)?;
+
+			Self::deposit_event(Event::ApprovedTransfer(id, owner, delegate, allowance));
+			Ok(().into())
+		}
+
+		/// Transfer some assets of `owner` to another account, spending the approval of the
+		/// sender.
+		///
+		/// Origin must be Signed and `owner` must have approved the sender to transfer at
+		/// least `amount` assets `id`.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `owner`: The account whose assets are transferred.
+		/// - `destination`: The account to be credited.
+		/// - `amount`: The amount of assets transferred, deducted from the approval.
+		///
+		/// Emits `TransferredApproved`, and `Transferred` with the actual amount transferred as
+		/// in `transfer`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::transfer_approved())]
+		pub(super) fn transfer_approved(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			destination: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let delegate = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let destination = T::Lookup::lookup(destination)?;
+
+			Self::do_transfer_approved(id, &owner, &delegate, &destination, amount)?;
+
+			Self::deposit_event(Event::TransferredApproved(id, owner, delegate, destination, amount));
+			Ok(().into())
+		}
 	}
 
 	#[pallet::event]
@@ -868,6 +1062,16 @@ pub mod pallet {
 		MaxZombiesChanged(T::AssetId, u32),
 		/// New metadata has been set for an asset. \[asset_id, name, symbol, decimals\]
 		MetadataSet(T::AssetId, Vec<u8>, Vec<u8>, u8),
+		/// A new dossier has been set for an asset. \[asset_id, dossier\]
+		DossierSet(T::AssetId, TokenDossier),
+		/// An account was allowed to transfer some assets of another one.
+		/// \[asset_id, owner, delegate, amount\]
+		ApprovedTransfer(T::AssetId, T::AccountId, T::AccountId, T::Balance),
+		/// An approval to transfer assets was cancelled. \[asset_id, owner, delegate\]
+		ApprovalCancelled(T::AssetId, T::AccountId, T::AccountId),
+		/// Some approved assets were transferred by a delegate.
+		/// \[asset_id, owner, delegate, destination, amount\]
+		TransferredApproved(T::AssetId, T::AccountId, T::AccountId, T::AccountId, T::Balance),
 	}
 
 	#[deprecated(note = "use `Event` instead")]
@@ -932,10 +1136,13 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		T::AssetId,
-		AssetMetadata<BalanceOf<T>>,
+		AssetMetadata<BalanceOf<T>, T::AccountId>,
 		ValueQuery
 	>;
 	#[pallet::storage]
+	/// Storage version of the pallet, new networks start with the metadata holding dossiers.
+	pub(super) type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+	#[pallet::storage]
 	#[pallet::getter(fn max_asset_id)]
 	/// The maximum asset id.
 	pub(super) type MaxAssetId<T: Config> = StorageValue<_, T::AssetId, ValueQuery>;
@@ -951,6 +1158,18 @@ pub mod pallet {
 		T::Balance,
 		ValueQuery
 	>;
+	#[pallet::storage]
+	#[pallet::getter(fn approval_deposit)]
+	/// The deposit reserved by the owner for every approval in `Allowances`.
+	pub(super) type ApprovalDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		(T::AccountId, T::AccountId),
+		BalanceOf<T>,
+		ValueQuery
+	>;
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
@@ -995,7 +1214,7 @@ pub mod pallet {
 					is_zombie: false
 				});
 			}
-
+			StorageVersion::<T>::put(Releases::V2);
 		}
 	}
 }
@@ -1066,19 +1285,39 @@ pub struct AssetBalance<
 }
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, Default)]
-pub struct AssetMetadata<DepositBalance> {
+pub struct AssetMetadata<DepositBalance, AccountId> {
 	/// The balance deposited for this metadata.
 	///
 	/// This pays for the data stored in this struct.
 	deposit: DepositBalance,
-	/// The user friendly name of this asset. Limited in length by `StringLimit`.
+	/// The account which first set the metadata, the creator of the asset for the assets
+	/// created with a dossier.
+	creator: AccountId,
+	/// The identity of this asset.
+	dossier: TokenDossier,
+}
+
+/// The metadata of an asset as stored before it held a `TokenDossier`.
+#[derive(Encode, Decode)]
+struct AssetMetadataV1<DepositBalance> {
+	deposit: DepositBalance,
 	name: Vec<u8>,
-	/// The ticker symbol for this asset. Limited in length by `StringLimit`.
 	symbol: Vec<u8>,
-	/// The number of decimals this asset uses to represent one unit.
 	decimals: u8,
 }
 
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 // The main implementation block for the module.
 impl<T: Config> Pallet<T> {
 	// Public immutables
@@ -1093,6 +1332,11 @@ impl<T: Config> Pallet<T> {
 		Asset::<T>::get(id).map(|x| x.supply).unwrap_or_else(Zero::zero)
 	}
 
+	/// Get the owner of an asset `id`.
+	pub fn owner(id: T::AssetId) -> Option<T::AccountId> {
+		Asset::<T>::get(id).map(|x| x.owner)
+	}
+
 	/// Check the number of zombies allow yet for an asset.
 	pub fn zombie_allowance(id: T::AssetId) -> u32 {
 		Asset::<T>::get(id).map(|x| x.max_zombies - x.zombies).unwrap_or_else(Zero::zero)
@@ -1105,6 +1349,136 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Set the amount of assets `id` of `owner` that `delegate` may transfer, reserving the
+	/// approval deposit of `owner` for a new approval and releasing it once it is zero.
+	fn set_allowance(
+		id: T::AssetId,
+		owner: &T::AccountId,
+		delegate: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		let key = (owner.clone(), delegate.clone());
+		if amount.is_zero() {
+			Allowances::<T>::remove(id, &key);
+			T::Currency::unreserve(owner, ApprovalDeposits::<T>::take(id, &key));
+		} else {
+			if !Allowances::<T>::contains_key(id, &key) {
+				let deposit = T::ApprovalDeposit::get();
+				T::Currency::reserve(owner, deposit)?;
+				ApprovalDeposits::<T>::insert(id, &key, deposit);
+			}
+			Allowances::<T>::insert(id, &key, amount);
+		}
+		Ok(())
+	}
+
+	/// Transfer `amount` assets `id` of `owner` to `destination` on behalf of `delegate`,
+	/// spending its approval.
+	pub fn do_transfer_approved(
+		id: T::AssetId,
+		owner: &T::AccountId,
+		delegate: &T::AccountId,
+		destination: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		let allowance = Self::allowances(id, (owner, delegate))
+			.checked_sub(&amount)
+			.ok_or(Error::<T>::NotAllowed)?;
+
+		if owner != destination {
+			Self::do_transfer(id, owner.clone(), destination.clone(), amount)
+				.map_err(|err| err.error)?;
+		}
+
+		Self::set_allowance(id, owner, delegate, allowance)
+	}
+
+	/// Get the creator and the dossier of the asset `id`, if it has any.
+	pub fn token_dossier(id: T::AssetId) -> Option<(T::AccountId, TokenDossier)> {
+		if Metadata::<T>::contains_key(id) {
+			let metadata = Metadata::<T>::get(id);
+			Some((metadata.creator, metadata.dossier))
+		} else {
+			None
+		}
+	}
+
+	/// Move the name, symbol and decimals of the metadata stored before dossiers into a
+	/// dossier, with the owner of the asset as its creator.
+	fn migrate_metadata_to_dossiers() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V1 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut translated = 0u64;
+		Metadata::<T>::translate::<AssetMetadataV1<BalanceOf<T>>, _>(|id, old| {
+			translated += 1;
+			let creator = Asset::<T>::get(id)?.owner;
+			Some(AssetMetadata {
+				deposit: old.deposit,
+				creator,
+				dossier: TokenDossier {
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					..Default::default()
+				},
+			})
+		});
+		StorageVersion::<T>::put(Releases::V2);
+
+		T::DbWeight::get().reads_writes(1 + 2 * translated, 1 + translated)
+	}
+
+	fn ensure_dossier_fits(dossier: &TokenDossier) -> DispatchResult {
+		let limit = T::StringLimit::get() as usize;
+		ensure!(dossier.name.len() <= limit, Error::<T>::BadMetadata);
+		ensure!(dossier.symbol.len() <= limit, Error::<T>::BadMetadata);
+		ensure!(dossier.social_link.len() <= limit, Error::<T>::BadMetadata);
+		Ok(())
+	}
+
+	/// Set the dossier of the asset `id` owned by `origin`, adjusting the deposit reserved
+	/// for it. An empty dossier removes it.
+	fn do_set_dossier(origin: T::AccountId, id: T::AssetId, dossier: TokenDossier) -> DispatchResult {
+		Self::ensure_dossier_fits(&dossier)?;
+
+		let d = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+		ensure!(&origin == &d.owner, Error::<T>::NoPermission);
+
+		Metadata::<T>::try_mutate_exists(id, |metadata| {
+			let old_deposit = match metadata {
+				Some(m) => m.deposit,
+				None => Default::default()
+			};
+
+			// Metadata is being removed
+			if dossier.is_empty() {
+				T::Currency::unreserve(&origin, old_deposit);
+				*metadata = None;
+			} else {
+				let new_deposit = T::MetadataDepositPerByte::get()
+					.saturating_mul((dossier.bytes_used() as u32).into())
+					.saturating_add(T::MetadataDepositBase::get());
+
+				if new_deposit > old_deposit {
+					T::Currency::reserve(&origin, new_deposit - old_deposit)?;
+				} else {
+					T::Currency::unreserve(&origin, old_deposit - new_deposit);
+				}
+
+				let creator = metadata.take().map_or_else(|| origin.clone(), |m| m.creator);
+				*metadata = Some(AssetMetadata {
+					deposit: new_deposit,
+					creator,
+					dossier,
+				})
+			}
+
+			Ok(())
+		})
+	}
+
 	pub fn do_create(
 		id: T::AssetId,
 		owner: T::AccountId,
@@ -1393,26 +1767,7 @@ impl<T: Config> Fungible<T::AssetId, T::AccountId> for Module<T> {
 		to: &T::AccountId,
 		value: Self::Balance,
 	) -> DispatchResult {
-		let new_allowance = Self::allowances(token_id, (from, operator))
-			.checked_sub(&value)
-			.ok_or(Error::<T>::Overflow)?;
-
-		if from != to {
-			Self::do_transfer(
-				*token_id,
-				from.clone(),
-				to.clone(),
-				value,
-			)
-			.map(|_| ())
-			.map_err(|err| err.error)?;
-		}
-
-		<Allowances<T>>::mutate(token_id, (from, operator), |approved_balance| {
-			*approved_balance = new_allowance;
-		});
-
-		Ok(())
+		Self::do_transfer_approved(*token_id, from, operator, to, value)
 	}
 }
 
@@ -1422,13 +1777,24 @@ impl<T: Config> IssueAndBurn<T::AssetId, T::AccountId> for Module<T> {
 	}
 
 	fn create_new_asset(owner: &T::AccountId,
-						_dossier: TokenDossier,
+						dossier: TokenDossier,
 						max_zombies: u32,
 						min_balance: u32) -> Result<T::AssetId, DispatchError> {
+		Self::ensure_dossier_fits(&dossier)?;
 		let id = Self::max_asset_id() + 1u32.into();
 		Self::do_create(id, owner.clone(), owner.clone(), max_zombies, min_balance.into())
-			.map(|_| id)
-			.map_err(|err| err.error)
+			.map_err(|err| err.error)?;
+
+		// the creation deposit already pays for the dossier
+		if !dossier.is_empty() {
+			Metadata::<T>::insert(id, AssetMetadata {
+				deposit: Zero::zero(),
+				creator: owner.clone(),
+				dossier: dossier.clone(),
+			});
+			Self::deposit_event(Event::DossierSet(id, dossier));
+		}
+		Ok(id)
 	}
 
 	fn issue(
@@ -1662,6 +2028,7 @@ mod tests {
 		pub const StringLimit: u32 = 50;
 		pub const MetadataDepositBase: u64 = 1;
 		pub const MetadataDepositPerByte: u64 = 1;
+		pub const ApprovalDeposit: u64 = 1;
 	}
 
 	impl Config for Test {
@@ -1675,6 +2042,7 @@ mod tests {
 		type StringLimit = StringLimit;
 		type MetadataDepositBase = MetadataDepositBase;
 		type MetadataDepositPerByte = MetadataDepositPerByte;
+		type ApprovalDeposit = ApprovalDeposit;
 		type WeightInfo = ();
 	}
 
@@ -2080,4 +2448,151 @@ mod tests {
 			assert!(!Metadata::<Test>::contains_key(0));
 		});
 	}
+
+	#[test]
+	fn set_dossier_should_work() {
+		new_test_ext().execute_with(|| {
+			let dossier = TokenDossier {
+				name: vec![0u8; 10],
+				symbol: vec![0u8; 5],
+				decimals: 12,
+				logo_hash: Some([1u8; 32]),
+				social_link: vec![0u8; 10],
+			};
+			assert_noop!(Assets::set_dossier(Origin::signed(1), 0, dossier.clone()), Error::<Test>::Unknown);
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 1));
+			assert_noop!(Assets::set_dossier(Origin::signed(2), 0, dossier.clone()), Error::<Test>::NoPermission);
+			assert_noop!(
+				Assets::set_dossier(Origin::signed(1), 0, TokenDossier { social_link: vec![0u8; 100], ..dossier.clone() }),
+				Error::<Test>::BadMetadata,
+			);
+
+			// Successfully add the dossier and take deposit for the logo hash too
+			Balances::make_free_balance_be(&1, 100);
+			assert_ok!(Assets::set_dossier(Origin::signed(1), 0, dossier.clone()));
+			assert_eq!(Balances::reserved_balance(&1), 58);
+			assert_eq!(Assets::token_dossier(0), Some((1, dossier.clone())));
+
+			// Metadata updates keep the rest of the dossier
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 5], vec![0u8; 5], 18));
+			assert_eq!(Balances::reserved_balance(&1), 53);
+			let (_, updated) = Assets::token_dossier(0).unwrap();
+			assert_eq!(updated.decimals, 18);
+			assert_eq!(updated.logo_hash, Some([1u8; 32]));
+			assert_eq!(updated.social_link, vec![0u8; 10]);
+
+			// Clear the dossier
+			assert_ok!(Assets::set_dossier(Origin::signed(1), 0, Default::default()));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Assets::token_dossier(0), None);
+		});
+	}
+
+	#[test]
+	fn metadata_should_migrate_to_dossiers() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 1));
+			let old = AssetMetadataV1 { deposit: 7u64, name: b"Net".to_vec(), symbol: b"NET".to_vec(), decimals: 12 };
+			frame_support::storage::unhashed::put(&Metadata::<Test>::hashed_key_for(0), &old);
+
+			Assets::migrate_metadata_to_dossiers();
+
+			let metadata = Metadata::<Test>::get(0);
+			assert_eq!(metadata.deposit, 7);
+			assert_eq!(Assets::token_dossier(0), Some((1, TokenDossier {
+				name: b"Net".to_vec(),
+				symbol: b"NET".to_vec(),
+				decimals: 12,
+				..Default::default()
+			})));
+			assert_eq!(StorageVersion::<Test>::get(), Releases::V2);
+		});
+	}
+
+	#[test]
+	fn approvals_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Unknown);
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_noop!(
+				Assets::approve_transfer(Origin::signed(1), 0, 2, 50),
+				BalancesError::<Test, _>::InsufficientBalance,
+			);
+
+			Balances::make_free_balance_be(&1, 100);
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+			assert_eq!(Assets::allowances(0, (1, 2)), 50);
+			assert_eq!(Balances::reserved_balance(&1), 1);
+			assert_ok!(Assets::increase_allowance(Origin::signed(1), 0, 2, 20));
+			assert_eq!(Assets::allowances(0, (1, 2)), 70);
+			assert_ok!(Assets::decrease_allowance(Origin::signed(1), 0, 2, 30));
+			assert_eq!(Assets::allowances(0, (1, 2)), 40);
+			assert_noop!(Assets::increase_allowance(Origin::signed(1), 0, 2, u64::max_value()), Error::<Test>::Overflow);
+
+			// a new approval replaces the previous one
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+			assert_eq!(Assets::allowances(0, (1, 2)), 10);
+			assert_eq!(Balances::reserved_balance(&1), 1);
+
+			assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+			assert!(!Allowances::<Test>::contains_key(0, (1, 2)));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_noop!(Assets::cancel_approval(Origin::signed(1), 0, 2), Error::<Test>::NotAllowed);
+
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+			assert_eq!(Balances::reserved_balance(&1), 1);
+			assert_ok!(Assets::decrease_allowance(Origin::signed(1), 0, 2, 30));
+			assert!(!Allowances::<Test>::contains_key(0, (1, 2)));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+		});
+	}
+
+	#[test]
+	fn transfer_approved_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			Balances::make_free_balance_be(&1, 100);
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+			assert_eq!(Balances::reserved_balance(&1), 1);
+
+			assert_noop!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 51), Error::<Test>::NotAllowed);
+			assert_noop!(Assets::transfer_approved(Origin::signed(3), 0, 1, 3, 10), Error::<Test>::NotAllowed);
+
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 40));
+			assert_eq!(Assets::balance(0, 1), 60);
+			assert_eq!(Assets::balance(0, 3), 40);
+			assert_eq!(Assets::allowances(0, (1, 2)), 10);
+
+			// spending the whole approval removes it
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 2, 10));
+			assert_eq!(Assets::balance(0, 2), 10);
+			assert!(!Allowances::<Test>::contains_key(0, (1, 2)));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+
+			// the owner balance still bounds the transfer
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 100));
+			assert_noop!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 70), Error::<Test>::BalanceLow);
+		});
+	}
+
+	#[test]
+	fn create_new_asset_should_record_dossier() {
+		new_test_ext().execute_with(|| {
+			Balances::make_free_balance_be(&1, 100);
+			let id = <Assets as IssueAndBurn<u32, u64>>::create_new_asset(
+				&1,
+				TokenDossier::new_lp_token(),
+				10,
+				1,
+			).unwrap();
+			assert_eq!(Assets::token_dossier(id), Some((1, TokenDossier::new_lp_token())));
+			// only the creation deposit is reserved
+			assert_eq!(Balances::reserved_balance(&1), 11);
+
+			let id = <Assets as IssueAndBurn<u32, u64>>::create_new_asset(&1, Default::default(), 10, 1).unwrap();
+			assert_eq!(Assets::token_dossier(id), None);
+		});
+	}
 }
@@ -5,15 +5,19 @@ use frame_support::{
     decl_error, decl_event, decl_module, decl_storage,
     dispatch::DispatchResult,
     ensure,
-    traits::{EnsureOrigin, Get},
-    weights::{GetDispatchInfo, Pays},
+    storage::{unhashed, StoragePrefixedMap},
+    traits::{Currency, EnsureOrigin, Get, OnUnbalanced, ReservableCurrency},
+    weights::{GetDispatchInfo, Pays, Weight},
     Parameter,
 };
 
 use frame_system::{self as system, ensure_root, ensure_signed};
 use sp_core::U256;
-use sp_runtime::traits::{AccountIdConversion, Dispatchable};
-use sp_runtime::{ModuleId, RuntimeDebug};
+use sp_runtime::traits::{
+    AccountIdConversion, AtLeast32BitUnsigned, Dispatchable, One, SaturatedConversion, Saturating,
+    Zero,
+};
+use sp_runtime::{DispatchError, ModuleId, RuntimeDebug};
 use sp_std::{prelude::*, vec};
 
 use codec::{Decode, Encode, EncodeLike};
@@ -28,6 +32,13 @@ pub type ChainId = u8;
 pub type DepositNonce = u64;
 pub type ResourceId = [u8; 32];
 
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+/// Volumes of a chain and a resource after a transfer, None for the ones without a limit
+type Volumes<BlockNumber> = (Option<Volume<BlockNumber>>, Option<Volume<BlockNumber>>);
+
 /// Helper function to concatenate a chain ID and some bytes to produce a resource ID.
 /// The common format is (31 bytes unique ID + 1 byte chain ID).
 pub fn derive_resource_id(chain: u8, id: &[u8]) -> ResourceId {
@@ -86,6 +97,110 @@ impl<A: PartialEq, B: PartialOrd + Default> ProposalVotes<A, B> {
     }
 }
 
+/// Volume limits of a resource or a chain, in the units of the bridged amounts
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct TransferLimit<BlockNumber> {
+    /// Largest amount of a single transfer
+    pub max_per_transfer: U256,
+    /// Largest volume within any window of `window` blocks
+    pub max_per_window: U256,
+    pub window: BlockNumber,
+}
+
+/// Volume counted against a limit, drained at `max_per_window / window` per block
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct Volume<BlockNumber> {
+    pub amount: U256,
+    pub updated: BlockNumber,
+}
+
+impl<B: AtLeast32BitUnsigned + Copy> TransferLimit<B> {
+    /// Volume after adding `amount`, None if it exceeds the limit
+    fn add(&self, volume: &Volume<B>, amount: U256, now: B) -> Option<Volume<B>> {
+        if amount > self.max_per_transfer {
+            return None;
+        }
+        let released = if self.window.is_zero() {
+            volume.amount
+        } else {
+            let elapsed = now.saturating_sub(volume.updated).min(self.window);
+            self.max_per_window.saturating_mul(U256::from(elapsed.saturated_into::<u128>()))
+                / U256::from(self.window.saturated_into::<u128>())
+        };
+        let amount = volume.amount.saturating_sub(released).checked_add(amount)?;
+        if amount > self.max_per_window {
+            return None;
+        }
+        Some(Volume { amount, updated: now })
+    }
+}
+
+/// Resource and amount moved by a proposal, so that inbound transfers count against the limits.
+/// Proposals it returns None for are always queued for `LimitDelay` blocks.
+pub trait ProposalTransfer<Proposal> {
+    fn transfer_of(proposal: &Proposal) -> Option<(ResourceId, U256)>;
+}
+
+impl<Proposal> ProposalTransfer<Proposal> for () {
+    fn transfer_of(_: &Proposal) -> Option<(ResourceId, U256)> {
+        None
+    }
+}
+
+/// Notified of the relayers who voted for a proposal once it executed successfully
+pub trait OnProposalExecuted<AccountId> {
+    fn on_proposal_executed(voters: &[AccountId]);
+}
+
+impl<AccountId> OnProposalExecuted<AccountId> for () {
+    fn on_proposal_executed(_: &[AccountId]) {}
+}
+
+/// Notified of a relayer leaving the set, and whether it was slashed
+pub trait OnRelayerRemoved<AccountId> {
+    fn on_relayer_removed(relayer: &AccountId, slashed: bool);
+}
+
+impl<AccountId> OnRelayerRemoved<AccountId> for () {
+    fn on_relayer_removed(_: &AccountId, _: bool) {}
+}
+
+/// Bond and track record of a relayer
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct RelayerInfo<Balance> {
+    /// Amount reserved when the relayer was added
+    pub bond: Balance,
+    /// Votes against the finalized outcome of a proposal
+    pub strikes: u32,
+    /// Proposals created before the relayer joined
+    pub since: u32,
+    /// Proposals the relayer voted on
+    pub votes: u32,
+}
+
+impl<Balance> RelayerInfo<Balance> {
+    /// Proposals created since the relayer joined that it didn't vote on
+    pub fn missed_votes(&self, proposals: u32) -> u32 {
+        proposals.saturating_sub(self.since).saturating_sub(self.votes)
+    }
+}
+
+// A value placed in storage that represents the current version of the storage. This value
+// is used by the `on_runtime_upgrade` logic to determine whether we run storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+    /// Proposals created before the upgrade aren't indexed by expiry
+    V1,
+    /// Every proposal is pruned through `ExpiringProposals`
+    V2,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
 impl<AccountId, BlockNumber: Default> Default for ProposalVotes<AccountId, BlockNumber> {
     fn default() -> Self {
         Self {
@@ -108,10 +223,34 @@ pub trait Config: system::Config {
     type ChainId: Get<ChainId>;
 
     type ProposalLifetime: Get<Self::BlockNumber>;
+    /// Finds the amount moved by a proposal
+    type ProposalTransfer: ProposalTransfer<Self::Proposal>;
+    /// Blocks an approved proposal over the limits waits before it can be executed
+    type LimitDelay: Get<Self::BlockNumber>;
+    /// Handler rewarding the relayers of executed proposals
+    type OnProposalExecuted: OnProposalExecuted<Self::AccountId>;
+    /// Handler settling or forfeiting the rewards of removed relayers
+    type OnRelayerRemoved: OnRelayerRemoved<Self::AccountId>;
+    /// Currency relayer bonds are reserved in
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// Amount reserved from a relayer while it is in the set
+    type RelayerBond: Get<BalanceOf<Self>>;
+    /// Handler for slashed relayer bonds
+    type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+    /// Votes against finalized outcomes after which a relayer is slashed and removed
+    type MaxStrikes: Get<u32>;
+    /// Proposals a relayer may leave without a vote before it can be reported
+    type MaxMissedVotes: Get<u32>;
+    /// Expired proposals, or empty blocks of the expiry index, pruned per block
+    type MaxPrunedPerBlock: Get<u32>;
 }
 
 decl_event! {
-    pub enum Event<T> where <T as frame_system::Config>::AccountId {
+    pub enum Event<T> where
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+        Balance = BalanceOf<T>,
+    {
         /// Vote threshold has changed (new_threshold)
         RelayerThresholdChanged(u32),
         /// Chain now available for transfers (chain_id)
@@ -138,6 +277,24 @@ decl_event! {
         ProposalSucceeded(ChainId, DepositNonce),
         /// Execution of call failed
         ProposalFailed(ChainId, DepositNonce),
+        /// SemiFungibleTransfer is for relaying multi tokens (dest_id, nonce, resource_id, token_id, amount, recipient, metadata)
+        SemiFungibleTransfer(ChainId, DepositNonce, ResourceId, Vec<u8>, U256, Vec<u8>, Vec<u8>),
+        /// Transfers and proposal execution stopped
+        Paused,
+        /// Transfers and proposal execution resumed
+        Unpaused,
+        /// Volume limit of a resource changed
+        ResourceLimitSet(ResourceId, Option<TransferLimit<BlockNumber>>),
+        /// Volume limit of a chain changed
+        ChainLimitSet(ChainId, Option<TransferLimit<BlockNumber>>),
+        /// Approved proposal over the limits, executable from the block
+        ProposalQueued(ChainId, DepositNonce, BlockNumber),
+        /// Queued proposal dropped by the admin
+        ProposalVetoed(ChainId, DepositNonce),
+        /// Relayer voted against a finalized outcome (relayer, strikes)
+        RelayerStruck(AccountId, u32),
+        /// Relayer bond slashed and relayer removed from set
+        RelayerSlashed(AccountId, Balance),
     }
 }
 
@@ -173,6 +330,18 @@ decl_error! {
         ProposalAlreadyComplete,
         /// Lifetime of proposal has been exceeded
         ProposalExpired,
+        /// Bridge is paused
+        BridgePaused,
+        /// Transfer exceeds the volume limit of its resource or chain
+        TransferLimitExceeded,
+        /// No queued proposal with the ID was found
+        ProposalNotQueued,
+        /// Delay of the queued proposal hasn't passed yet
+        ProposalStillQueued,
+        /// Relayer hasn't missed enough votes to be removed
+        RelayerNotAbsent,
+        /// No proposals from before the upgrade are left to remove
+        NoLegacyVotes,
     }
 }
 
@@ -198,7 +367,60 @@ decl_storage! {
 
         /// Utilized by the bridge software to map resource IDs to actual methods
         pub Resources get(fn resources):
-            map hasher(opaque_blake2_256) ResourceId => Option<Vec<u8>>
+            map hasher(opaque_blake2_256) ResourceId => Option<Vec<u8>>;
+
+        /// Stops transfers and proposal execution when set
+        pub IsPaused get(fn is_paused): bool;
+
+        /// Volume limits per resource and per chain
+        pub ResourceLimits get(fn resource_limit):
+            map hasher(opaque_blake2_256) ResourceId => Option<TransferLimit<T::BlockNumber>>;
+        pub ChainLimits get(fn chain_limit):
+            map hasher(opaque_blake2_256) ChainId => Option<TransferLimit<T::BlockNumber>>;
+
+        /// Volume bridged in either direction, per resource and per chain
+        pub ResourceVolume get(fn resource_volume):
+            map hasher(opaque_blake2_256) ResourceId => Volume<T::BlockNumber>;
+        pub ChainVolume get(fn chain_volume):
+            map hasher(opaque_blake2_256) ChainId => Volume<T::BlockNumber>;
+
+        /// Approved proposals over the limits and the block they can be executed from
+        pub QueuedProposals get(fn queued_proposals):
+            double_map hasher(opaque_blake2_256) ChainId, hasher(opaque_blake2_256) (DepositNonce, T::Proposal)
+            => Option<T::BlockNumber>;
+
+        /// Bond and track record of each relayer
+        pub RelayerInfos get(fn relayer_info):
+            map hasher(blake2_128_concat) T::AccountId => Option<RelayerInfo<BalanceOf<T>>>;
+
+        /// Number of proposals ever created
+        pub ProposalCount get(fn proposal_count): u32;
+
+        /// Proposals by the block they expire at, to be pruned from `Votes`
+        ExpiringProposals:
+            double_map hasher(twox_64_concat) T::BlockNumber, hasher(blake2_128_concat) (ChainId, DepositNonce, T::Proposal)
+            => ();
+
+        /// Next block of `ExpiringProposals` to prune
+        PruneCursor: Option<T::BlockNumber>;
+
+        /// Deposit nonces above `PrunedUpTo` whose proposals were pruned, so they can't be proposed again
+        PrunedNonces:
+            double_map hasher(twox_64_concat) ChainId, hasher(twox_64_concat) DepositNonce => bool;
+
+        /// Every deposit nonce of a chain up to this one was pruned or closed by the admin
+        pub PrunedUpTo get(fn pruned_up_to): map hasher(twox_64_concat) ChainId => DepositNonce;
+
+        /// Proposals created before the upgrade expire at this block at the latest
+        LegacyExpiry: Option<T::BlockNumber>;
+
+        /// Last key of `Votes` visited while removing the proposals created before the upgrade
+        LegacyVotesCursor: Option<Vec<u8>>;
+
+        /// Storage version of the pallet.
+        ///
+        /// New networks start with last version.
+        StorageVersion build(|_| Releases::V2): Releases;
     }
 }
 
@@ -209,9 +431,27 @@ decl_module! {
         const ChainIdentity: ChainId = T::ChainId::get();
         const ProposalLifetime: T::BlockNumber = T::ProposalLifetime::get();
         const BridgeAccountId: T::AccountId = MODULE_ID.into_account();
+        const LimitDelay: T::BlockNumber = T::LimitDelay::get();
+        const RelayerBond: BalanceOf<T> = T::RelayerBond::get();
+        const MaxStrikes: u32 = T::MaxStrikes::get();
+        const MaxMissedVotes: u32 = T::MaxMissedVotes::get();
 
         fn deposit_event() = default;
 
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            Self::prune_expired(now).saturating_add(Self::sweep_legacy_votes(now))
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get() == Releases::V1 && <LegacyExpiry<T>>::get().is_none() {
+                let now = <frame_system::Module<T>>::block_number();
+                <LegacyExpiry<T>>::put(now + T::ProposalLifetime::get());
+                T::DbWeight::get().reads_writes(2, 1)
+            } else {
+                T::DbWeight::get().reads(2)
+            }
+        }
+
         /// Sets the vote threshold for proposals.
         ///
         /// This threshold is used to determine how many votes are required
@@ -251,6 +491,97 @@ decl_module! {
             Self::unregister_resource(id)
         }
 
+        /// Stops outbound transfers and the execution of proposals.
+        ///
+        /// # <weight>
+        /// - O(1) write
+        /// # </weight>
+        #[weight = 195_000_000]
+        pub fn pause(origin) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            <IsPaused>::put(true);
+            Self::deposit_event(RawEvent::Paused);
+            Ok(())
+        }
+
+        /// Resumes outbound transfers and the execution of proposals.
+        ///
+        /// # <weight>
+        /// - O(1) write
+        /// # </weight>
+        #[weight = 195_000_000]
+        pub fn unpause(origin) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            <IsPaused>::put(false);
+            Self::deposit_event(RawEvent::Unpaused);
+            Ok(())
+        }
+
+        /// Sets or clears the volume limit of a resource ID.
+        ///
+        /// # <weight>
+        /// - O(1) write
+        /// # </weight>
+        #[weight = 195_000_000]
+        pub fn set_resource_limit(origin, id: ResourceId, limit: Option<TransferLimit<T::BlockNumber>>) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            match &limit {
+                Some(limit) => <ResourceLimits<T>>::insert(id, limit),
+                None => <ResourceLimits<T>>::remove(id),
+            }
+            <ResourceVolume<T>>::remove(id);
+            Self::deposit_event(RawEvent::ResourceLimitSet(id, limit));
+            Ok(())
+        }
+
+        /// Sets or clears the volume limit of a chain ID.
+        ///
+        /// # <weight>
+        /// - O(1) write
+        /// # </weight>
+        #[weight = 195_000_000]
+        pub fn set_chain_limit(origin, id: ChainId, limit: Option<TransferLimit<T::BlockNumber>>) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            match &limit {
+                Some(limit) => <ChainLimits<T>>::insert(id, limit),
+                None => <ChainLimits<T>>::remove(id),
+            }
+            <ChainVolume<T>>::remove(id);
+            Self::deposit_event(RawEvent::ChainLimitSet(id, limit));
+            Ok(())
+        }
+
+        /// Drops a queued proposal before its delay has passed.
+        ///
+        /// # <weight>
+        /// - O(1) removal
+        /// # </weight>
+        #[weight = 195_000_000]
+        pub fn veto_proposal(origin, nonce: DepositNonce, src_id: ChainId, prop: Box<<T as Config>::Proposal>) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            ensure!(<QueuedProposals<T>>::contains_key(src_id, (nonce, prop.clone())), Error::<T>::ProposalNotQueued);
+            <QueuedProposals<T>>::remove(src_id, (nonce, prop));
+            Self::deposit_event(RawEvent::ProposalVetoed(src_id, nonce));
+            Ok(())
+        }
+
+        /// Executes a queued proposal once its delay has passed.
+        ///
+        /// # <weight>
+        /// - weight of proposed call
+        /// # </weight>
+        #[weight = (prop.get_dispatch_info().weight + 195_000_000, prop.get_dispatch_info().class, Pays::Yes)]
+        pub fn execute_queued(origin, nonce: DepositNonce, src_id: ChainId, prop: Box<<T as Config>::Proposal>) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(!Self::is_paused(), Error::<T>::BridgePaused);
+            let execute_at = <QueuedProposals<T>>::get(src_id, (nonce, prop.clone()))
+                .ok_or(Error::<T>::ProposalNotQueued)?;
+            ensure!(execute_at <= <frame_system::Module<T>>::block_number(), Error::<T>::ProposalStillQueued);
+
+            <QueuedProposals<T>>::remove(src_id, (nonce, prop.clone()));
+            Self::execute_proposal(src_id, nonce, prop)
+        }
+
         /// Enables a chain ID as a source or destination for a bridge transfer.
         ///
         /// # <weight>
@@ -262,7 +593,7 @@ decl_module! {
             Self::whitelist(id)
         }
 
-        /// Adds a new relayer to the relayer set.
+        /// Adds a new relayer to the relayer set, reserving `RelayerBond` from it.
         ///
         /// # <weight>
         /// - O(1) lookup and insert
@@ -273,7 +604,7 @@ decl_module! {
             Self::register_relayer(v)
         }
 
-        /// Removes an existing relayer from the set.
+        /// Removes an existing relayer from the set and returns its bond.
         ///
         /// # <weight>
         /// - O(1) lookup and removal
@@ -284,6 +615,49 @@ decl_module! {
             Self::unregister_relayer(v)
         }
 
+        /// Closes the deposit nonces of each chain up to the given ones, and removes the proposals
+        /// created before the upgrade from `Votes` in batches once they all expired.
+        ///
+        /// Those proposals aren't indexed by nonce, so the nonces have to cover every proposal
+        /// made before the upgrade for none of them to be proposed again. Calling it again only
+        /// raises the nonces.
+        ///
+        /// # <weight>
+        /// - O(chains) writes
+        /// # </weight>
+        #[weight = 195_000_000 + T::DbWeight::get().writes(nonces.len() as u64)]
+        pub fn sweep_legacy_votes(origin, nonces: Vec<(ChainId, DepositNonce)>) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            ensure!(
+                StorageVersion::get() == Releases::V1 && <LegacyExpiry<T>>::exists(),
+                Error::<T>::NoLegacyVotes
+            );
+            for (src_id, nonce) in nonces {
+                <PrunedUpTo>::mutate(src_id, |up_to| *up_to = (*up_to).max(nonce));
+            }
+            if <LegacyVotesCursor>::get().is_none() {
+                <LegacyVotesCursor>::put(<Votes<T>>::final_prefix().to_vec());
+            }
+            Ok(())
+        }
+
+        /// Slashes and removes a relayer that left more than `MaxMissedVotes` proposals
+        /// without a vote.
+        ///
+        /// # <weight>
+        /// - O(1) lookup and removal
+        /// # </weight>
+        #[weight = 195_000_000]
+        pub fn report_absent_relayer(origin, relayer: T::AccountId) -> DispatchResult {
+            ensure_signed(origin)?;
+            let info = Self::relayer_info(&relayer).ok_or(Error::<T>::RelayerInvalid)?;
+            ensure!(
+                info.missed_votes(Self::proposal_count()) > T::MaxMissedVotes::get(),
+                Error::<T>::RelayerNotAbsent
+            );
+            Self::slash_relayer(relayer)
+        }
+
         /// Commits a vote in favour of the provided proposal.
         ///
         /// If a proposal with the given nonce and source chain ID does not already exist, it will
@@ -295,6 +669,7 @@ decl_module! {
         #[weight = (call.get_dispatch_info().weight + 195_000_000, call.get_dispatch_info().class, Pays::Yes)]
         pub fn acknowledge_proposal(origin, nonce: DepositNonce, src_id: ChainId, r_id: ResourceId, call: Box<<T as Config>::Proposal>) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            ensure!(!Self::is_paused(), Error::<T>::BridgePaused);
             ensure!(Self::is_relayer(&who), Error::<T>::MustBeRelayer);
             ensure!(Self::chain_whitelisted(src_id), Error::<T>::ChainNotWhitelisted);
             ensure!(Self::resource_exists(r_id), Error::<T>::ResourceDoesNotExist);
@@ -328,6 +703,7 @@ decl_module! {
         #[weight = (prop.get_dispatch_info().weight + 195_000_000, prop.get_dispatch_info().class, Pays::Yes)]
         pub fn eval_vote_state(origin, nonce: DepositNonce, src_id: ChainId, prop: Box<<T as Config>::Proposal>) -> DispatchResult {
             ensure_signed(origin)?;
+            ensure!(!Self::is_paused(), Error::<T>::BridgePaused);
 
             Self::try_resolve_proposal(nonce, src_id, prop)
         }
@@ -365,6 +741,51 @@ impl<T: Config> Module<T> {
         return Self::chains(id) != None;
     }
 
+    /// Counts a transfer against the limits of its chain and resource, failing if either is exceeded
+    fn record_transfer(chain_id: ChainId, resource_id: ResourceId, amount: U256) -> DispatchResult {
+        let volumes = Self::check_transfer(chain_id, resource_id, amount)?;
+        Self::commit_transfer(chain_id, resource_id, volumes);
+        Ok(())
+    }
+
+    /// Computes the volumes of a chain and resource after a transfer, failing if either limit is exceeded
+    fn check_transfer(
+        chain_id: ChainId,
+        resource_id: ResourceId,
+        amount: U256,
+    ) -> Result<Volumes<T::BlockNumber>, DispatchError> {
+        let now = <frame_system::Module<T>>::block_number();
+        let chain_volume = match Self::chain_limit(chain_id) {
+            Some(limit) => Some(
+                limit.add(&Self::chain_volume(chain_id), amount, now)
+                    .ok_or(Error::<T>::TransferLimitExceeded)?
+            ),
+            None => None,
+        };
+        let resource_volume = match Self::resource_limit(resource_id) {
+            Some(limit) => Some(
+                limit.add(&Self::resource_volume(resource_id), amount, now)
+                    .ok_or(Error::<T>::TransferLimitExceeded)?
+            ),
+            None => None,
+        };
+        Ok((chain_volume, resource_volume))
+    }
+
+    /// Stores the volumes computed by `check_transfer`
+    fn commit_transfer(
+        chain_id: ChainId,
+        resource_id: ResourceId,
+        (chain_volume, resource_volume): Volumes<T::BlockNumber>,
+    ) {
+        if let Some(volume) = chain_volume {
+            <ChainVolume<T>>::insert(chain_id, volume);
+        }
+        if let Some(volume) = resource_volume {
+            <ResourceVolume<T>>::insert(resource_id, volume);
+        }
+    }
+
     /// Increments the deposit nonce for the specified chain ID
     fn bump_nonce(id: ChainId) -> DepositNonce {
         let nonce = Self::chains(id).unwrap_or_default() + 1;
@@ -414,6 +835,14 @@ impl<T: Config> Module<T> {
             !Self::is_relayer(&relayer),
             Error::<T>::RelayerAlreadyExists
         );
+        let bond = T::RelayerBond::get();
+        T::Currency::reserve(&relayer, bond)?;
+        <RelayerInfos<T>>::insert(&relayer, RelayerInfo {
+            bond,
+            strikes: 0,
+            since: Self::proposal_count(),
+            votes: 0,
+        });
         <Relayers<T>>::insert(&relayer, true);
         <RelayerCount>::mutate(|i| *i += 1);
 
@@ -424,12 +853,165 @@ impl<T: Config> Module<T> {
     /// Removes a relayer from the set
     pub fn unregister_relayer(relayer: T::AccountId) -> DispatchResult {
         ensure!(Self::is_relayer(&relayer), Error::<T>::RelayerInvalid);
+        if let Some(info) = <RelayerInfos<T>>::take(&relayer) {
+            T::Currency::unreserve(&relayer, info.bond);
+        }
         <Relayers<T>>::remove(&relayer);
         <RelayerCount>::mutate(|i| *i -= 1);
+        T::OnRelayerRemoved::on_relayer_removed(&relayer, false);
         Self::deposit_event(RawEvent::RelayerRemoved(relayer));
         Ok(())
     }
 
+    /// Slashes the bond of a relayer and removes it from the set, lowering the threshold to the
+    /// relayers left so that proposals can still pass
+    fn slash_relayer(relayer: T::AccountId) -> DispatchResult {
+        ensure!(Self::is_relayer(&relayer), Error::<T>::RelayerInvalid);
+        let bond = <RelayerInfos<T>>::take(&relayer).map(|info| info.bond).unwrap_or_else(Zero::zero);
+        let (imbalance, _) = T::Currency::slash_reserved(&relayer, bond);
+        T::Slash::on_unbalanced(imbalance);
+        <Relayers<T>>::remove(&relayer);
+        let count = <RelayerCount>::mutate(|i| {
+            *i -= 1;
+            *i
+        });
+        T::OnRelayerRemoved::on_relayer_removed(&relayer, true);
+        Self::deposit_event(RawEvent::RelayerSlashed(relayer, bond));
+        if count > 0 && count < Self::relayer_threshold() {
+            Self::set_relayer_threshold(count)?;
+        }
+        Ok(())
+    }
+
+    /// Gives a strike to each relayer who voted against the finalized outcome, slashing those
+    /// who reach `MaxStrikes`
+    fn strike_dissenters(dissenters: &[T::AccountId]) {
+        for relayer in dissenters {
+            let strikes = <RelayerInfos<T>>::mutate(relayer, |info| {
+                info.as_mut().map(|info| {
+                    info.strikes += 1;
+                    info.strikes
+                })
+            });
+            if let Some(strikes) = strikes {
+                Self::deposit_event(RawEvent::RelayerStruck(relayer.clone(), strikes));
+                if strikes >= T::MaxStrikes::get() {
+                    let _ = Self::slash_relayer(relayer.clone());
+                }
+            }
+        }
+    }
+
+    /// Removes the votes of expired proposals, scanning at most `MaxPrunedPerBlock` entries and blocks.
+    /// Proposals still queued are kept until they can be executed.
+    fn prune_expired(now: T::BlockNumber) -> Weight {
+        let mut budget = T::MaxPrunedPerBlock::get() as usize;
+        let mut cursor = <PruneCursor<T>>::get().unwrap_or(now);
+        let (mut reads, mut writes) = (1u64, 1u64);
+
+        while cursor <= now && budget > 0 {
+            let expired: Vec<_> = <ExpiringProposals<T>>::iter_prefix(cursor)
+                .take(budget)
+                .map(|(key, _)| key)
+                .collect();
+            budget -= expired.len();
+            reads += expired.len() as u64 * 2;
+            writes += expired.len() as u64 * 3;
+
+            for (src_id, nonce, prop) in expired {
+                <ExpiringProposals<T>>::remove(cursor, (src_id, nonce, prop.clone()));
+                match <QueuedProposals<T>>::get(src_id, (nonce, prop.clone())) {
+                    Some(execute_at) => {
+                        let expiry = execute_at.max(now + One::one());
+                        <ExpiringProposals<T>>::insert(expiry, (src_id, nonce, prop), ());
+                    }
+                    None => {
+                        <Votes<T>>::remove(src_id, (nonce, prop));
+                        let (r, w) = Self::mark_pruned(src_id, nonce);
+                        reads += r;
+                        writes += w;
+                    }
+                }
+            }
+
+            reads += 1;
+            if <ExpiringProposals<T>>::iter_prefix(cursor).next().is_some() {
+                break;
+            }
+            budget = budget.saturating_sub(1);
+            cursor += One::one();
+        }
+
+        <PruneCursor<T>>::put(cursor);
+        T::DbWeight::get().reads_writes(reads, writes)
+    }
+
+    /// Whether the proposals of a deposit nonce were pruned
+    pub fn is_pruned(src_id: ChainId, nonce: DepositNonce) -> bool {
+        nonce <= Self::pruned_up_to(src_id) || <PrunedNonces>::get(src_id, nonce)
+    }
+
+    /// Records a pruned nonce, folding the pruned nonces that follow `PrunedUpTo` into it.
+    /// Returns the reads and writes done.
+    fn mark_pruned(src_id: ChainId, nonce: DepositNonce) -> (u64, u64) {
+        let up_to = Self::pruned_up_to(src_id);
+        if nonce <= up_to {
+            return (1, 0);
+        }
+        if nonce != up_to + 1 {
+            <PrunedNonces>::insert(src_id, nonce, true);
+            return (1, 1);
+        }
+
+        let mut up_to = nonce;
+        let mut folded = 0u64;
+        while <PrunedNonces>::contains_key(src_id, up_to + 1) {
+            up_to += 1;
+            <PrunedNonces>::remove(src_id, up_to);
+            folded += 1;
+        }
+        <PrunedUpTo>::insert(src_id, up_to);
+        (2 + folded, 1 + folded)
+    }
+
+    /// Removes the proposals created before the upgrade once they all expired, visiting at most
+    /// `MaxPrunedPerBlock` entries of `Votes` per block
+    fn sweep_legacy_votes(now: T::BlockNumber) -> Weight {
+        let (expiry, mut key) = match (<LegacyExpiry<T>>::get(), <LegacyVotesCursor>::get()) {
+            (Some(expiry), Some(key)) if expiry < now => (expiry, key),
+            _ => return T::DbWeight::get().reads(2),
+        };
+        let prefix = <Votes<T>>::final_prefix();
+        let (mut visited, mut removed) = (0u64, 0u64);
+        let mut done = false;
+        while visited < T::MaxPrunedPerBlock::get() as u64 {
+            match sp_io::storage::next_key(&key).filter(|next| next.starts_with(&prefix)) {
+                Some(next) => {
+                    let votes = unhashed::get::<ProposalVotes<T::AccountId, T::BlockNumber>>(&next);
+                    if votes.map_or(false, |votes| votes.expiry <= expiry) {
+                        unhashed::kill(&next);
+                        removed += 1;
+                    }
+                    key = next;
+                    visited += 1;
+                }
+                None => {
+                    done = true;
+                    break;
+                }
+            }
+        }
+
+        if done {
+            <LegacyVotesCursor>::kill();
+            <LegacyExpiry<T>>::kill();
+            StorageVersion::put(Releases::V2);
+        } else {
+            <LegacyVotesCursor>::put(key);
+        }
+        T::DbWeight::get().reads_writes(2 * visited + 3, removed + 3)
+    }
+
     // *** Proposal voting and execution methods ***
 
     /// Commits a vote for a proposal. If the proposal doesn't exist it will be created.
@@ -441,12 +1023,13 @@ impl<T: Config> Module<T> {
         in_favour: bool,
     ) -> DispatchResult {
         let now = <frame_system::Module<T>>::block_number();
-        let mut votes = match <Votes<T>>::get(src_id, (nonce, prop.clone())) {
-            Some(v) => v,
+        let (mut votes, is_new) = match <Votes<T>>::get(src_id, (nonce, prop.clone())) {
+            Some(v) => (v, false),
             None => {
+                ensure!(!Self::is_pruned(src_id, nonce), Error::<T>::ProposalExpired);
                 let mut v = ProposalVotes::default();
                 v.expiry = now + T::ProposalLifetime::get();
-                v
+                (v, true)
             }
         };
 
@@ -463,6 +1046,19 @@ impl<T: Config> Module<T> {
             Self::deposit_event(RawEvent::VoteAgainst(src_id, nonce, who.clone()));
         }
 
+        if is_new {
+            <ProposalCount>::mutate(|i| *i += 1);
+            <ExpiringProposals<T>>::insert(votes.expiry, (src_id, nonce, *prop.clone()), ());
+        }
+        let proposals = Self::proposal_count();
+        <RelayerInfos<T>>::mutate(&who, |info| {
+            // relayers added before bonding was introduced start their record on their first vote
+            let info = info.get_or_insert_with(|| RelayerInfo {
+                since: proposals.saturating_sub(1),
+                ..Default::default()
+            });
+            info.votes += 1;
+        });
         <Votes<T>>::insert(src_id, (nonce, prop.clone()), votes.clone());
 
         Ok(())
@@ -483,8 +1079,14 @@ impl<T: Config> Module<T> {
             <Votes<T>>::insert(src_id, (nonce, prop.clone()), votes.clone());
 
             match status {
-                ProposalStatus::Approved => Self::finalize_execution(src_id, nonce, prop),
-                ProposalStatus::Rejected => Self::cancel_execution(src_id, nonce),
+                ProposalStatus::Approved => {
+                    Self::strike_dissenters(&votes.votes_against);
+                    Self::finalize_execution(src_id, nonce, prop)
+                }
+                ProposalStatus::Rejected => {
+                    Self::strike_dissenters(&votes.votes_for);
+                    Self::cancel_execution(src_id, nonce)
+                }
                 _ => Ok(()),
             }
         } else {
@@ -515,16 +1117,46 @@ impl<T: Config> Module<T> {
         Self::try_resolve_proposal(nonce, src_id, prop)
     }
 
-    /// Execute the proposal and signals the result as an event
+    /// Execute the proposal, or queue it if it exceeds the volume limits or moves an unknown amount
     fn finalize_execution(
         src_id: ChainId,
         nonce: DepositNonce,
         call: Box<T::Proposal>,
     ) -> DispatchResult {
         Self::deposit_event(RawEvent::ProposalApproved(src_id, nonce));
+        let checked = T::ProposalTransfer::transfer_of(&call).and_then(|(resource_id, amount)| {
+            Self::check_transfer(src_id, resource_id, amount).ok().map(|volumes| (resource_id, volumes))
+        });
+        match checked {
+            Some((resource_id, volumes)) => {
+                Self::execute_proposal(src_id, nonce, call)?;
+                Self::commit_transfer(src_id, resource_id, volumes);
+            }
+            None => Self::queue_proposal(src_id, nonce, call),
+        }
+        Ok(())
+    }
+
+    /// Delays the execution of a proposal so that the admin can veto it
+    fn queue_proposal(src_id: ChainId, nonce: DepositNonce, call: Box<T::Proposal>) {
+        let execute_at = <frame_system::Module<T>>::block_number() + T::LimitDelay::get();
+        <QueuedProposals<T>>::insert(src_id, (nonce, call), execute_at);
+        Self::deposit_event(RawEvent::ProposalQueued(src_id, nonce, execute_at));
+    }
+
+    /// Dispatches the proposal and signals the result as an event
+    fn execute_proposal(
+        src_id: ChainId,
+        nonce: DepositNonce,
+        call: Box<T::Proposal>,
+    ) -> DispatchResult {
+        let voters = <Votes<T>>::get(src_id, (nonce, call.clone())).map(|votes| votes.votes_for);
         call.dispatch(frame_system::RawOrigin::Signed(Self::account_id()).into())
             .map(|_| ())
             .map_err(|e| e.error)?;
+        if let Some(voters) = voters {
+            T::OnProposalExecuted::on_proposal_executed(&voters);
+        }
         Self::deposit_event(RawEvent::ProposalSucceeded(src_id, nonce));
         Ok(())
     }
@@ -542,10 +1174,12 @@ impl<T: Config> Module<T> {
         to: Vec<u8>,
         amount: U256,
     ) -> DispatchResult {
+        ensure!(!Self::is_paused(), Error::<T>::BridgePaused);
         ensure!(
             Self::chain_whitelisted(dest_id),
             Error::<T>::ChainNotWhitelisted
         );
+        Self::record_transfer(dest_id, resource_id, amount)?;
         let nonce = Self::bump_nonce(dest_id);
         Self::deposit_event(RawEvent::FungibleTransfer(
             dest_id,
@@ -565,10 +1199,12 @@ impl<T: Config> Module<T> {
         to: Vec<u8>,
         metadata: Vec<u8>,
     ) -> DispatchResult {
+        ensure!(!Self::is_paused(), Error::<T>::BridgePaused);
         ensure!(
             Self::chain_whitelisted(dest_id),
             Error::<T>::ChainNotWhitelisted
         );
+        Self::record_transfer(dest_id, resource_id, U256::one())?;
         let nonce = Self::bump_nonce(dest_id);
         Self::deposit_event(RawEvent::NonFungibleTransfer(
             dest_id,
@@ -581,12 +1217,41 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Initiates a transfer of some copies of a semi-fungible asset out of the chain. This should be called by another pallet.
+    pub fn transfer_semifungible(
+        dest_id: ChainId,
+        resource_id: ResourceId,
+        token_id: Vec<u8>,
+        amount: U256,
+        to: Vec<u8>,
+        metadata: Vec<u8>,
+    ) -> DispatchResult {
+        ensure!(!Self::is_paused(), Error::<T>::BridgePaused);
+        ensure!(
+            Self::chain_whitelisted(dest_id),
+            Error::<T>::ChainNotWhitelisted
+        );
+        Self::record_transfer(dest_id, resource_id, amount)?;
+        let nonce = Self::bump_nonce(dest_id);
+        Self::deposit_event(RawEvent::SemiFungibleTransfer(
+            dest_id,
+            nonce,
+            resource_id,
+            token_id,
+            amount,
+            to,
+            metadata,
+        ));
+        Ok(())
+    }
+
     /// Initiates a transfer of generic data out of the chain. This should be called by another pallet.
     pub fn transfer_generic(
         dest_id: ChainId,
         resource_id: ResourceId,
         metadata: Vec<u8>,
     ) -> DispatchResult {
+        ensure!(!Self::is_paused(), Error::<T>::BridgePaused);
         ensure!(
             Self::chain_whitelisted(dest_id),
             Error::<T>::ChainNotWhitelisted
@@ -5,6 +5,7 @@ use super::*;
 use frame_support::{assert_ok, ord_parameter_types, parameter_types, weights::Weight};
 use frame_system::{self as system};
 use sp_core::H256;
+use std::cell::RefCell;
 use sp_runtime::{
     testing::Header,
     traits::{AccountIdConversion, BlakeTwo256, Block as BlockT, IdentityLookup},
@@ -71,6 +72,41 @@ impl pallet_balances::Trait for Test {
 parameter_types! {
     pub const TestChainId: u8 = 5;
     pub const ProposalLifetime: u64 = 50;
+    pub const LimitDelay: u64 = 10;
+    pub const MaxStrikes: u32 = 2;
+    pub const MaxMissedVotes: u32 = 1;
+    pub const MaxPrunedPerBlock: u32 = 10;
+}
+
+thread_local! {
+    static RELAYER_BOND: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct RelayerBond;
+impl Get<u64> for RelayerBond {
+    fn get() -> u64 {
+        RELAYER_BOND.with(|v| *v.borrow())
+    }
+}
+
+pub fn set_relayer_bond(bond: u64) {
+    RELAYER_BOND.with(|v| *v.borrow_mut() = bond);
+}
+
+/// Resource balance transfer proposals are counted against
+pub const TRANSFER_RESOURCE: ResourceId = [7; 32];
+/// Remark proposals move nothing
+pub const REMARK_RESOURCE: ResourceId = [8; 32];
+
+pub struct MockProposalTransfer;
+impl ProposalTransfer<Call> for MockProposalTransfer {
+    fn transfer_of(proposal: &Call) -> Option<(ResourceId, U256)> {
+        match proposal {
+            Call::Balances(balances::Call::transfer(_, amount)) => Some((TRANSFER_RESOURCE, (*amount).into())),
+            Call::System(system::Call::remark(_)) => Some((REMARK_RESOURCE, U256::zero())),
+            _ => None,
+        }
+    }
 }
 
 impl Trait for Test {
@@ -79,6 +115,16 @@ impl Trait for Test {
     type Proposal = Call;
     type ChainId = TestChainId;
     type ProposalLifetime = ProposalLifetime;
+    type ProposalTransfer = MockProposalTransfer;
+    type LimitDelay = LimitDelay;
+    type OnProposalExecuted = ();
+    type OnRelayerRemoved = ();
+    type Currency = Balances;
+    type RelayerBond = RelayerBond;
+    type Slash = ();
+    type MaxStrikes = MaxStrikes;
+    type MaxMissedVotes = MaxMissedVotes;
+    type MaxPrunedPerBlock = MaxPrunedPerBlock;
 }
 
 pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
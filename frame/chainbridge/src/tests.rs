@@ -1,12 +1,17 @@
 #![cfg(test)]
 
 use super::mock::{
-    assert_events, new_test_ext, Balances, Bridge, Call, Event, Origin, ProposalLifetime, System,
-    Test, TestChainId, ENDOWED_BALANCE, RELAYER_A, RELAYER_B, RELAYER_C, TEST_THRESHOLD,
+    assert_events, balances, new_test_ext, set_relayer_bond, Balances, Bridge, Call, Event, LimitDelay, Origin,
+    ProposalLifetime, System, Test, TestChainId, ENDOWED_BALANCE, RELAYER_A, RELAYER_B, RELAYER_C, TEST_THRESHOLD,
+    TRANSFER_RESOURCE,
 };
 use super::*;
 use crate::mock::new_test_ext_initialized;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+    assert_err, assert_noop, assert_ok,
+    dispatch::DispatchError,
+    traits::{OnInitialize, OnRuntimeUpgrade},
+};
 
 #[test]
 fn derive_ids() {
@@ -315,6 +320,7 @@ fn create_sucessful_proposal() {
             Event::bridge(RawEvent::VoteFor(src_id, prop_id, RELAYER_A)),
             Event::bridge(RawEvent::VoteAgainst(src_id, prop_id, RELAYER_B)),
             Event::bridge(RawEvent::VoteFor(src_id, prop_id, RELAYER_C)),
+            Event::bridge(RawEvent::RelayerStruck(RELAYER_B, 1)),
             Event::bridge(RawEvent::ProposalApproved(src_id, prop_id)),
             Event::bridge(RawEvent::ProposalSucceeded(src_id, prop_id)),
         ]);
@@ -391,6 +397,7 @@ fn create_unsucessful_proposal() {
             Event::bridge(RawEvent::VoteFor(src_id, prop_id, RELAYER_A)),
             Event::bridge(RawEvent::VoteAgainst(src_id, prop_id, RELAYER_B)),
             Event::bridge(RawEvent::VoteAgainst(src_id, prop_id, RELAYER_C)),
+            Event::bridge(RawEvent::RelayerStruck(RELAYER_A, 1)),
             Event::bridge(RawEvent::ProposalRejected(src_id, prop_id)),
         ]);
     })
@@ -532,3 +539,421 @@ fn proposal_expires() {
         ))]);
     })
 }
+
+fn limit(max_per_transfer: u64, max_per_window: u64, window: u64) -> TransferLimit<u64> {
+    TransferLimit {
+        max_per_transfer: max_per_transfer.into(),
+        max_per_window: max_per_window.into(),
+        window,
+    }
+}
+
+#[test]
+fn pause_blocks_transfers_and_execution() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"remark");
+
+    new_test_ext_initialized(src_id, r_id, b"System.remark".to_vec()).execute_with(|| {
+        let proposal = make_proposal(vec![10]);
+
+        assert_noop!(Bridge::pause(Origin::signed(RELAYER_A)), DispatchError::BadOrigin);
+        assert_ok!(Bridge::pause(Origin::root()));
+        assert!(Bridge::is_paused());
+
+        assert_noop!(
+            Bridge::transfer_fungible(src_id, r_id, vec![], 10.into()),
+            Error::<Test>::BridgePaused
+        );
+        assert_noop!(
+            Bridge::transfer_nonfungible(src_id, r_id, vec![], vec![], vec![]),
+            Error::<Test>::BridgePaused
+        );
+        assert_noop!(
+            Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), 1, src_id, r_id, Box::new(proposal.clone())),
+            Error::<Test>::BridgePaused
+        );
+        assert_noop!(
+            Bridge::eval_vote_state(Origin::signed(RELAYER_A), 1, src_id, Box::new(proposal.clone())),
+            Error::<Test>::BridgePaused
+        );
+
+        assert_ok!(Bridge::unpause(Origin::root()));
+        assert_ok!(Bridge::transfer_fungible(src_id, r_id, vec![], 10.into()));
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), 1, src_id, r_id, Box::new(proposal)));
+
+        assert_events(vec![
+            Event::bridge(RawEvent::Unpaused),
+            Event::bridge(RawEvent::FungibleTransfer(src_id, 1, r_id, 10.into(), vec![])),
+            Event::bridge(RawEvent::VoteFor(src_id, 1, RELAYER_A)),
+        ]);
+    })
+}
+
+#[test]
+fn transfer_limits() {
+    new_test_ext().execute_with(|| {
+        let dest_id = 2;
+        let resource_id = [1; 32];
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_id));
+
+        assert_ok!(Bridge::set_resource_limit(Origin::root(), resource_id, Some(limit(60, 100, 10))));
+        assert_noop!(
+            Bridge::transfer_fungible(dest_id, resource_id, vec![], 61.into()),
+            Error::<Test>::TransferLimitExceeded
+        );
+        assert_ok!(Bridge::transfer_fungible(dest_id, resource_id, vec![], 60.into()));
+        assert_noop!(
+            Bridge::transfer_fungible(dest_id, resource_id, vec![], 41.into()),
+            Error::<Test>::TransferLimitExceeded
+        );
+
+        // half a window later half of the volume is available again
+        System::set_block_number(6);
+        assert_ok!(Bridge::transfer_fungible(dest_id, resource_id, vec![], 60.into()));
+        assert_eq!(Bridge::resource_volume(resource_id), Volume { amount: 70.into(), updated: 6 });
+
+        // the chain limit applies to every resource
+        assert_ok!(Bridge::set_chain_limit(Origin::root(), dest_id, Some(limit(1_000, 1, 10))));
+        assert_ok!(Bridge::transfer_nonfungible(dest_id, [2; 32], vec![], vec![], vec![]));
+        assert_noop!(
+            Bridge::transfer_nonfungible(dest_id, [2; 32], vec![], vec![], vec![]),
+            Error::<Test>::TransferLimitExceeded
+        );
+
+        assert_ok!(Bridge::set_chain_limit(Origin::root(), dest_id, None));
+        assert_ok!(Bridge::transfer_nonfungible(dest_id, [2; 32], vec![], vec![], vec![]));
+    })
+}
+
+#[test]
+fn proposal_over_limit_is_queued() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"transfer");
+
+    new_test_ext_initialized(src_id, r_id, b"Balances.transfer".to_vec()).execute_with(|| {
+        let prop_id = 1;
+        let proposal = Call::Balances(balances::Call::transfer(RELAYER_A, 10));
+        assert_ok!(Bridge::set_resource_limit(Origin::root(), TRANSFER_RESOURCE, Some(limit(5, 100, 10))));
+
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, Box::new(proposal.clone())));
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, Box::new(proposal.clone())));
+
+        let execute_at = 1 + LimitDelay::get();
+        assert_eq!(Bridge::queued_proposals(src_id, (prop_id, proposal.clone())), Some(execute_at));
+        assert_eq!(Balances::free_balance(RELAYER_A), 0);
+        assert_events(vec![
+            Event::bridge(RawEvent::ProposalApproved(src_id, prop_id)),
+            Event::bridge(RawEvent::ProposalQueued(src_id, prop_id, execute_at)),
+        ]);
+
+        assert_noop!(
+            Bridge::execute_queued(Origin::signed(RELAYER_C), prop_id, src_id, Box::new(proposal.clone())),
+            Error::<Test>::ProposalStillQueued
+        );
+        System::set_block_number(execute_at);
+        assert_ok!(Bridge::execute_queued(Origin::signed(RELAYER_C), prop_id, src_id, Box::new(proposal.clone())));
+        assert_eq!(Balances::free_balance(RELAYER_A), 10);
+        assert_eq!(Bridge::queued_proposals(src_id, (prop_id, proposal.clone())), None);
+        assert_noop!(
+            Bridge::execute_queued(Origin::signed(RELAYER_C), prop_id, src_id, Box::new(proposal)),
+            Error::<Test>::ProposalNotQueued
+        );
+    })
+}
+
+#[test]
+fn veto_queued_proposal() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"transfer");
+
+    new_test_ext_initialized(src_id, r_id, b"Balances.transfer".to_vec()).execute_with(|| {
+        let prop_id = 1;
+        let proposal = Call::Balances(balances::Call::transfer(RELAYER_A, 10));
+        assert_ok!(Bridge::set_chain_limit(Origin::root(), src_id, Some(limit(5, 100, 10))));
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, Box::new(proposal.clone())));
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, Box::new(proposal.clone())));
+
+        assert_noop!(
+            Bridge::veto_proposal(Origin::signed(RELAYER_A), prop_id, src_id, Box::new(proposal.clone())),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Bridge::veto_proposal(Origin::root(), prop_id, src_id, Box::new(proposal.clone())));
+        assert_events(vec![Event::bridge(RawEvent::ProposalVetoed(src_id, prop_id))]);
+
+        System::set_block_number(1 + LimitDelay::get());
+        assert_noop!(
+            Bridge::execute_queued(Origin::signed(RELAYER_C), prop_id, src_id, Box::new(proposal)),
+            Error::<Test>::ProposalNotQueued
+        );
+        assert_eq!(Balances::free_balance(RELAYER_A), 0);
+    })
+}
+
+#[test]
+fn proposal_of_unknown_amount_is_queued() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"transfer");
+
+    new_test_ext_initialized(src_id, r_id, b"Balances.transfer_keep_alive".to_vec()).execute_with(|| {
+        let prop_id = 1;
+        let proposal = Call::Balances(balances::Call::transfer_keep_alive(RELAYER_A, 10));
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, Box::new(proposal.clone())));
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, Box::new(proposal.clone())));
+
+        let execute_at = 1 + LimitDelay::get();
+        assert_eq!(Bridge::queued_proposals(src_id, (prop_id, proposal)), Some(execute_at));
+        assert_eq!(Balances::free_balance(RELAYER_A), 0);
+    })
+}
+
+#[test]
+fn failed_proposal_is_not_counted() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"transfer");
+
+    new_test_ext_initialized(src_id, r_id, b"Balances.transfer".to_vec()).execute_with(|| {
+        let prop_id = 1;
+        let proposal = Call::Balances(balances::Call::transfer(RELAYER_A, ENDOWED_BALANCE + 1));
+        let max = ENDOWED_BALANCE * 2;
+        assert_ok!(Bridge::set_resource_limit(Origin::root(), TRANSFER_RESOURCE, Some(limit(max, max, 10))));
+
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, Box::new(proposal.clone())));
+        assert_err!(
+            Bridge::acknowledge_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, Box::new(proposal)),
+            balances::Error::<Test, _>::InsufficientBalance
+        );
+        assert_eq!(Bridge::resource_volume(TRANSFER_RESOURCE), Volume::default());
+    })
+}
+
+#[test]
+fn relayer_bond() {
+    new_test_ext().execute_with(|| {
+        set_relayer_bond(10);
+        assert_noop!(
+            Bridge::add_relayer(Origin::root(), RELAYER_A),
+            balances::Error::<Test, _>::InsufficientBalance
+        );
+
+        Balances::make_free_balance_be(&RELAYER_A, 100);
+        assert_ok!(Bridge::add_relayer(Origin::root(), RELAYER_A));
+        assert_eq!(Balances::reserved_balance(RELAYER_A), 10);
+        assert_eq!(Bridge::relayer_info(RELAYER_A).unwrap().bond, 10);
+
+        // the bond registered with the relayer is returned even if the parameter changed
+        set_relayer_bond(20);
+        assert_ok!(Bridge::remove_relayer(Origin::root(), RELAYER_A));
+        assert_eq!(Balances::reserved_balance(RELAYER_A), 0);
+        assert_eq!(Balances::free_balance(RELAYER_A), 100);
+        assert_eq!(Bridge::relayer_info(RELAYER_A), None);
+    })
+}
+
+#[test]
+fn prune_expired_proposals() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"remark");
+
+    new_test_ext_initialized(src_id, r_id, b"System.remark".to_vec()).execute_with(|| {
+        let prop_id = 1;
+        let proposal = make_proposal(vec![10]);
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, Box::new(proposal.clone())));
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, Box::new(proposal.clone())));
+
+        Bridge::on_initialize(ProposalLifetime::get());
+        assert!(Bridge::votes(src_id, (prop_id, proposal.clone())).is_some());
+
+        System::set_block_number(ProposalLifetime::get() + 1);
+        Bridge::on_initialize(ProposalLifetime::get() + 1);
+        assert_eq!(Bridge::votes(src_id, (prop_id, proposal.clone())), None);
+        assert!(Bridge::is_pruned(src_id, prop_id));
+
+        // the pruned nonce can't be proposed again
+        assert_noop!(
+            Bridge::acknowledge_proposal(Origin::signed(RELAYER_C), prop_id, src_id, r_id, Box::new(proposal)),
+            Error::<Test>::ProposalExpired
+        );
+    })
+}
+
+#[test]
+fn pruned_nonces_are_folded() {
+    new_test_ext().execute_with(|| {
+        let src_id = 1;
+        Bridge::mark_pruned(src_id, 2);
+        assert!(Bridge::is_pruned(src_id, 2));
+        assert!(!Bridge::is_pruned(src_id, 1));
+        assert_eq!(Bridge::pruned_up_to(src_id), 0);
+
+        Bridge::mark_pruned(src_id, 4);
+        Bridge::mark_pruned(src_id, 1);
+        assert_eq!(Bridge::pruned_up_to(src_id), 2);
+        assert!(!PrunedNonces::contains_key(src_id, 2u64));
+        assert!(!Bridge::is_pruned(src_id, 3));
+
+        Bridge::mark_pruned(src_id, 3);
+        assert_eq!(Bridge::pruned_up_to(src_id), 4);
+        assert!(!PrunedNonces::contains_key(src_id, 4u64));
+        assert!(Bridge::is_pruned(src_id, 4));
+        assert!(!Bridge::is_pruned(2, 1));
+    })
+}
+
+#[test]
+fn legacy_votes_are_swept() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"remark");
+
+    new_test_ext_initialized(src_id, r_id, b"System.remark".to_vec()).execute_with(|| {
+        // proposals created before they were indexed by expiry
+        StorageVersion::put(Releases::V1);
+        for nonce in 1..12u64 {
+            let votes = ProposalVotes {
+                votes_for: vec![RELAYER_A, RELAYER_B],
+                votes_against: vec![],
+                status: ProposalStatus::Approved,
+                expiry: 30,
+            };
+            Votes::<Test>::insert(src_id, (nonce, make_proposal(vec![nonce as u8])), votes);
+        }
+        Bridge::on_runtime_upgrade();
+        let legacy_expiry = 1 + ProposalLifetime::get();
+        assert_eq!(LegacyExpiry::<Test>::get(), Some(legacy_expiry));
+
+        System::set_block_number(10);
+        let proposal = make_proposal(vec![20]);
+        assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), 20, src_id, r_id, Box::new(proposal.clone())));
+
+        assert_noop!(
+            Bridge::sweep_legacy_votes(Origin::signed(RELAYER_A), vec![(src_id, 11)]),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Bridge::sweep_legacy_votes(Origin::root(), vec![(src_id, 11)]));
+        assert!(Bridge::is_pruned(src_id, 11));
+
+        // nothing is removed before every legacy proposal expired
+        Bridge::on_initialize(legacy_expiry);
+        assert!(Bridge::votes(src_id, (1u64, make_proposal(vec![1]))).is_some());
+
+        System::set_block_number(legacy_expiry + 1);
+        Bridge::on_initialize(legacy_expiry + 1);
+        assert!(LegacyVotesCursor::get().is_some());
+        System::set_block_number(legacy_expiry + 2);
+        Bridge::on_initialize(legacy_expiry + 2);
+        assert_eq!(LegacyVotesCursor::get(), None);
+        assert_eq!(StorageVersion::get(), Releases::V2);
+
+        for nonce in 1..12u64 {
+            assert_eq!(Bridge::votes(src_id, (nonce, make_proposal(vec![nonce as u8]))), None);
+        }
+        assert!(Bridge::votes(src_id, (20u64, proposal)).is_some());
+        assert_noop!(
+            Bridge::acknowledge_proposal(Origin::signed(RELAYER_C), 5, src_id, r_id, Box::new(make_proposal(vec![5]))),
+            Error::<Test>::ProposalExpired
+        );
+        assert_noop!(
+            Bridge::sweep_legacy_votes(Origin::root(), vec![(src_id, 12)]),
+            Error::<Test>::NoLegacyVotes
+        );
+    })
+}
+
+/// Whitelists `src_id` and adds the three relayers with a bond of 10
+fn setup_bonded_relayers(src_id: ChainId, r_id: ResourceId) {
+    set_relayer_bond(10);
+    assert_ok!(Bridge::set_threshold(Origin::root(), TEST_THRESHOLD));
+    assert_ok!(Bridge::whitelist_chain(Origin::root(), src_id));
+    assert_ok!(Bridge::set_resource(Origin::root(), r_id, b"System.remark".to_vec()));
+    for relayer in vec![RELAYER_A, RELAYER_B, RELAYER_C] {
+        Balances::make_free_balance_be(&relayer, 100);
+        assert_ok!(Bridge::add_relayer(Origin::root(), relayer));
+    }
+}
+
+#[test]
+fn relayer_slashed_for_dissent() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"remark");
+
+    new_test_ext().execute_with(|| {
+        setup_bonded_relayers(src_id, r_id);
+
+        for prop_id in 1..3 {
+            let proposal = Box::new(make_proposal(vec![prop_id as u8]));
+            assert_ok!(Bridge::reject_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, proposal.clone()));
+            assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, proposal.clone()));
+            assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_C), prop_id, src_id, r_id, proposal));
+        }
+
+        assert!(!Bridge::is_relayer(&RELAYER_B));
+        assert_eq!(Bridge::relayer_count(), 2);
+        assert_eq!(Balances::reserved_balance(RELAYER_B), 0);
+        assert_eq!(Balances::free_balance(RELAYER_B), 90);
+        assert_eq!(Bridge::relayer_info(RELAYER_A).unwrap().strikes, 0);
+        assert_events(vec![
+            Event::bridge(RawEvent::VoteFor(src_id, 2, RELAYER_C)),
+            Event::bridge(RawEvent::RelayerStruck(RELAYER_B, 2)),
+            Event::bridge(RawEvent::RelayerSlashed(RELAYER_B, 10)),
+            Event::bridge(RawEvent::ProposalApproved(src_id, 2)),
+            Event::bridge(RawEvent::ProposalSucceeded(src_id, 2)),
+        ]);
+    })
+}
+
+#[test]
+fn report_absent_relayer() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"remark");
+
+    new_test_ext().execute_with(|| {
+        setup_bonded_relayers(src_id, r_id);
+
+        for prop_id in 1..3 {
+            let proposal = Box::new(make_proposal(vec![prop_id as u8]));
+            assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, proposal.clone()));
+            assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, proposal));
+
+            if prop_id == 1 {
+                assert_noop!(
+                    Bridge::report_absent_relayer(Origin::signed(RELAYER_A), RELAYER_C),
+                    Error::<Test>::RelayerNotAbsent
+                );
+            }
+        }
+
+        assert_noop!(
+            Bridge::report_absent_relayer(Origin::signed(RELAYER_C), RELAYER_A),
+            Error::<Test>::RelayerNotAbsent
+        );
+        assert_ok!(Bridge::report_absent_relayer(Origin::signed(RELAYER_A), RELAYER_C));
+        assert!(!Bridge::is_relayer(&RELAYER_C));
+        assert_eq!(Balances::free_balance(RELAYER_C), 90);
+        assert_eq!(Balances::reserved_balance(RELAYER_C), 0);
+        assert_events(vec![Event::bridge(RawEvent::RelayerSlashed(RELAYER_C, 10))]);
+    })
+}
+
+#[test]
+fn slashing_lowers_threshold() {
+    let src_id = 1;
+    let r_id = derive_resource_id(src_id, b"remark");
+
+    new_test_ext().execute_with(|| {
+        setup_bonded_relayers(src_id, r_id);
+        assert_ok!(Bridge::set_threshold(Origin::root(), 3));
+
+        for prop_id in 1..3 {
+            let proposal = Box::new(make_proposal(vec![prop_id as u8]));
+            assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, proposal.clone()));
+            assert_ok!(Bridge::acknowledge_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, proposal));
+        }
+
+        assert_ok!(Bridge::report_absent_relayer(Origin::signed(RELAYER_A), RELAYER_C));
+        assert_eq!(Bridge::relayer_count(), 2);
+        assert_eq!(Bridge::relayer_threshold(), 2);
+        assert_events(vec![
+            Event::bridge(RawEvent::RelayerSlashed(RELAYER_C, 10)),
+            Event::bridge(RawEvent::RelayerThresholdChanged(2)),
+        ]);
+    })
+}
@@ -0,0 +1,23 @@
+//! Runtime API definition for fee quotes and relayer rewards of the social bridge module.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to quote bridge transfer fees.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use chainbridge::{ChainId, ResourceId};
+
+sp_api::decl_runtime_apis! {
+	/// The API to quote transfer fees of the social bridge module.
+	pub trait SocialBridgeApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Native fee charged for a transfer to `dest_id` under `resource_id`.
+		fn transfer_fee(dest_id: ChainId, resource_id: ResourceId) -> Balance;
+
+		/// Share of the collected fees `relayer` can claim.
+		fn relayer_reward(relayer: AccountId) -> Balance;
+	}
+}
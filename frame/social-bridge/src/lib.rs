@@ -1,14 +1,39 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+//! Bridges the native currency, social tokens, nfts and multi tokens through chainbridge, under
+//! the resource ids of the registry kept by this pallet.
+//!
+//! ## Resource ids of the previous runtime
+//!
+//! The previous runtime sent social tokens out under `derive_resource_id(dest_id, b"NET" ++ id)`
+//! without registering them anywhere. Encoding the id into an empty buffer panicked, so those
+//! transfers were reverted and no social token is locked in the bridge account under such an id.
+//! There is nothing to migrate. Should a remote chain still send a social token under an id of its
+//! own, the admin origin brings it back by removing the current resource of the token with
+//! `remove_resource` and calling `register_resource(id, ResourceKind::SocialToken(token_id,
+//! BridgeMode::Lock), local_decimals, remote_decimals)`. The locked tokens are then released to
+//! the recipients of `transfer_social_tokens_in`.
+
 use chainbridge as bridge;
-use frame_support::traits::{Currency, EnsureOrigin, ExistenceRequirement::AllowDeath, Get};
+use frame_support::traits::{Currency, EnsureOrigin, ExistenceRequirement::AllowDeath, IsSubType};
 use frame_support::{
-    decl_error, decl_event, decl_module, dispatch::DispatchResult, ensure,
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure, transactional,
+    traits::Get,
+    weights::Weight,
 };
 use frame_system::{self as system, ensure_signed};
+use codec::{Decode, Encode};
+use pallet_assets::IssueAndBurn;
+use pallet_social_nft::CollectionId;
 use sp_arithmetic::traits::SaturatedConversion;
 use sp_core::U256;
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    RuntimeDebug,
+};
 use sp_std::prelude::*;
 
 mod mock;
@@ -19,8 +44,111 @@ type ResourceId = bridge::ResourceId;
 type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// How a social token crosses the bridge
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum BridgeMode {
+    /// Native to this chain, locked in the bridge account when sent out and released when it comes back
+    Lock,
+    /// Native to the other chain, burned when sent out and minted when it comes in
+    Mint,
+}
+
+/// Decimals above this would overflow the conversion factor
+const MAX_DECIMALS: u8 = 38;
+
+/// Scale of `RewardPerPoint`, so that small fees shared by many points aren't rounded away
+const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// A value placed in storage that represents the current version of the storage. This value
+// is used by the `on_runtime_upgrade` logic to determine whether we run storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+    /// Resource ids are constants of the runtime
+    V1,
+    /// Resource ids are kept in the registry
+    V2,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
+/// What a resource id stands for on this chain
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum ResourceKind<AssetId> {
+    /// The native currency, locked in the bridge account
+    Native,
+    /// A social token and how it crosses the bridge
+    SocialToken(AssetId, BridgeMode),
+    /// Nfts of a collection, or nfts outside any collection
+    Nft(Option<CollectionId>),
+    /// Tokens of pallet_social_multi_token
+    MultiToken,
+    /// Arbitrary data, executed on arrival by the named call
+    Generic(Vec<u8>),
+}
+
+impl<AssetId> ResourceKind<AssetId> {
+    /// Call executing inbound transfers of this kind, as registered with the bridge
+    pub fn method(&self) -> Vec<u8> {
+        match self {
+            ResourceKind::Native => b"SocialBridge.transfer".to_vec(),
+            ResourceKind::SocialToken(..) => b"SocialBridge.transfer_social_tokens_in".to_vec(),
+            ResourceKind::Nft(_) => b"SocialBridge.mint_erc721".to_vec(),
+            ResourceKind::MultiToken => b"SocialBridge.mint_erc1155".to_vec(),
+            ResourceKind::Generic(method) => method.clone(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct ResourceInfo<AssetId> {
+    pub kind: ResourceKind<AssetId>,
+    /// Decimals of amounts on this chain
+    pub local_decimals: u8,
+    /// Decimals of amounts on the other chain
+    pub remote_decimals: u8,
+    /// Disabled resources reject transfers in both directions
+    pub enabled: bool,
+}
+
+impl<AssetId> ResourceInfo<AssetId> {
+    /// Converts a local amount to the decimals of the other chain, None if precision would be lost
+    pub fn to_remote(&self, amount: u128) -> Option<U256> {
+        scale(U256::from(amount), self.local_decimals, self.remote_decimals)
+    }
+
+    /// Converts an amount of the other chain to local decimals, None if precision would be lost
+    pub fn to_local(&self, amount: U256) -> Option<u128> {
+        let amount = scale(amount, self.remote_decimals, self.local_decimals)?;
+        if amount > U256::from(u128::max_value()) {
+            return None;
+        }
+        Some(amount.low_u128())
+    }
+}
+
+fn scale(amount: U256, from: u8, to: u8) -> Option<U256> {
+    if to >= from {
+        amount.checked_mul(U256::exp10((to - from) as usize))
+    } else {
+        let factor = U256::exp10((from - to) as usize);
+        if (amount % factor).is_zero() {
+            Some(amount / factor)
+        } else {
+            None
+        }
+    }
+}
+
 pub trait Config:
-    system::Config + bridge::Config + pallet_assets::Config + pallet_social_nft::Config
+    system::Config
+    + bridge::Config
+    + pallet_assets::Config
+    + pallet_social_nft::Config
+    + pallet_social_multi_token::Config
 {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
     /// Specifies the origin check provided by the bridge for calls that can only be called by the bridge pallet
@@ -28,86 +156,164 @@ pub trait Config:
 
     /// The currency mechanism.
     type Currency: Currency<Self::AccountId>;
-
-    /// Ids can be defined by the runtime and passed in, perhaps from blake2b_128 hashes.
-    type HashId: Get<ResourceId>;
-    type NativeTokenId: Get<ResourceId>;
-    type Erc721Id: Get<ResourceId>;
 }
 
 decl_event! {
     pub enum Event<T> where
         <T as frame_system::Config>::Hash,
+        <T as frame_system::Config>::AccountId,
+        Balance = BalanceOf<T>,
+        Kind = ResourceKind<<T as pallet_assets::Config>::AssetId>,
     {
         Remark(Hash),
+        /// Resource id registered for a kind of transfer
+        ResourceRegistered(ResourceId, Kind),
+        /// Resource id removed from the registry
+        ResourceRemoved(ResourceId),
+        /// Resource id enabled or disabled
+        ResourceStatusChanged(ResourceId, bool),
+        /// Fee of transfers to a chain under a resource id changed
+        FeeSet(bridge::ChainId, ResourceId, Balance),
+        /// Transfer fee paid into the bridge account
+        FeePaid(AccountId, bridge::ChainId, ResourceId, Balance),
+        /// Relayer claimed its share of the collected fees
+        RelayerRewardClaimed(AccountId, Balance),
     }
 }
 
 decl_error! {
     pub enum Error for Module<T: Config>{
         InvalidTransfer,
+        /// Resource id or kind of transfer not in the registry
+        ResourceNotRegistered,
+        /// Resource id or kind of transfer already registered
+        AlreadyRegistered,
+        /// Resource id is registered for another kind of transfer
+        ResourceMismatch,
+        /// Resource id is disabled
+        ResourceDisabled,
+        /// Decimals larger than supported
+        InvalidDecimals,
+        /// Amount can't be converted between the decimals of both chains
+        InvalidAmount,
+        /// Nothing to claim for the relayer
+        NoRelayerReward,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as SocialBridge {
+        /// Kind, decimals and status of every bridged resource id
+        pub Resources get(fn resources):
+            map hasher(blake2_128_concat) ResourceId => Option<ResourceInfo<T::AssetId>>;
+        /// Resource id a kind of transfer goes out under
+        pub KindResources get(fn kind_resource):
+            map hasher(blake2_128_concat) ResourceKind<T::AssetId> => Option<ResourceId>;
+        /// Native fee of transfers to a chain under a resource id
+        pub Fees get(fn transfer_fee):
+            double_map hasher(opaque_blake2_256) bridge::ChainId, hasher(blake2_128_concat) ResourceId => BalanceOf<T>;
+        /// Fees held by the bridge account which relayers haven't claimed yet
+        pub FeePot get(fn fee_pot): BalanceOf<T>;
+        /// Votes of a relayer on executed proposals since its last claim
+        pub RelayerPoints get(fn relayer_points): map hasher(blake2_128_concat) T::AccountId => u32;
+        pub TotalRelayerPoints get(fn total_relayer_points): u32;
+        /// Fees earned by a single point since genesis, scaled by `REWARD_PRECISION`
+        pub RewardPerPoint get(fn reward_per_point): u128;
+        /// `RewardPerPoint` times the points of a relayer when they last changed
+        pub RewardDebt get(fn reward_debt): map hasher(blake2_128_concat) T::AccountId => u128;
+        /// Fees earned by the points a relayer had before they last changed
+        pub PendingRelayerReward get(fn pending_relayer_reward):
+            map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+        /// Fees collected while no relayer had points, shared by the next votes
+        pub UnallocatedFees get(fn unallocated_fees): BalanceOf<T>;
+
+        /// Storage version of the pallet.
+        ///
+        /// New networks start with last version.
+        StorageVersion build(|_| Releases::V2): Releases;
     }
 }
 
 decl_module! {
     pub struct Module<T: Config> for enum Call where origin: T::Origin {
-        const HashId: ResourceId = T::HashId::get();
-        const NativeTokenId: ResourceId = T::NativeTokenId::get();
-
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get() == Releases::V1 {
+                Self::register_runtime_resources()
+            } else {
+                T::DbWeight::get().reads(1)
+            }
+        }
+
         /// Transfers an arbitrary hash to a (whitelisted) destination chain.
         #[weight = 195_000_000]
+        #[transactional]
         pub fn transfer_hash(origin, hash: T::Hash, dest_id: bridge::ChainId) -> DispatchResult {
-            ensure_signed(origin)?;
+            let source = ensure_signed(origin)?;
 
-            let resource_id = T::HashId::get();
+            let (resource_id, _) = Self::outbound_resource(&ResourceKind::Generic(b"SocialBridge.remark".to_vec()))?;
+            Self::charge_fee(&source, dest_id, resource_id)?;
             let metadata: Vec<u8> = hash.as_ref().to_vec();
             <bridge::Module<T>>::transfer_generic(dest_id, resource_id, metadata)
         }
 
         /// Transfers some amount of the native token to some recipient on a (whitelisted) destination chain.
         #[weight = 195_000_000]
+        #[transactional]
         pub fn transfer_native(origin, amount: BalanceOf<T>, recipient: Vec<u8>, dest_id: bridge::ChainId) -> DispatchResult {
             let source = ensure_signed(origin)?;
             ensure!(<bridge::Module<T>>::chain_whitelisted(dest_id), Error::<T>::InvalidTransfer);
+            let (resource_id, info) = Self::outbound_resource(&ResourceKind::Native)?;
+            let remote_amount = info.to_remote(amount.saturated_into::<u128>()).ok_or(Error::<T>::InvalidAmount)?;
+            Self::charge_fee(&source, dest_id, resource_id)?;
             let bridge_id = <bridge::Module<T>>::account_id();
             <T as Config>::Currency::transfer(&source, &bridge_id, amount, AllowDeath)?;
 
-            let resource_id = T::NativeTokenId::get();
-            <bridge::Module<T>>::transfer_fungible(dest_id, resource_id, recipient, U256::from(amount.saturated_into::<u128>()))
+            <bridge::Module<T>>::transfer_fungible(dest_id, resource_id, recipient, remote_amount)
         }
 
         /// Transfers some amount of the social tokens to some recipient on a (whitelisted) destination chain.
         #[weight = 195_000_000]
+        #[transactional]
         pub fn transfer_social_tokens(
             origin,
             #[compact] token_id: T::AssetId,
-            #[compact] value: T::Balance,
+            #[compact] value: <T as pallet_assets::Config>::Balance,
             recipient: Vec<u8>,
             dest_id: bridge::ChainId
         ) -> DispatchResult {
             let source = ensure_signed(origin)?;
             ensure!(<bridge::Module<T>>::chain_whitelisted(dest_id), Error::<T>::InvalidTransfer);
-            <pallet_assets::Module<T>>::validate_asset_id(token_id)?;
-            let bridge_id = <bridge::Module<T>>::account_id();
-            <pallet_assets::Module<T>>::do_transfer(token_id, source, bridge_id, value)
-                .map(|_| ())
-                .map_err(|err| err.error)?;
+            let (resource_id, mode) = Self::social_token_resource(token_id).ok_or(Error::<T>::ResourceNotRegistered)?;
+            let info = Self::enabled_resource(resource_id)?;
+            let remote_amount = info.to_remote(value.saturated_into::<u128>()).ok_or(Error::<T>::InvalidAmount)?;
+            Self::charge_fee(&source, dest_id, resource_id)?;
+            match mode {
+                BridgeMode::Lock => {
+                    let bridge_id = <bridge::Module<T>>::account_id();
+                    <pallet_assets::Module<T>>::do_transfer(token_id, source, bridge_id, value)
+                        .map(|_| ())
+                        .map_err(|err| err.error)?;
+                }
+                BridgeMode::Mint => <pallet_assets::Module<T> as IssueAndBurn<_, _>>::burn(&token_id, &source, value)?,
+            }
 
-            let resource_id = bridge::derive_resource_id(dest_id, &Self::social_token_acronym(token_id));
-            <bridge::Module<T>>::transfer_fungible(dest_id, resource_id, recipient, U256::from(value.saturated_into::<u128>()))
+            <bridge::Module<T>>::transfer_fungible(dest_id, resource_id, recipient, remote_amount)
         }
 
         /// Transfer a non-fungible token (pallet_social_nft) to a (whitelisted) destination chain.
         #[weight = 195_000_000]
+        #[transactional]
         pub fn transfer_erc721(origin, recipient: Vec<u8>, token_id: U256, dest_id: bridge::ChainId) -> DispatchResult {
             let source = ensure_signed(origin)?;
             ensure!(<bridge::Module<T>>::chain_whitelisted(dest_id), Error::<T>::InvalidTransfer);
             match <pallet_social_nft::Module<T>>::tokens(&token_id) {
                 Some(token) => {
+                    let collection = <pallet_social_nft::Module<T>>::token_collection(&token_id);
+                    let (resource_id, _) = Self::outbound_resource(&ResourceKind::Nft(collection))?;
+                    Self::charge_fee(&source, dest_id, resource_id)?;
                     <pallet_social_nft::Module<T>>::burn_token(source, token_id)?;
-                    let resource_id = T::Erc721Id::get();
                     let tid: &mut [u8] = &mut[0; 32];
                     token_id.to_big_endian(tid);
                     <bridge::Module<T>>::transfer_nonfungible(dest_id, resource_id, tid.to_vec(), recipient, token.metadata)
@@ -116,6 +322,126 @@ decl_module! {
             }
         }
 
+        /// Transfer some copies of a multi token (pallet_social_multi_token) to a (whitelisted) destination chain.
+        #[weight = 195_000_000]
+        #[transactional]
+        pub fn transfer_erc1155(
+            origin,
+            recipient: Vec<u8>,
+            token_id: U256,
+            amount: <T as pallet_social_multi_token::Config>::Balance,
+            dest_id: bridge::ChainId
+        ) -> DispatchResult {
+            let source = ensure_signed(origin)?;
+            ensure!(<bridge::Module<T>>::chain_whitelisted(dest_id), Error::<T>::InvalidTransfer);
+            match <pallet_social_multi_token::Module<T>>::tokens(&token_id) {
+                Some(token) => {
+                    let (resource_id, _) = Self::outbound_resource(&ResourceKind::MultiToken)?;
+                    Self::charge_fee(&source, dest_id, resource_id)?;
+                    <pallet_social_multi_token::Module<T>>::burn_tokens(source, token_id, amount)?;
+                    let tid: &mut [u8] = &mut[0; 32];
+                    token_id.to_big_endian(tid);
+                    <bridge::Module<T>>::transfer_semifungible(
+                        dest_id,
+                        resource_id,
+                        tid.to_vec(),
+                        U256::from(amount.saturated_into::<u128>()),
+                        recipient,
+                        token.uri,
+                    )
+                }
+                None => Err(Error::<T>::InvalidTransfer.into())
+            }
+        }
+
+        /// Register a resource id for a kind of transfer, along with the call executing it on arrival
+        #[weight = 195_000_000]
+        pub fn register_resource(
+            origin,
+            id: ResourceId,
+            kind: ResourceKind<T::AssetId>,
+            local_decimals: u8,
+            remote_decimals: u8
+        ) -> DispatchResult {
+            <T as bridge::Config>::AdminOrigin::ensure_origin(origin)?;
+            ensure!(local_decimals <= MAX_DECIMALS && remote_decimals <= MAX_DECIMALS, Error::<T>::InvalidDecimals);
+            ensure!(!Resources::<T>::contains_key(id), Error::<T>::AlreadyRegistered);
+            match &kind {
+                ResourceKind::SocialToken(token_id, _) => {
+                    <pallet_assets::Module<T>>::validate_asset_id(*token_id)?;
+                    ensure!(Self::social_token_resource(*token_id).is_none(), Error::<T>::AlreadyRegistered);
+                }
+                ResourceKind::Nft(Some(collection_id)) => ensure!(
+                    <pallet_social_nft::Module<T>>::collections(collection_id).is_some(),
+                    pallet_social_nft::Error::<T>::CollectionNotExists
+                ),
+                _ => {}
+            }
+            ensure!(!KindResources::<T>::contains_key(&kind), Error::<T>::AlreadyRegistered);
+
+            <bridge::Module<T>>::register_resource(id, kind.method())?;
+            KindResources::<T>::insert(&kind, id);
+            Resources::<T>::insert(id, ResourceInfo { kind: kind.clone(), local_decimals, remote_decimals, enabled: true });
+            Self::deposit_event(RawEvent::ResourceRegistered(id, kind));
+            Ok(())
+        }
+
+        /// Enable or disable transfers of a resource id in both directions
+        #[weight = 195_000_000]
+        pub fn set_resource_status(origin, id: ResourceId, enabled: bool) -> DispatchResult {
+            <T as bridge::Config>::AdminOrigin::ensure_origin(origin)?;
+            Resources::<T>::try_mutate(id, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::ResourceNotRegistered)?;
+                info.enabled = enabled;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::ResourceStatusChanged(id, enabled));
+            Ok(())
+        }
+
+        /// Remove a resource id from the registry and the bridge
+        #[weight = 195_000_000]
+        pub fn remove_resource(origin, id: ResourceId) -> DispatchResult {
+            <T as bridge::Config>::AdminOrigin::ensure_origin(origin)?;
+            let info = Resources::<T>::take(id).ok_or(Error::<T>::ResourceNotRegistered)?;
+
+            KindResources::<T>::remove(&info.kind);
+            <bridge::Module<T>>::unregister_resource(id)?;
+            Self::deposit_event(RawEvent::ResourceRemoved(id));
+            Ok(())
+        }
+
+        /// Set the native fee of transfers to a chain under a resource id, zero removes it
+        #[weight = 195_000_000]
+        pub fn set_fee(origin, dest_id: bridge::ChainId, resource_id: ResourceId, fee: BalanceOf<T>) -> DispatchResult {
+            <T as bridge::Config>::AdminOrigin::ensure_origin(origin)?;
+            if fee.is_zero() {
+                Fees::<T>::remove(dest_id, resource_id);
+            } else {
+                Fees::<T>::insert(dest_id, resource_id, fee);
+            }
+            Self::deposit_event(RawEvent::FeeSet(dest_id, resource_id, fee));
+            Ok(())
+        }
+
+        /// Pay out the share of the collected fees earned by voting on executed proposals
+        #[weight = 195_000_000]
+        pub fn claim_relayer_reward(origin) -> DispatchResult {
+            let relayer = ensure_signed(origin)?;
+            let reward = Self::relayer_reward(&relayer);
+            ensure!(!reward.is_zero(), Error::<T>::NoRelayerReward);
+
+            let bridge_id = <bridge::Module<T>>::account_id();
+            <T as Config>::Currency::transfer(&bridge_id, &relayer, reward, AllowDeath)?;
+            let points = RelayerPoints::<T>::take(&relayer);
+            RewardDebt::<T>::remove(&relayer);
+            PendingRelayerReward::<T>::remove(&relayer);
+            TotalRelayerPoints::mutate(|total| *total = total.saturating_sub(points));
+            FeePot::<T>::mutate(|pot| *pot = pot.saturating_sub(reward));
+            Self::deposit_event(RawEvent::RelayerRewardClaimed(relayer, reward));
+            Ok(())
+        }
+
         //
         // Executable calls. These can be triggered by a bridge transfer initiated on another chain
         //
@@ -124,7 +450,36 @@ decl_module! {
         #[weight = 195_000_000]
         pub fn transfer(origin, to: T::AccountId, amount: BalanceOf<T>, r_id: ResourceId) -> DispatchResult {
             let source = T::BridgeOrigin::ensure_origin(origin)?;
-            <T as Config>::Currency::transfer(&source, &to, amount, AllowDeath)?;
+            let info = Self::enabled_resource(r_id)?;
+            ensure!(info.kind == ResourceKind::Native, Error::<T>::ResourceMismatch);
+            let amount = info.to_local(U256::from(amount.saturated_into::<u128>())).ok_or(Error::<T>::InvalidAmount)?;
+            <T as Config>::Currency::transfer(&source, &to, amount.saturated_into(), AllowDeath)?;
+            Ok(())
+        }
+
+        /// Releases or mints the social token registered under `r_id`, depending on its bridge mode
+        #[weight = 195_000_000]
+        pub fn transfer_social_tokens_in(
+            origin,
+            to: T::AccountId,
+            amount: <T as pallet_assets::Config>::Balance,
+            r_id: ResourceId
+        ) -> DispatchResult {
+            let source = T::BridgeOrigin::ensure_origin(origin)?;
+            let info = Self::enabled_resource(r_id)?;
+            let (token_id, mode) = match info.kind {
+                ResourceKind::SocialToken(token_id, mode) => (token_id, mode),
+                _ => return Err(Error::<T>::ResourceMismatch.into()),
+            };
+            let amount = info.to_local(U256::from(amount.saturated_into::<u128>()))
+                .ok_or(Error::<T>::InvalidAmount)?
+                .saturated_into();
+            match mode {
+                BridgeMode::Lock => <pallet_assets::Module<T>>::do_transfer(token_id, source, to, amount)
+                    .map(|_| ())
+                    .map_err(|err| err.error)?,
+                BridgeMode::Mint => <pallet_assets::Module<T> as IssueAndBurn<_, _>>::issue(&token_id, &to, amount)?,
+            }
             Ok(())
         }
 
@@ -132,27 +487,186 @@ decl_module! {
         #[weight = 195_000_000]
         pub fn remark(origin, hash: T::Hash, r_id: ResourceId) -> DispatchResult {
             T::BridgeOrigin::ensure_origin(origin)?;
+            let info = Self::enabled_resource(r_id)?;
+            ensure!(matches!(info.kind, ResourceKind::Generic(_)), Error::<T>::ResourceMismatch);
             Self::deposit_event(RawEvent::Remark(hash));
             Ok(())
         }
 
-        /// Allows the bridge to issue new pallet_social_nft tokens
+        /// Allows the bridge to issue new pallet_social_nft tokens, keeping the collection of the resource
         #[weight = 195_000_000]
-        pub fn mint_erc721(origin, recipient: T::AccountId, id: U256, metadata: Vec<u8>, royalty: T::Balance, r_id: ResourceId) -> DispatchResult {
+        pub fn mint_erc721(
+            origin,
+            recipient: T::AccountId,
+            id: U256,
+            metadata: Vec<u8>,
+            royalty: <T as pallet_assets::Config>::Balance,
+            r_id: ResourceId
+        ) -> DispatchResult {
             T::BridgeOrigin::ensure_origin(origin)?;
-            <pallet_social_nft::Module<T>>::mint_token(recipient, id, metadata, royalty)?;
+            let collection = match Self::enabled_resource(r_id)?.kind {
+                ResourceKind::Nft(collection) => collection,
+                _ => return Err(Error::<T>::ResourceMismatch.into()),
+            };
+            <pallet_social_nft::Module<T>>::mint_bridged_token(recipient, id, metadata, royalty, collection)
+        }
+
+        /// Allows the bridge to issue copies of pallet_social_multi_token tokens, creating the token id if needed
+        #[weight = 195_000_000]
+        pub fn mint_erc1155(
+            origin,
+            recipient: T::AccountId,
+            id: U256,
+            amount: <T as pallet_social_multi_token::Config>::Balance,
+            uri: Vec<u8>,
+            r_id: ResourceId
+        ) -> DispatchResult {
+            let source = T::BridgeOrigin::ensure_origin(origin)?;
+            ensure!(Self::enabled_resource(r_id)?.kind == ResourceKind::MultiToken, Error::<T>::ResourceMismatch);
+            if <pallet_social_multi_token::Module<T>>::tokens(&id).is_none() {
+                <pallet_social_multi_token::Module<T>>::create_bridged_token(source, id, uri)?;
+            }
+            <pallet_social_multi_token::Module<T>>::mint_tokens(recipient, id, amount)?;
             Ok(())
         }
     }
 }
 
 impl<T: Config> Module<T> {
-    fn social_token_acronym(token_id: T::AssetId) -> Vec<u8> {
-        let mut acronym = b"NET".to_vec();
-        let postfix = U256::from(token_id.saturated_into::<u128>());
-        let mut buf = vec![];
-        postfix.to_big_endian(&mut buf);
-        acronym.append(&mut buf);
-        acronym
+    /// Registered and enabled resource id
+    pub fn enabled_resource(id: ResourceId) -> Result<ResourceInfo<T::AssetId>, DispatchError> {
+        let info = Self::resources(id).ok_or(Error::<T>::ResourceNotRegistered)?;
+        ensure!(info.enabled, Error::<T>::ResourceDisabled);
+        Ok(info)
+    }
+
+    /// Resource id a social token is bridged under and its bridge mode
+    pub fn social_token_resource(token_id: T::AssetId) -> Option<(ResourceId, BridgeMode)> {
+        [BridgeMode::Lock, BridgeMode::Mint].iter().find_map(|mode| {
+            Self::kind_resource(ResourceKind::SocialToken(token_id, *mode)).map(|id| (id, *mode))
+        })
+    }
+
+    /// Fees a relayer can claim, collected after its votes
+    pub fn relayer_reward(relayer: &T::AccountId) -> BalanceOf<T> {
+        Self::pending_relayer_reward(relayer).saturating_add(Self::accrued_reward(relayer))
+    }
+
+    /// Fees earned by the current points of a relayer since they last changed
+    fn accrued_reward(relayer: &T::AccountId) -> BalanceOf<T> {
+        let earned = (Self::relayer_points(relayer) as u128).saturating_mul(Self::reward_per_point());
+        (earned.saturating_sub(Self::reward_debt(relayer)) / REWARD_PRECISION).saturated_into()
+    }
+
+    /// Shares fees among the current points, or sets them aside until a relayer has points
+    fn distribute_fees(amount: BalanceOf<T>) {
+        let total = Self::total_relayer_points();
+        if total == 0 {
+            UnallocatedFees::<T>::mutate(|fees| *fees = fees.saturating_add(amount));
+            return;
+        }
+        let per_point = amount.saturated_into::<u128>().saturating_mul(REWARD_PRECISION) / total as u128;
+        RewardPerPoint::mutate(|reward| *reward = reward.saturating_add(per_point));
+    }
+
+    /// Register the resource ids the previous runtime had as constants, derived from the chain id
+    fn register_runtime_resources() -> Weight {
+        let chain_id = <T as bridge::Config>::ChainId::get();
+        let resources: [(&[u8], ResourceKind<T::AssetId>); 4] = [
+            (b"NET", ResourceKind::Native),
+            (b"NET_HASH", ResourceKind::Generic(b"SocialBridge.remark".to_vec())),
+            (b"NET_NFT", ResourceKind::Nft(None)),
+            (b"NET_MULTI", ResourceKind::MultiToken),
+        ];
+        let mut registered = 0u64;
+        for (name, kind) in resources.iter() {
+            let id = bridge::derive_resource_id(chain_id, name);
+            if Resources::<T>::contains_key(id) || KindResources::<T>::contains_key(kind) {
+                continue;
+            }
+            // the previous runtime bridged amounts one to one
+            let _ = <bridge::Module<T>>::register_resource(id, kind.method());
+            KindResources::<T>::insert(kind, id);
+            Resources::<T>::insert(id, ResourceInfo { kind: kind.clone(), local_decimals: 0, remote_decimals: 0, enabled: true });
+            registered += 1;
+        }
+        StorageVersion::put(Releases::V2);
+        T::DbWeight::get().reads_writes(1 + 2 * resources.len() as u64, 1 + 3 * registered)
+    }
+
+    /// Moves the fee of a transfer from the sender into the bridge account
+    fn charge_fee(who: &T::AccountId, dest_id: bridge::ChainId, resource_id: ResourceId) -> DispatchResult {
+        let fee = Self::transfer_fee(dest_id, resource_id);
+        if fee.is_zero() {
+            return Ok(());
+        }
+        <T as Config>::Currency::transfer(who, &<bridge::Module<T>>::account_id(), fee, AllowDeath)?;
+        FeePot::<T>::mutate(|pot| *pot = pot.saturating_add(fee));
+        Self::distribute_fees(fee);
+        Self::deposit_event(RawEvent::FeePaid(who.clone(), dest_id, resource_id, fee));
+        Ok(())
+    }
+
+    /// Registered and enabled resource a kind of transfer goes out under
+    fn outbound_resource(kind: &ResourceKind<T::AssetId>) -> Result<(ResourceId, ResourceInfo<T::AssetId>), DispatchError> {
+        let id = Self::kind_resource(kind).ok_or(Error::<T>::ResourceNotRegistered)?;
+        Ok((id, Self::enabled_resource(id)?))
+    }
+}
+
+/// Inbound transfers of this pallet count against the volume limits of the bridge,
+/// any other proposal is queued by the bridge
+impl<T: Config, P: IsSubType<Call<T>>> bridge::ProposalTransfer<P> for Module<T> {
+    fn transfer_of(proposal: &P) -> Option<(ResourceId, U256)> {
+        match proposal.is_sub_type()? {
+            Call::transfer(_, amount, r_id) => Some((*r_id, U256::from((*amount).saturated_into::<u128>()))),
+            Call::transfer_social_tokens_in(_, amount, r_id) => Some((*r_id, U256::from((*amount).saturated_into::<u128>()))),
+            Call::mint_erc721(_, _, _, _, r_id) => Some((*r_id, U256::one())),
+            Call::mint_erc1155(_, _, amount, _, r_id) => Some((*r_id, U256::from((*amount).saturated_into::<u128>()))),
+            Call::remark(_, r_id) => Some((*r_id, U256::zero())),
+            _ => None,
+        }
+    }
+}
+
+/// Every vote for an executed proposal earns the relayer a share of the fees collected afterwards
+impl<T: Config> bridge::OnProposalExecuted<T::AccountId> for Module<T> {
+    fn on_proposal_executed(voters: &[T::AccountId]) {
+        let reward_per_point = Self::reward_per_point();
+        for relayer in voters {
+            let accrued = Self::accrued_reward(relayer);
+            if !accrued.is_zero() {
+                PendingRelayerReward::<T>::mutate(relayer, |pending| *pending = pending.saturating_add(accrued));
+            }
+            let points = RelayerPoints::<T>::mutate(relayer, |points| {
+                *points = points.saturating_add(1);
+                *points
+            });
+            RewardDebt::<T>::insert(relayer, (points as u128).saturating_mul(reward_per_point));
+        }
+        TotalRelayerPoints::mutate(|total| *total = total.saturating_add(voters.len() as u32));
+
+        let unallocated = UnallocatedFees::<T>::take();
+        if !unallocated.is_zero() {
+            Self::distribute_fees(unallocated);
+        }
+    }
+}
+
+/// A removed relayer stops earning, and a slashed one forfeits its unclaimed fees to the others
+impl<T: Config> bridge::OnRelayerRemoved<T::AccountId> for Module<T> {
+    fn on_relayer_removed(relayer: &T::AccountId, slashed: bool) {
+        let reward = Self::relayer_reward(relayer);
+        let points = RelayerPoints::<T>::take(relayer);
+        RewardDebt::<T>::remove(relayer);
+        TotalRelayerPoints::mutate(|total| *total = total.saturating_sub(points));
+        if slashed {
+            PendingRelayerReward::<T>::remove(relayer);
+            if !reward.is_zero() {
+                Self::distribute_fees(reward);
+            }
+        } else if !reward.is_zero() {
+            PendingRelayerReward::<T>::insert(relayer, reward);
+        }
     }
 }
@@ -1,6 +1,6 @@
 #![cfg(test)]
 
-use frame_support::{ord_parameter_types, parameter_types, weights::Weight};
+use frame_support::{assert_ok, ord_parameter_types, parameter_types, weights::Weight};
 use frame_system::{self as system, EnsureRoot};
 use sp_core::{hashing::blake2_128, H256};
 use sp_runtime::{
@@ -9,7 +9,7 @@ use sp_runtime::{
     ModuleId, Perbill,
 };
 
-use crate::{self as social_bridge, Config};
+use crate::{self as social_bridge, Config, ResourceKind};
 use chainbridge as bridge;
 pub use pallet_balances as balances;
 
@@ -26,8 +26,9 @@ frame_support::construct_runtime!(
         Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
         Bridge: bridge::{Module, Call, Storage, Event<T>},
         Assets: pallet_assets::{Module, Call, Storage, Event<T>},
-        SocialBridge: social_bridge::{Module, Call, Event<T>},
+        SocialBridge: social_bridge::{Module, Call, Storage, Event<T>},
         Erc721: pallet_social_nft::{Module, Call, Storage, Event<T>},
+        Erc1155: pallet_social_multi_token::{Module, Call, Storage, Event<T>},
     }
 );
 
@@ -90,6 +91,11 @@ impl pallet_balances::Config for Test {
 parameter_types! {
     pub const TestChainId: u8 = 5;
     pub const ProposalLifetime: u64 = 100;
+    pub const LimitDelay: u64 = 10;
+    pub const RelayerBond: u64 = 0;
+    pub const MaxStrikes: u32 = 3;
+    pub const MaxMissedVotes: u32 = 100;
+    pub const MaxPrunedPerBlock: u32 = 10;
 }
 
 impl bridge::Config for Test {
@@ -98,6 +104,16 @@ impl bridge::Config for Test {
     type Proposal = Call;
     type ChainId = TestChainId;
     type ProposalLifetime = ProposalLifetime;
+    type ProposalTransfer = SocialBridge;
+    type LimitDelay = LimitDelay;
+    type OnProposalExecuted = SocialBridge;
+    type OnRelayerRemoved = SocialBridge;
+    type Currency = Balances;
+    type RelayerBond = RelayerBond;
+    type Slash = ();
+    type MaxStrikes = MaxStrikes;
+    type MaxMissedVotes = MaxMissedVotes;
+    type MaxPrunedPerBlock = MaxPrunedPerBlock;
 }
 
 parameter_types! {
@@ -106,6 +122,7 @@ parameter_types! {
     pub const StringLimit: u32 = 50;
     pub const MetadataDepositBase: u64 = 1;
     pub const MetadataDepositPerByte: u64 = 1;
+    pub const ApprovalDeposit: u64 = 1;
 }
 
 impl pallet_assets::Config for Test {
@@ -119,6 +136,7 @@ impl pallet_assets::Config for Test {
     type StringLimit = StringLimit;
     type MetadataDepositBase = MetadataDepositBase;
     type MetadataDepositPerByte = MetadataDepositPerByte;
+    type ApprovalDeposit = ApprovalDeposit;
     type WeightInfo = ();
 }
 
@@ -126,20 +144,42 @@ parameter_types! {
     pub HashId: bridge::ResourceId = bridge::derive_resource_id(1, &blake2_128(b"hash"));
     pub NativeTokenId: bridge::ResourceId = bridge::derive_resource_id(1, &blake2_128(b"NET"));
     pub Erc721Id: bridge::ResourceId = bridge::derive_resource_id(1, &blake2_128(b"NFT"));
+    pub Erc1155Id: bridge::ResourceId = bridge::derive_resource_id(1, &blake2_128(b"MULTI"));
+    pub const UriLimit: u32 = 64;
+    pub const MaxBatchSize: u32 = 8;
+    pub const CreateDeposit: u64 = 10;
+    pub const NftModuleId: ModuleId = ModuleId(*b"py/nftau");
+    pub const SoftCloseWindow: u64 = 5;
+    pub const MaxRoyaltyRecipients: u32 = 3;
+    pub const MaxAuctionsSettledPerBlock: u32 = 2;
+    pub const MaxBidsPerToken: u32 = 2;
+    pub const MaxOwnersIndexedPerBlock: u32 = 2;
 }
 
 impl pallet_social_nft::Config for Test {
     type Event = Event;
     type Identifier = Erc721Id;
+    type ModuleId = NftModuleId;
+    type SoftCloseWindow = SoftCloseWindow;
+    type MaxRoyaltyRecipients = MaxRoyaltyRecipients;
+    type MaxAuctionsSettledPerBlock = MaxAuctionsSettledPerBlock;
+    type MaxBidsPerToken = MaxBidsPerToken;
+    type MaxOwnersIndexedPerBlock = MaxOwnersIndexedPerBlock;
+}
+
+impl pallet_social_multi_token::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type UriLimit = UriLimit;
+    type MaxBatchSize = MaxBatchSize;
+    type Currency = Balances;
+    type CreateDeposit = CreateDeposit;
 }
 
 impl Config for Test {
     type Event = Event;
     type BridgeOrigin = bridge::EnsureBridge<Test>;
     type Currency = Balances;
-    type HashId = HashId;
-    type NativeTokenId = NativeTokenId;
-    type Erc721Id = Erc721Id;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -153,10 +193,20 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage(&mut t)
     .unwrap();
     let mut ext = sp_io::TestExternalities::new(t);
-    ext.execute_with(|| System::set_block_number(1));
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        register_resource(HashId::get(), ResourceKind::Generic(b"SocialBridge.remark".to_vec()));
+        register_resource(NativeTokenId::get(), ResourceKind::Native);
+        register_resource(Erc721Id::get(), ResourceKind::Nft(None));
+        register_resource(Erc1155Id::get(), ResourceKind::MultiToken);
+    });
     ext
 }
 
+fn register_resource(id: bridge::ResourceId, kind: ResourceKind<u32>) {
+    assert_ok!(SocialBridge::register_resource(Origin::root(), id, kind, 0, 0));
+}
+
 fn last_event() -> Event {
     system::Module::<Test>::events()
         .pop()
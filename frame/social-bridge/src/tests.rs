@@ -1,17 +1,18 @@
 #![cfg(test)]
 
 use super::mock::{
-    assert_events, balances, event_exists, expect_event, new_test_ext, Balances, Bridge, Call,
-    Erc721, Erc721Id, Event, HashId, NativeTokenId, Origin, ProposalLifetime, SocialBridge, Test,
-    ENDOWED_BALANCE, RELAYER_A, RELAYER_B, RELAYER_C,
+    assert_events, balances, event_exists, expect_event, new_test_ext, Assets, Balances, Bridge, Call,
+    Erc1155, Erc1155Id, Erc721, Erc721Id, Event, HashId, NativeTokenId, Origin, ProposalLifetime, SocialBridge, Test,
+    TestChainId, ENDOWED_BALANCE, RELAYER_A, RELAYER_B, RELAYER_C,
 };
 use super::*;
 use frame_support::dispatch::DispatchError;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
 
 use codec::Encode;
 use sp_core::{blake2_256, H256};
 
+use pallet_assets::{IssueAndBurn, TokenDossier};
 use pallet_social_nft::Erc721Token;
 
 const TEST_THRESHOLD: u32 = 2;
@@ -22,7 +23,7 @@ fn make_remark_proposal(hash: H256) -> Call {
 }
 
 fn make_transfer_proposal(to: u64, amount: u64) -> Call {
-    let resource_id = HashId::get();
+    let resource_id = NativeTokenId::get();
     Call::SocialBridge(crate::Call::transfer(to, amount, resource_id))
 }
 
@@ -200,7 +201,7 @@ fn transfer() {
     new_test_ext().execute_with(|| {
         // Check inital state
         let bridge_id: u64 = Bridge::account_id();
-        let resource_id = HashId::get();
+        let resource_id = NativeTokenId::get();
         assert_eq!(Balances::free_balance(&bridge_id), ENDOWED_BALANCE);
         // Transfer and check result
         assert_ok!(SocialBridge::transfer(
@@ -227,7 +228,7 @@ fn mint_erc721() {
         let recipient = RELAYER_A;
         let metadata = vec![1, 1, 1, 1];
         let bridge_id: u64 = Bridge::account_id();
-        let resource_id = HashId::get();
+        let resource_id = Erc721Id::get();
         // Token doesn't yet exist
         assert_eq!(Erc721::tokens(token_id), None);
         // Mint
@@ -260,6 +261,502 @@ fn mint_erc721() {
     })
 }
 
+#[test]
+fn transfer_erc1155() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        let resource_id = Erc1155Id::get();
+        // created on this chain, so the top bit is set
+        let token_id: U256 = (U256::one() << 255) + U256::from(100);
+        let token_id_slice: &mut [u8] = &mut [0; 32];
+        token_id.to_big_endian(token_id_slice);
+        let uri: Vec<u8> = vec![1, 2, 3, 4];
+        let recipient = vec![99];
+
+        // Create an edition of 500
+        assert_ok!(Erc1155::create(Origin::signed(RELAYER_A), token_id, uri.clone()));
+        assert_ok!(Erc1155::mint(Origin::signed(RELAYER_A), RELAYER_A, token_id, 500));
+
+        // Whitelist destination and transfer
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain.clone()));
+        assert_ok!(SocialBridge::transfer_erc1155(
+            Origin::signed(RELAYER_A),
+            recipient.clone(),
+            token_id,
+            200,
+            dest_chain,
+        ));
+
+        expect_event(bridge::RawEvent::SemiFungibleTransfer(
+            dest_chain,
+            1,
+            resource_id,
+            token_id_slice.to_vec(),
+            200.into(),
+            recipient.clone(),
+            uri,
+        ));
+
+        // The bridged copies are burned
+        assert_eq!(Erc1155::balance_of(token_id, RELAYER_A), 300);
+        assert_eq!(Erc1155::tokens(token_id).unwrap().total_supply, 300);
+
+        assert_noop!(
+            SocialBridge::transfer_erc1155(
+                Origin::signed(RELAYER_A),
+                recipient.clone(),
+                U256::from(101),
+                1,
+                dest_chain,
+            ),
+            Error::<Test>::InvalidTransfer
+        );
+    })
+}
+
+#[test]
+fn mint_erc1155() {
+    new_test_ext().execute_with(|| {
+        let token_id = U256::from(99);
+        let uri = vec![1, 1, 1, 1];
+        let bridge_id: u64 = Bridge::account_id();
+        let resource_id = Erc1155Id::get();
+        assert_eq!(Erc1155::tokens(token_id), None);
+
+        // The first mint creates the token
+        assert_ok!(SocialBridge::mint_erc1155(
+            Origin::signed(bridge_id),
+            RELAYER_A,
+            token_id,
+            10,
+            uri.clone(),
+            resource_id,
+        ));
+        assert_ok!(SocialBridge::mint_erc1155(
+            Origin::signed(bridge_id),
+            RELAYER_B,
+            token_id,
+            5,
+            uri.clone(),
+            resource_id,
+        ));
+        assert_eq!(Erc1155::tokens(token_id).unwrap().creator, bridge_id);
+        assert_eq!(Erc1155::balance_of(token_id, RELAYER_A), 10);
+        assert_eq!(Erc1155::balance_of(token_id, RELAYER_B), 5);
+        assert_eq!(Erc1155::tokens(token_id).unwrap().total_supply, 15);
+
+        assert_noop!(
+            SocialBridge::mint_erc1155(
+                Origin::signed(RELAYER_A),
+                RELAYER_A,
+                token_id,
+                10,
+                uri,
+                resource_id,
+            ),
+            DispatchError::BadOrigin
+        );
+    })
+}
+
+fn create_social_token(mode: BridgeMode) -> (u32, ResourceId) {
+    let token_id = <Assets as IssueAndBurn<_, _>>::create_new_asset(&RELAYER_A, TokenDossier::default(), 10, 1).unwrap();
+    assert_ok!(<Assets as IssueAndBurn<_, _>>::issue(&token_id, &RELAYER_A, 1_000));
+    let resource_id = bridge::derive_resource_id(0, b"SOCIAL");
+    assert_ok!(SocialBridge::register_resource(
+        Origin::root(),
+        resource_id,
+        ResourceKind::SocialToken(token_id, mode),
+        0,
+        0,
+    ));
+    (token_id, resource_id)
+}
+
+#[test]
+fn register_resource() {
+    new_test_ext().execute_with(|| {
+        let resource_id = bridge::derive_resource_id(0, b"SOCIAL");
+        assert_noop!(
+            SocialBridge::register_resource(Origin::signed(RELAYER_A), resource_id, ResourceKind::SocialToken(1, BridgeMode::Lock), 0, 0),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            SocialBridge::register_resource(Origin::root(), resource_id, ResourceKind::SocialToken(1, BridgeMode::Lock), 0, 0),
+            pallet_assets::Error::<Test>::Unknown
+        );
+        assert_noop!(
+            SocialBridge::register_resource(Origin::root(), resource_id, ResourceKind::Nft(Some(1)), 0, 0),
+            pallet_social_nft::Error::<Test>::CollectionNotExists
+        );
+        assert_noop!(
+            SocialBridge::register_resource(Origin::root(), resource_id, ResourceKind::Native, 0, 0),
+            Error::<Test>::AlreadyRegistered
+        );
+        assert_noop!(
+            SocialBridge::register_resource(Origin::root(), NativeTokenId::get(), ResourceKind::Generic(vec![1]), 0, 0),
+            Error::<Test>::AlreadyRegistered
+        );
+        assert_noop!(
+            SocialBridge::register_resource(Origin::root(), resource_id, ResourceKind::Generic(vec![1]), 0, 39),
+            Error::<Test>::InvalidDecimals
+        );
+
+        let (token_id, resource_id) = create_social_token(BridgeMode::Lock);
+        let kind = ResourceKind::SocialToken(token_id, BridgeMode::Lock);
+        assert_eq!(SocialBridge::resources(resource_id).unwrap().kind, kind.clone());
+        assert_eq!(SocialBridge::kind_resource(&kind), Some(resource_id));
+        assert_eq!(SocialBridge::social_token_resource(token_id), Some((resource_id, BridgeMode::Lock)));
+        assert_eq!(Bridge::resources(resource_id), Some(b"SocialBridge.transfer_social_tokens_in".to_vec()));
+        assert_noop!(
+            SocialBridge::register_resource(
+                Origin::root(),
+                [9; 32],
+                ResourceKind::SocialToken(token_id, BridgeMode::Mint),
+                0,
+                0,
+            ),
+            Error::<Test>::AlreadyRegistered
+        );
+
+        assert_ok!(SocialBridge::remove_resource(Origin::root(), resource_id));
+        assert_eq!(SocialBridge::resources(resource_id), None);
+        assert_eq!(SocialBridge::social_token_resource(token_id), None);
+        assert_eq!(Bridge::resources(resource_id), None);
+        event_exists(RawEvent::ResourceRemoved(resource_id));
+        assert_noop!(
+            SocialBridge::remove_resource(Origin::root(), resource_id),
+            Error::<Test>::ResourceNotRegistered
+        );
+    })
+}
+
+#[test]
+fn runtime_upgrade_should_register_runtime_resources() {
+    new_test_ext().execute_with(|| {
+        // the chain before the registry, with the native resource already registered
+        for id in [HashId::get(), Erc721Id::get(), Erc1155Id::get()].iter() {
+            assert_ok!(SocialBridge::remove_resource(Origin::root(), *id));
+        }
+        StorageVersion::put(Releases::V1);
+
+        SocialBridge::on_runtime_upgrade();
+        let chain_id = TestChainId::get();
+        let hash_id = bridge::derive_resource_id(chain_id, b"NET_HASH");
+        let nft_id = bridge::derive_resource_id(chain_id, b"NET_NFT");
+        let multi_id = bridge::derive_resource_id(chain_id, b"NET_MULTI");
+        assert_eq!(SocialBridge::kind_resource(ResourceKind::Generic(b"SocialBridge.remark".to_vec())), Some(hash_id));
+        assert_eq!(SocialBridge::kind_resource(ResourceKind::Nft(None)), Some(nft_id));
+        assert_eq!(SocialBridge::kind_resource(ResourceKind::MultiToken), Some(multi_id));
+        assert_eq!(SocialBridge::resources(nft_id).unwrap().kind, ResourceKind::Nft(None));
+        assert_eq!(Bridge::resources(nft_id), Some(b"SocialBridge.mint_erc721".to_vec()));
+        // registered kinds keep their resource id
+        assert_eq!(SocialBridge::kind_resource(ResourceKind::Native), Some(NativeTokenId::get()));
+        assert_eq!(SocialBridge::resources(bridge::derive_resource_id(chain_id, b"NET")), None);
+        assert_eq!(StorageVersion::get(), Releases::V2);
+    })
+}
+
+#[test]
+fn mint_erc721_should_count_collection_tokens() {
+    new_test_ext().execute_with(|| {
+        let bridge_id: u64 = Bridge::account_id();
+        let resource_id = bridge::derive_resource_id(0, b"COLLECTION");
+        assert_ok!(Erc721::create_collection(
+            Origin::signed(RELAYER_A),
+            vec![],
+            vec![],
+            Some(3),
+            pallet_social_nft::MintPolicy::Issuer,
+        ));
+        assert_ok!(SocialBridge::register_resource(Origin::root(), resource_id, ResourceKind::Nft(Some(1)), 0, 0));
+
+        let token_id = Erc721::collection_token_id(1, 1);
+        assert_ok!(SocialBridge::mint_erc721(Origin::signed(bridge_id), RELAYER_B, token_id, vec![], 0, resource_id));
+        assert_eq!(Erc721::token_collection(token_id), Some(1));
+        assert_eq!(Erc721::collections(1).unwrap().minted, 2);
+
+        // only the tokens of the collection of the resource id
+        assert_noop!(
+            SocialBridge::mint_erc721(Origin::signed(bridge_id), RELAYER_B, Erc721::collection_token_id(2, 0), vec![], 0, resource_id),
+            pallet_social_nft::Error::<Test>::InvalidTokenId
+        );
+        assert_noop!(
+            SocialBridge::mint_erc721(Origin::signed(bridge_id), RELAYER_B, Erc721::collection_token_id(1, 3), vec![], 0, resource_id),
+            pallet_social_nft::Error::<Test>::CollectionFull
+        );
+    })
+}
+
+#[test]
+fn disabled_resource() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        let bridge_id: u64 = Bridge::account_id();
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain));
+
+        assert_ok!(SocialBridge::set_resource_status(Origin::root(), NativeTokenId::get(), false));
+        expect_event(RawEvent::ResourceStatusChanged(NativeTokenId::get(), false));
+        assert_noop!(
+            SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain),
+            Error::<Test>::ResourceDisabled
+        );
+        assert_noop!(
+            SocialBridge::transfer(Origin::signed(bridge_id), RELAYER_A, 10, NativeTokenId::get()),
+            Error::<Test>::ResourceDisabled
+        );
+
+        assert_ok!(SocialBridge::set_resource_status(Origin::root(), NativeTokenId::get(), true));
+        assert_ok!(SocialBridge::transfer(Origin::signed(bridge_id), RELAYER_A, 10, NativeTokenId::get()));
+        assert_noop!(
+            SocialBridge::set_resource_status(Origin::root(), [9; 32], false),
+            Error::<Test>::ResourceNotRegistered
+        );
+    })
+}
+
+#[test]
+fn resource_decimals() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        let bridge_id: u64 = Bridge::account_id();
+        let recipient = vec![99];
+        let token_id = <Assets as IssueAndBurn<_, _>>::create_new_asset(&RELAYER_A, TokenDossier::default(), 10, 1).unwrap();
+        assert_ok!(<Assets as IssueAndBurn<_, _>>::issue(&token_id, &RELAYER_A, 1_000));
+        let resource_id = bridge::derive_resource_id(0, b"SOCIAL");
+        // two decimals here, six on the other chain
+        assert_ok!(SocialBridge::register_resource(
+            Origin::root(),
+            resource_id,
+            ResourceKind::SocialToken(token_id, BridgeMode::Lock),
+            2,
+            6,
+        ));
+
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain));
+        assert_ok!(SocialBridge::transfer_social_tokens(
+            Origin::signed(RELAYER_A),
+            token_id,
+            400,
+            recipient.clone(),
+            dest_chain,
+        ));
+        expect_event(bridge::RawEvent::FungibleTransfer(
+            dest_chain,
+            1,
+            resource_id,
+            4_000_000.into(),
+            recipient,
+        ));
+
+        assert_ok!(SocialBridge::transfer_social_tokens_in(Origin::signed(bridge_id), RELAYER_B, 1_500_000, resource_id));
+        assert_eq!(Assets::balance(token_id, RELAYER_B), 150);
+        // dust below the local decimals can't be bridged in
+        assert_noop!(
+            SocialBridge::transfer_social_tokens_in(Origin::signed(bridge_id), RELAYER_B, 1_500_001, resource_id),
+            Error::<Test>::InvalidAmount
+        );
+    })
+}
+
+#[test]
+fn transfer_social_tokens_lock() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        let bridge_id: u64 = Bridge::account_id();
+        let recipient = vec![99];
+        let (token_id, resource_id) = create_social_token(BridgeMode::Lock);
+
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain));
+        assert_ok!(SocialBridge::transfer_social_tokens(
+            Origin::signed(RELAYER_A),
+            token_id,
+            400,
+            recipient.clone(),
+            dest_chain,
+        ));
+        expect_event(bridge::RawEvent::FungibleTransfer(
+            dest_chain,
+            1,
+            resource_id,
+            400.into(),
+            recipient,
+        ));
+        assert_eq!(Assets::balance(token_id, RELAYER_A), 600);
+        assert_eq!(Assets::balance(token_id, bridge_id), 400);
+
+        // coming back releases the locked tokens
+        assert_ok!(SocialBridge::transfer_social_tokens_in(Origin::signed(bridge_id), RELAYER_B, 300, resource_id));
+        assert_eq!(Assets::balance(token_id, RELAYER_B), 300);
+        assert_eq!(Assets::balance(token_id, bridge_id), 100);
+        assert_noop!(
+            SocialBridge::transfer_social_tokens_in(Origin::signed(bridge_id), RELAYER_B, 101, resource_id),
+            pallet_assets::Error::<Test>::BalanceLow
+        );
+        assert_noop!(
+            SocialBridge::transfer_social_tokens_in(Origin::signed(RELAYER_A), RELAYER_B, 1, resource_id),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            SocialBridge::transfer_social_tokens_in(Origin::signed(bridge_id), RELAYER_B, 1, HashId::get()),
+            Error::<Test>::ResourceMismatch
+        );
+    })
+}
+
+#[test]
+fn transfer_social_tokens_mint() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        let bridge_id: u64 = Bridge::account_id();
+        let (token_id, resource_id) = create_social_token(BridgeMode::Mint);
+
+        assert_ok!(SocialBridge::transfer_social_tokens_in(Origin::signed(bridge_id), RELAYER_B, 500, resource_id));
+        assert_eq!(Assets::balance(token_id, RELAYER_B), 500);
+        assert_eq!(Assets::total_supply(token_id), 1_500);
+
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain));
+        assert_ok!(SocialBridge::transfer_social_tokens(
+            Origin::signed(RELAYER_B),
+            token_id,
+            200,
+            vec![99],
+            dest_chain,
+        ));
+        assert_eq!(Assets::balance(token_id, RELAYER_B), 300);
+        assert_eq!(Assets::balance(token_id, bridge_id), 0);
+        assert_eq!(Assets::total_supply(token_id), 1_300);
+    })
+}
+
+#[test]
+fn proposal_transfer() {
+    new_test_ext().execute_with(|| {
+        let hash: H256 = "ABC".using_encoded(blake2_256).into();
+        assert_eq!(
+            <SocialBridge as bridge::ProposalTransfer<Call>>::transfer_of(&make_transfer_proposal(RELAYER_A, 10)),
+            Some((NativeTokenId::get(), 10.into()))
+        );
+        assert_eq!(
+            <SocialBridge as bridge::ProposalTransfer<Call>>::transfer_of(&make_remark_proposal(hash)),
+            Some((HashId::get(), U256::zero()))
+        );
+        let proposal = Call::Balances(balances::Call::transfer(RELAYER_A, 10));
+        assert_eq!(<SocialBridge as bridge::ProposalTransfer<Call>>::transfer_of(&proposal), None);
+    })
+}
+
+#[test]
+fn transfer_fees_and_relayer_rewards() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        let bridge_id: u64 = Bridge::account_id();
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain));
+
+        assert_noop!(
+            SocialBridge::set_fee(Origin::signed(RELAYER_A), dest_chain, NativeTokenId::get(), 30),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(SocialBridge::set_fee(Origin::root(), dest_chain, NativeTokenId::get(), 30));
+        assert_eq!(SocialBridge::transfer_fee(dest_chain, NativeTokenId::get()), 30);
+
+        assert_ok!(SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain));
+        event_exists(RawEvent::FeePaid(RELAYER_A, dest_chain, NativeTokenId::get(), 30));
+        assert_eq!(Balances::free_balance(RELAYER_A), ENDOWED_BALANCE - 130);
+        assert_eq!(Balances::free_balance(bridge_id), ENDOWED_BALANCE + 130);
+        assert_eq!(SocialBridge::fee_pot(), 30);
+
+        // a transfer failing after the fee was taken gets it back
+        assert_noop!(
+            SocialBridge::transfer_native(Origin::signed(RELAYER_A), ENDOWED_BALANCE - 140, vec![99], dest_chain),
+            balances::Error::<Test>::InsufficientBalance
+        );
+
+        assert_noop!(SocialBridge::claim_relayer_reward(Origin::signed(RELAYER_A)), Error::<Test>::NoRelayerReward);
+        <SocialBridge as bridge::OnProposalExecuted<u64>>::on_proposal_executed(&[RELAYER_A, RELAYER_B]);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 15);
+
+        assert_ok!(SocialBridge::claim_relayer_reward(Origin::signed(RELAYER_A)));
+        expect_event(RawEvent::RelayerRewardClaimed(RELAYER_A, 15));
+        assert_eq!(Balances::free_balance(RELAYER_A), ENDOWED_BALANCE - 115);
+        assert_eq!(SocialBridge::fee_pot(), 15);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 0);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_B), 15);
+
+        assert_ok!(SocialBridge::set_fee(Origin::root(), dest_chain, NativeTokenId::get(), 0));
+        assert_ok!(SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain));
+        assert_eq!(SocialBridge::fee_pot(), 15);
+    })
+}
+
+#[test]
+fn relayer_reward_should_only_share_later_fees() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain));
+        assert_ok!(SocialBridge::set_fee(Origin::root(), dest_chain, NativeTokenId::get(), 30));
+
+        <SocialBridge as bridge::OnProposalExecuted<u64>>::on_proposal_executed(&[RELAYER_A]);
+        assert_ok!(SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain));
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 30);
+
+        // fees collected before its vote don't go to B
+        <SocialBridge as bridge::OnProposalExecuted<u64>>::on_proposal_executed(&[RELAYER_B]);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_B), 0);
+        assert_ok!(SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain));
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 45);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_B), 15);
+
+        // a second vote of A keeps what its first point earned
+        <SocialBridge as bridge::OnProposalExecuted<u64>>::on_proposal_executed(&[RELAYER_A]);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 45);
+        assert_ok!(SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain));
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 65);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_B), 25);
+
+        assert_ok!(SocialBridge::claim_relayer_reward(Origin::signed(RELAYER_A)));
+        expect_event(RawEvent::RelayerRewardClaimed(RELAYER_A, 65));
+        assert_eq!(SocialBridge::fee_pot(), 25);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 0);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_B), 25);
+    })
+}
+
+#[test]
+fn removed_relayers_should_stop_earning() {
+    new_test_ext().execute_with(|| {
+        let dest_chain = 0;
+        assert_ok!(Bridge::whitelist_chain(Origin::root(), dest_chain));
+        assert_ok!(SocialBridge::set_fee(Origin::root(), dest_chain, NativeTokenId::get(), 30));
+        for relayer in &[RELAYER_A, RELAYER_B, RELAYER_C] {
+            assert_ok!(Bridge::add_relayer(Origin::root(), *relayer));
+        }
+        <SocialBridge as bridge::OnProposalExecuted<u64>>::on_proposal_executed(&[RELAYER_A, RELAYER_B, RELAYER_C]);
+        assert_ok!(SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain));
+
+        // a removed relayer keeps what it earned
+        assert_ok!(Bridge::remove_relayer(Origin::root(), RELAYER_B));
+        assert_eq!(SocialBridge::total_relayer_points(), 2);
+        assert_ok!(SocialBridge::transfer_native(Origin::signed(RELAYER_A), 100, vec![99], dest_chain));
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 25);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_B), 10);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_C), 25);
+
+        // a slashed relayer forfeits its fees to the others
+        <SocialBridge as bridge::OnRelayerRemoved<u64>>::on_relayer_removed(&RELAYER_C, true);
+        assert_eq!(SocialBridge::total_relayer_points(), 1);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_A), 50);
+        assert_eq!(SocialBridge::relayer_reward(&RELAYER_C), 0);
+        assert_noop!(
+            SocialBridge::claim_relayer_reward(Origin::signed(RELAYER_C)),
+            Error::<Test>::NoRelayerReward
+        );
+
+        assert_ok!(SocialBridge::claim_relayer_reward(Origin::signed(RELAYER_B)));
+        assert_eq!(SocialBridge::fee_pot(), 50);
+    })
+}
+
 #[test]
 fn create_sucessful_transfer_proposal() {
     new_test_ext().execute_with(|| {
@@ -332,6 +829,11 @@ fn create_sucessful_transfer_proposal() {
             Balances::free_balance(Bridge::account_id()),
             ENDOWED_BALANCE - 10
         );
+        // only the relayers voting for the proposal earn a share of the fees
+        assert_eq!(SocialBridge::relayer_points(RELAYER_A), 1);
+        assert_eq!(SocialBridge::relayer_points(RELAYER_B), 0);
+        assert_eq!(SocialBridge::relayer_points(RELAYER_C), 1);
+        assert_eq!(SocialBridge::total_relayer_points(), 2);
 
         assert_events(vec![
             Event::bridge(bridge::RawEvent::VoteFor(src_id, prop_id, RELAYER_A)),
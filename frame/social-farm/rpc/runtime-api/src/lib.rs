@@ -0,0 +1,22 @@
+//! Runtime API definition required by Social Farm RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding Social Farm reward queries.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the rewards of the social farm module.
+	pub trait SocialFarmApi<AccountId, Balance, ExchangeId> where
+		AccountId: Codec,
+		Balance: Codec,
+		ExchangeId: Codec,
+	{
+		/// Rewards earned by `who` for the lp tokens of the given exchange it staked,
+		/// and not paid yet.
+		fn pending_rewards(exchange_id: ExchangeId, who: AccountId) -> Balance;
+	}
+}
@@ -0,0 +1,91 @@
+//! Node-specific RPC methods for the rewards of the social farm module.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_social_farm_rpc_runtime_api::SocialFarmApi as SocialFarmRuntimeApi;
+pub use self::gen_client::Client as SocialFarmClient;
+
+/// Social farm RPC methods.
+#[rpc]
+pub trait SocialFarmApi<BlockHash, AccountId, Balance, ExchangeId> {
+	/// Rewards earned by `who` for the lp tokens of the given exchange it staked,
+	/// and not paid yet.
+	#[rpc(name = "socialFarm_pendingRewards")]
+	fn pending_rewards(
+		&self,
+		exchange_id: ExchangeId,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<NumberOrHex>;
+}
+
+/// A struct that implements the [`SocialFarmApi`].
+pub struct SocialFarm<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> SocialFarm<C, B> {
+	/// Create new `SocialFarm` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		SocialFarm { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId, Balance, ExchangeId> SocialFarmApi<
+	<Block as BlockT>::Hash,
+	AccountId,
+	Balance,
+	ExchangeId,
+> for SocialFarm<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SocialFarmRuntimeApi<Block, AccountId, Balance, ExchangeId>,
+	AccountId: Codec,
+	Balance: Codec + Into<NumberOrHex>,
+	ExchangeId: Codec,
+{
+	fn pending_rewards(
+		&self,
+		exchange_id: ExchangeId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let rewards = api.pending_rewards(&at, exchange_id, who).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query pending rewards.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})?;
+
+		Ok(rewards.into())
+	}
+}
@@ -0,0 +1,349 @@
+//! Liquidity mining for the lp tokens of `pallet_social_swap`.
+//!
+//! Liquidity providers stake the lp tokens of an exchange in the pot of this module and
+//! earn the rewards emitted every block for that exchange, pro rata to their stake.
+//! As in the MasterChef contract, a pool only tracks its accumulated rewards per staked
+//! lp token, so staking, unstaking and claiming do not depend on the number of stakers.
+//! Rewards are paid in native currency or in a `pallet_assets` token out of the reward
+//! pot, which has to be funded for the emission of every pool.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure,
+    traits::{Currency, EnsureOrigin, ExistenceRequirement, Get},
+    transactional,
+};
+use frame_system::ensure_signed;
+use pallet_assets::{Fungible, IssueAndBurn};
+use sp_runtime::{
+    traits::{AccountIdConversion, CheckedAdd, CheckedSub, SaturatedConversion, Saturating, Zero},
+    DispatchResult, ModuleId, RuntimeDebug,
+};
+use sp_std::cmp;
+
+pub use pallet_social_swap::{BalanceOf, CurrencyOf};
+
+pub type RewardTokenOf<T> = RewardToken<<T as pallet_assets::Config>::AssetId>;
+pub type PoolOf<T> = Pool<
+    <T as pallet_assets::Config>::AssetId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+pub type StakeOf<T> = Stake<BalanceOf<T>>;
+
+/// Scale of the accumulated rewards per staked lp token
+const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The token the rewards of a pool are paid in
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RewardToken<AssetId> {
+    /// native currency
+    Native,
+    /// a `pallet_assets` token, such as a social token
+    Asset(AssetId),
+}
+
+/// Rewards emitted for the lp tokens of an exchange
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Pool<AssetId, Balance, BlockNumber> {
+    pub reward: RewardToken<AssetId>,
+    /// rewards emitted every block, shared by the stakers
+    pub reward_per_block: Balance,
+    /// rewards per staked lp token since the pool was created, times `ACC_REWARD_PRECISION`
+    pub acc_reward_per_share: u128,
+    /// block up to which the rewards are accumulated
+    pub last_reward_block: BlockNumber,
+    /// lp tokens staked in the pool
+    pub total_staked: Balance,
+}
+
+/// Lp tokens staked by an account in a pool
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct Stake<Balance> {
+    pub amount: Balance,
+    /// accumulated rewards of `amount` already accounted for
+    pub reward_debt: u128,
+    /// rewards earned which the reward pot could not pay yet
+    pub unpaid: u128,
+}
+
+pub trait Config: frame_system::Config + pallet_social_swap::Config {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    type ModuleId: Get<ModuleId>;
+    /// origin allowed to set the emission rates of the pools
+    type EmissionOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_event! {
+    pub enum Event<T> where
+        <T as frame_system::Config>::AccountId,
+        <T as pallet_social_swap::Config>::ExchangeId,
+        Balance = BalanceOf<T>,
+        Reward = RewardTokenOf<T>,
+    {
+        /// Emission of a pool changed [exchange_id, reward_token, reward_per_block]
+        EmissionRateSet(ExchangeId, Reward, Balance),
+        /// Lp tokens staked [exchange_id, staker, amount]
+        Staked(ExchangeId, AccountId, Balance),
+        /// Lp tokens unstaked [exchange_id, staker, amount]
+        Unstaked(ExchangeId, AccountId, Balance),
+        /// Rewards paid [exchange_id, staker, reward_token, amount]
+        RewardPaid(ExchangeId, AccountId, Reward, Balance),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// exchange does not exist
+        ExchangeNotExists,
+        /// no rewards are emitted for the exchange
+        PoolNotExists,
+        RewardTokenNotExists,
+        /// a pool keeps the reward token it was created with
+        RewardTokenMismatch,
+        ZeroAmount,
+        /// unstaking more than staked
+        InsufficientStake,
+        OverFlow,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as SocialFarm {
+        /// Reward pools by exchange
+        pub Pools get(fn pools): map hasher(twox_64_concat) T::ExchangeId => Option<PoolOf<T>>;
+        /// Lp tokens staked by every account in every pool
+        pub Stakes get(fn stakes): double_map hasher(twox_64_concat) T::ExchangeId,
+            hasher(blake2_128_concat) T::AccountId => StakeOf<T>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+
+        fn deposit_event() = default;
+
+        /// Emit `reward_per_block` reward tokens every block to the stakers of an exchange,
+        /// creating its pool if needed. Rewards accumulated so far keep the previous rate.
+        #[weight = 20_000_000 + T::DbWeight::get().reads_writes(3, 1)]
+        fn set_emission_rate(
+            origin,
+            exchange_id: T::ExchangeId,
+            reward: RewardTokenOf<T>,
+            reward_per_block: BalanceOf<T>
+        ) -> DispatchResult {
+            T::EmissionOrigin::ensure_origin(origin)?;
+            Self::lp_token(&exchange_id)?;
+            if let RewardToken::Asset(asset_id) = reward {
+                ensure!(T::FungibleToken::exists(&asset_id), Error::<T>::RewardTokenNotExists);
+            }
+
+            let pool = match Self::pools(&exchange_id) {
+                Some(mut pool) => {
+                    ensure!(pool.reward == reward, Error::<T>::RewardTokenMismatch);
+                    Self::update_pool(&mut pool);
+                    pool.reward_per_block = reward_per_block;
+                    pool
+                }
+                None => Pool {
+                    reward,
+                    reward_per_block,
+                    acc_reward_per_share: 0,
+                    last_reward_block: <frame_system::Module<T>>::block_number(),
+                    total_staked: Zero::zero(),
+                },
+            };
+            <Pools<T>>::insert(&exchange_id, pool);
+
+            Self::deposit_event(RawEvent::EmissionRateSet(exchange_id, reward, reward_per_block));
+            Ok(())
+        }
+
+        /// Stake `amount` lp tokens of an exchange, paying the rewards earned so far.
+        #[weight = 40_000_000 + T::DbWeight::get().reads_writes(6, 6)]
+        #[transactional]
+        fn stake_lp(origin, exchange_id: T::ExchangeId, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let mut pool = Self::pools(&exchange_id).ok_or(Error::<T>::PoolNotExists)?;
+            let lp_token = Self::lp_token(&exchange_id)?;
+            Self::update_pool(&mut pool);
+
+            let mut stake = Self::stakes(&exchange_id, &who);
+            Self::harvest(&exchange_id, &who, &pool, &mut stake)?;
+
+            T::FungibleToken::transfer(&lp_token, &who, &Self::account_id(), amount)?;
+            stake.amount = stake.amount.checked_add(&amount).ok_or(Error::<T>::OverFlow)?;
+            pool.total_staked = pool.total_staked.checked_add(&amount).ok_or(Error::<T>::OverFlow)?;
+            stake.reward_debt = Self::accumulated(&pool, stake.amount);
+
+            <Stakes<T>>::insert(&exchange_id, &who, stake);
+            <Pools<T>>::insert(&exchange_id, pool);
+            Self::deposit_event(RawEvent::Staked(exchange_id, who, amount));
+            Ok(())
+        }
+
+        /// Withdraw `amount` staked lp tokens of an exchange, paying the rewards earned so far.
+        #[weight = 40_000_000 + T::DbWeight::get().reads_writes(6, 6)]
+        #[transactional]
+        fn unstake(origin, exchange_id: T::ExchangeId, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let mut pool = Self::pools(&exchange_id).ok_or(Error::<T>::PoolNotExists)?;
+            let lp_token = Self::lp_token(&exchange_id)?;
+            let mut stake = Self::stakes(&exchange_id, &who);
+            ensure!(stake.amount >= amount, Error::<T>::InsufficientStake);
+            Self::update_pool(&mut pool);
+
+            Self::harvest(&exchange_id, &who, &pool, &mut stake)?;
+
+            T::FungibleToken::transfer(&lp_token, &Self::account_id(), &who, amount)?;
+            stake.amount -= amount;
+            pool.total_staked = pool.total_staked.saturating_sub(amount);
+            stake.reward_debt = Self::accumulated(&pool, stake.amount);
+
+            if stake.amount.is_zero() && stake.unpaid == 0 {
+                <Stakes<T>>::remove(&exchange_id, &who);
+            } else {
+                <Stakes<T>>::insert(&exchange_id, &who, stake);
+            }
+            <Pools<T>>::insert(&exchange_id, pool);
+            Self::deposit_event(RawEvent::Unstaked(exchange_id, who, amount));
+            Ok(())
+        }
+
+        /// Pay the rewards earned in the pool of an exchange.
+        #[weight = 30_000_000 + T::DbWeight::get().reads_writes(4, 4)]
+        #[transactional]
+        fn claim(origin, exchange_id: T::ExchangeId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut pool = Self::pools(&exchange_id).ok_or(Error::<T>::PoolNotExists)?;
+            Self::update_pool(&mut pool);
+
+            let mut stake = Self::stakes(&exchange_id, &who);
+            Self::harvest(&exchange_id, &who, &pool, &mut stake)?;
+            stake.reward_debt = Self::accumulated(&pool, stake.amount);
+
+            if stake.amount.is_zero() && stake.unpaid == 0 {
+                <Stakes<T>>::remove(&exchange_id, &who);
+            } else {
+                <Stakes<T>>::insert(&exchange_id, &who, stake);
+            }
+            <Pools<T>>::insert(&exchange_id, pool);
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// The account id of the pot holding the staked lp tokens
+    pub fn account_id() -> T::AccountId {
+        <T as Config>::ModuleId::get().into_account()
+    }
+
+    /// The account id of the pot the rewards are paid from
+    pub fn reward_account_id() -> T::AccountId {
+        <T as Config>::ModuleId::get().into_sub_account(b"rewards")
+    }
+
+    /// Rewards earned by `who` in the pool of an exchange and not paid yet
+    pub fn pending_rewards(exchange_id: T::ExchangeId, who: T::AccountId) -> BalanceOf<T> {
+        let mut pool = match Self::pools(&exchange_id) {
+            Some(pool) => pool,
+            None => return Zero::zero(),
+        };
+        Self::update_pool(&mut pool);
+        let stake = Self::stakes(&exchange_id, &who);
+        Self::earned(&pool, &stake).saturated_into()
+    }
+
+    fn lp_token(exchange_id: &T::ExchangeId) -> Result<T::AssetId, Error<T>> {
+        pallet_social_swap::Module::<T>::exchanges(exchange_id)
+            .map(|exchange| exchange.lp_token)
+            .ok_or(Error::<T>::ExchangeNotExists)
+    }
+
+    /// Accumulate the rewards emitted since the last update of the pool
+    fn update_pool(pool: &mut PoolOf<T>) {
+        let now = <frame_system::Module<T>>::block_number();
+        if now <= pool.last_reward_block {
+            return;
+        }
+        if !pool.total_staked.is_zero() {
+            let blocks = now.saturating_sub(pool.last_reward_block).saturated_into::<u128>();
+            let rewards = blocks.saturating_mul(pool.reward_per_block.saturated_into::<u128>());
+            pool.acc_reward_per_share = pool.acc_reward_per_share.saturating_add(
+                rewards.saturating_mul(ACC_REWARD_PRECISION) / pool.total_staked.saturated_into::<u128>(),
+            );
+        }
+        pool.last_reward_block = now;
+    }
+
+    /// Rewards accumulated by `amount` lp tokens since the pool was created
+    fn accumulated(pool: &PoolOf<T>, amount: BalanceOf<T>) -> u128 {
+        amount.saturated_into::<u128>().saturating_mul(pool.acc_reward_per_share) / ACC_REWARD_PRECISION
+    }
+
+    fn earned(pool: &PoolOf<T>, stake: &StakeOf<T>) -> u128 {
+        Self::accumulated(pool, stake.amount)
+            .saturating_sub(stake.reward_debt)
+            .saturating_add(stake.unpaid)
+    }
+
+    /// Pay the rewards earned by a stake up to what the reward pot holds,
+    /// the rest is kept for later so the lp tokens can always be unstaked
+    fn harvest(
+        exchange_id: &T::ExchangeId,
+        who: &T::AccountId,
+        pool: &PoolOf<T>,
+        stake: &mut StakeOf<T>,
+    ) -> DispatchResult {
+        let earned = Self::earned(pool, stake);
+        let pot = Self::reward_account_id();
+        let available = match pool.reward {
+            RewardToken::Native => {
+                <T as pallet_social_swap::Config>::Currency::free_balance(&pot).saturated_into::<u128>()
+            }
+            RewardToken::Asset(asset_id) => T::FungibleToken::balances(&asset_id, &pot).saturated_into::<u128>(),
+        };
+        let amount = cmp::min(earned, available);
+
+        let paid = !amount.is_zero()
+            && match pool.reward {
+                RewardToken::Native => <T as pallet_social_swap::Config>::Currency::transfer(
+                    &pot,
+                    who,
+                    amount.saturated_into(),
+                    ExistenceRequirement::AllowDeath,
+                )
+                .is_ok(),
+                RewardToken::Asset(asset_id) => {
+                    T::FungibleToken::transfer(&asset_id, &pot, who, amount.saturated_into()).is_ok()
+                }
+            };
+
+        if paid {
+            stake.unpaid = earned.checked_sub(amount).ok_or(Error::<T>::OverFlow)?;
+            Self::deposit_event(RawEvent::RewardPaid(
+                *exchange_id,
+                who.clone(),
+                pool.reward,
+                amount.saturated_into(),
+            ));
+        } else {
+            // nothing to pay or below the minimum balance of the staker
+            stake.unpaid = earned;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{mock::*, Error};
+use frame_support::{assert_noop, assert_ok, traits::UnfilteredDispatchable};
+use sp_runtime::DispatchError;
+use super::*;
+
+const LIQUIDITY: u64 = 10_000;
+
+/// Create the exchange of `ASSET_ID` and give lp tokens to `OWNER` and `BOB`
+fn create_exchange() -> (u64, u32) {
+	assert_ok!(pallet_social_swap::Call::<Test>::create_exchange(ASSET_ID, MAX_ZOMBIES, 1)
+		.dispatch_bypass_filter(Origin::signed(OWNER)));
+	let exchange_id = SocialSwap::tt_to_exchange(ASSET_ID).unwrap();
+	assert_ok!(pallet_social_swap::Call::<Test>::add_liquidity(exchange_id, LIQUIDITY, 0, LIQUIDITY, 10)
+		.dispatch_bypass_filter(Origin::signed(OWNER)));
+
+	let lp_token = SocialSwap::exchanges(exchange_id).unwrap().lp_token;
+	assert_ok!(<Assets as Fungible<_, _>>::transfer(&lp_token, &OWNER, &BOB, 4_000));
+	(exchange_id, lp_token)
+}
+
+fn fund_rewards(amount: u64) {
+	assert_ok!(<Assets as Fungible<_, _>>::transfer(&ASSET_ID, &OWNER, &SocialFarm::reward_account_id(), amount));
+}
+
+#[test]
+fn test_set_emission_rate_should_work() {
+	new_test_ext().execute_with(|| {
+		let (exchange_id, _) = create_exchange();
+
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Asset(ASSET_ID), 100));
+		let pool = SocialFarm::pools(exchange_id).unwrap();
+		assert_eq!(pool.reward, RewardToken::Asset(ASSET_ID));
+		assert_eq!(pool.reward_per_block, 100);
+		assert_eq!(pool.last_reward_block, 1);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_farm(RawEvent::EmissionRateSet(exchange_id, RewardToken::Asset(ASSET_ID), 100))));
+	});
+}
+
+#[test]
+fn test_set_emission_rate_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SocialFarm::set_emission_rate(Origin::root(), 0, RewardToken::Native, 100),
+			Error::<Test>::ExchangeNotExists
+		);
+		let (exchange_id, _) = create_exchange();
+
+		assert_noop!(
+			SocialFarm::set_emission_rate(Origin::signed(OWNER), exchange_id, RewardToken::Native, 100),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Asset(42), 100),
+			Error::<Test>::RewardTokenNotExists
+		);
+
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Native, 100));
+		assert_noop!(
+			SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Asset(ASSET_ID), 100),
+			Error::<Test>::RewardTokenMismatch
+		);
+	});
+}
+
+#[test]
+fn test_rewards_should_be_shared_pro_rata() {
+	new_test_ext().execute_with(|| {
+		let (exchange_id, lp_token) = create_exchange();
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Asset(ASSET_ID), 100));
+		fund_rewards(10_000);
+
+		assert_ok!(SocialFarm::stake_lp(Origin::signed(OWNER), exchange_id, 1_000));
+		assert_eq!(Assets::balance(lp_token, SocialFarm::account_id()), 1_000);
+
+		System::set_block_number(3);
+		assert_ok!(SocialFarm::stake_lp(Origin::signed(BOB), exchange_id, 3_000));
+		assert_eq!(SocialFarm::pending_rewards(exchange_id, OWNER), 200);
+		assert_eq!(SocialFarm::pending_rewards(exchange_id, BOB), 0);
+
+		System::set_block_number(5);
+		assert_eq!(SocialFarm::pending_rewards(exchange_id, OWNER), 250);
+		assert_eq!(SocialFarm::pending_rewards(exchange_id, BOB), 150);
+
+		let balance = Assets::balance(ASSET_ID, OWNER);
+		assert_ok!(SocialFarm::claim(Origin::signed(OWNER), exchange_id));
+		assert_eq!(Assets::balance(ASSET_ID, OWNER), balance + 250);
+		assert_eq!(SocialFarm::pending_rewards(exchange_id, OWNER), 0);
+		assert_eq!(SocialFarm::stakes(exchange_id, OWNER).amount, 1_000);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_farm(RawEvent::RewardPaid(exchange_id, OWNER, RewardToken::Asset(ASSET_ID), 250))));
+	});
+}
+
+#[test]
+fn test_emission_rate_change_should_not_be_retroactive() {
+	new_test_ext().execute_with(|| {
+		let (exchange_id, _) = create_exchange();
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Asset(ASSET_ID), 100));
+		assert_ok!(SocialFarm::stake_lp(Origin::signed(OWNER), exchange_id, 1_000));
+
+		System::set_block_number(3);
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Asset(ASSET_ID), 300));
+
+		System::set_block_number(4);
+		assert_eq!(SocialFarm::pending_rewards(exchange_id, OWNER), 500);
+	});
+}
+
+#[test]
+fn test_unstake_should_work() {
+	new_test_ext().execute_with(|| {
+		let (exchange_id, lp_token) = create_exchange();
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Asset(ASSET_ID), 100));
+		fund_rewards(10_000);
+		assert_ok!(SocialFarm::stake_lp(Origin::signed(BOB), exchange_id, 4_000));
+
+		System::set_block_number(2);
+		assert_noop!(
+			SocialFarm::unstake(Origin::signed(BOB), exchange_id, 4_001),
+			Error::<Test>::InsufficientStake
+		);
+		assert_ok!(SocialFarm::unstake(Origin::signed(BOB), exchange_id, 2_000));
+		assert_eq!(Assets::balance(lp_token, BOB), 2_000);
+		assert_eq!(Assets::balance(ASSET_ID, BOB), 100);
+		assert_eq!(SocialFarm::pools(exchange_id).unwrap().total_staked, 2_000);
+
+		System::set_block_number(3);
+		assert_ok!(SocialFarm::unstake(Origin::signed(BOB), exchange_id, 2_000));
+		assert_eq!(Assets::balance(lp_token, BOB), 4_000);
+		assert_eq!(Assets::balance(ASSET_ID, BOB), 200);
+		assert_eq!(SocialFarm::stakes(exchange_id, BOB), Stake::default());
+		assert_eq!(SocialFarm::pools(exchange_id).unwrap().total_staked, 0);
+	});
+}
+
+#[test]
+fn test_stake_lp_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let (exchange_id, _) = create_exchange();
+		assert_noop!(
+			SocialFarm::stake_lp(Origin::signed(BOB), exchange_id, 1_000),
+			Error::<Test>::PoolNotExists
+		);
+
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Native, 100));
+		assert_noop!(
+			SocialFarm::stake_lp(Origin::signed(BOB), exchange_id, 0),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			SocialFarm::stake_lp(Origin::signed(BOB), exchange_id, 4_001),
+			pallet_assets::Error::<Test>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn test_unfunded_rewards_should_be_kept() {
+	new_test_ext().execute_with(|| {
+		let (exchange_id, lp_token) = create_exchange();
+		assert_ok!(SocialFarm::set_emission_rate(Origin::root(), exchange_id, RewardToken::Native, 100));
+		assert_ok!(SocialFarm::stake_lp(Origin::signed(BOB), exchange_id, 4_000));
+
+		// the lp tokens can be withdrawn with an empty reward pot
+		System::set_block_number(3);
+		assert_ok!(SocialFarm::unstake(Origin::signed(BOB), exchange_id, 4_000));
+		assert_eq!(Assets::balance(lp_token, BOB), 4_000);
+		assert_eq!(SocialFarm::pending_rewards(exchange_id, BOB), 200);
+
+		assert_ok!(Balances::transfer(Origin::signed(OWNER), SocialFarm::reward_account_id(), 1_000));
+		let balance = Balances::free_balance(BOB);
+		assert_ok!(SocialFarm::claim(Origin::signed(BOB), exchange_id));
+		assert_eq!(Balances::free_balance(BOB), balance + 200);
+		assert_eq!(SocialFarm::stakes(exchange_id, BOB), Stake::default());
+	});
+}
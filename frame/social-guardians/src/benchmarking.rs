@@ -0,0 +1,96 @@
+//! Social guardians pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use pallet_assets::{IssueAndBurn, TokenDossier};
+use sp_runtime::traits::Bounded;
+
+use crate::Module as SocialGuardians;
+
+const SEED: u32 = 0;
+
+// Create a social token owned by `owner`.
+fn create_token<T: Config>(owner: &T::AccountId) -> T::AssetId {
+    let balance = Bounded::max_value();
+    let _ = <T as pallet_assets::Config>::Currency::make_free_balance_be(owner, balance);
+    <pallet_assets::Module<T> as IssueAndBurn<_, _>>::create_new_asset(owner, TokenDossier::default(), 0, 1)
+        .expect("the owner pays the asset deposit")
+}
+
+// Create a controller able to pay the guardian deposit, approved by the token owner.
+fn approved_guardian<T: Config>(owner: &T::AccountId, social_token_id: T::AssetId, index: u32) -> T::AccountId {
+    let guardian: T::AccountId = account("guardian", index, SEED);
+    let _ = <T as Config>::Currency::make_free_balance_be(&guardian, Bounded::max_value());
+    SocialGuardians::<T>::approve(RawOrigin::Signed(owner.clone()).into(), social_token_id, guardian.clone())
+        .expect("the owner approves the guardian");
+    guardian
+}
+
+// Register a guardian in era 0 and move to era `h`, so that removing it records `h + 1` eras.
+fn guardian_since<T: Config>(owner: &T::AccountId, social_token_id: T::AssetId, h: u32) -> T::AccountId {
+    let guardian = approved_guardian::<T>(owner, social_token_id, 0);
+    pallet_staking::CurrentEra::put(0);
+    SocialGuardians::<T>::register(RawOrigin::Signed(guardian.clone()).into(), social_token_id)
+        .expect("the guardian is approved");
+    pallet_staking::CurrentEra::put(h);
+    guardian
+}
+
+benchmarks! {
+    register {
+        let owner: T::AccountId = whitelisted_caller();
+        let social_token_id = create_token::<T>(&owner);
+        let guardian = approved_guardian::<T>(&owner, social_token_id, 0);
+    }: _(RawOrigin::Signed(guardian.clone()), social_token_id)
+    verify {
+        assert!(SocialGuardians::<T>::is_guardian_of(social_token_id, &guardian));
+    }
+
+    unregister {
+        let h in 0 .. 84;
+        let owner: T::AccountId = whitelisted_caller();
+        let social_token_id = create_token::<T>(&owner);
+        let guardian = guardian_since::<T>(&owner, social_token_id, h);
+    }: _(RawOrigin::Signed(guardian.clone()))
+    verify {
+        assert_eq!(SocialGuardians::<T>::champion_of(&guardian), None);
+        assert_eq!(SocialGuardians::<T>::guardian_at(0, &guardian), Some(social_token_id));
+    }
+
+    approve {
+        let owner: T::AccountId = whitelisted_caller();
+        let social_token_id = create_token::<T>(&owner);
+        let guardian: T::AccountId = account("guardian", 0, SEED);
+    }: _(RawOrigin::Signed(owner), social_token_id, guardian.clone())
+    verify {
+        assert!(SocialGuardians::<T>::is_approved(social_token_id, &guardian));
+    }
+
+    revoke {
+        let h in 0 .. 84;
+        let owner: T::AccountId = whitelisted_caller();
+        let social_token_id = create_token::<T>(&owner);
+        let guardian = guardian_since::<T>(&owner, social_token_id, h);
+    }: _(RawOrigin::Signed(owner), social_token_id, guardian.clone())
+    verify {
+        assert_eq!(SocialGuardians::<T>::champion_of(&guardian), None);
+    }
+
+    prune_history {
+        let n in 0 .. T::MaxPrunedPerBlock::get();
+        let owner: T::AccountId = whitelisted_caller();
+        let social_token_id = create_token::<T>(&owner);
+        for i in 0 .. n {
+            let guardian: T::AccountId = account("guardian", i, SEED);
+            <GuardianDetailHistory<T>>::insert(0, guardian, social_token_id);
+        }
+        // era 0 is out of the default history depth of 84 eras
+        let current_era = 85;
+    }: {
+        SocialGuardians::<T>::prune_history(current_era);
+    }
+}
@@ -1,11 +1,20 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage, dispatch, ensure, traits::Get,
+    decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+    storage::{migration, unhashed, IterableStorageDoubleMap, IterableStorageMap, StoragePrefixedMap},
+    traits::{Currency, Get, ReservableCurrency},
+    weights::Weight,
 };
 use frame_system::ensure_signed;
 use pallet_staking::EraIndex;
+use sp_runtime::RuntimeDebug;
 use sp_std::prelude::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -13,22 +22,72 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The social token of a guardian and the era it registered in.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct Guardian<AssetId> {
+    pub social_token_id: AssetId,
+    pub since: EraIndex,
+}
+
+// A value placed in storage that represents the current version of the storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+    V1,
+    V2,
+    V3,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
 pub trait Config: frame_system::Config + pallet_assets::Config + pallet_staking::Config {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// The currency guardian deposits are reserved in.
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// The amount reserved from a guardian while it is registered.
+    type GuardianDeposit: Get<BalanceOf<Self>>;
+    /// The maximum number of guardians of a social token.
+    type MaxGuardians: Get<u32>;
+    /// The maximum number of history entries pruned, or of guardians registered before deposits
+    /// checked, in `on_initialize`.
+    type MaxPrunedPerBlock: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
     trait Store for Module<T: Config> as ValidatorRegistry {
-        /// Map from the controller account to the social token id.
-        GuardianOf get(fn champion_of): map hasher(blake2_128_concat) T::AccountId => T::AssetId;
-        /// Map from the social token id to the vector of controller accounts.
-        GuardiansOfSocialToken get(fn guardians_of_social_token): map hasher(blake2_128_concat) T::AssetId => Vec<T::AccountId>;
-        /// Current guardians (controller accounts).
-        Guardians get(fn guardians): Vec<T::AccountId>;
-        /// Map from the era index to the vector of controller accounts.
-        GuardianHistory get(fn champion_history): map hasher(blake2_128_concat) EraIndex => Vec<T::AccountId>;
-        /// Map from (era index, controller account) to the social token id.
+        /// Map from the controller account to its social token id and registration era.
+        GuardianOf get(fn champion_of): map hasher(blake2_128_concat) T::AccountId => Option<Guardian<T::AssetId>>;
+        /// Controller accounts of the guardians of a social token.
+        TokenGuardians get(fn is_guardian_of): double_map hasher(blake2_128_concat) T::AssetId, hasher(blake2_128_concat) T::AccountId => bool;
+        /// Number of guardians of a social token.
+        GuardianCount get(fn guardian_count): map hasher(blake2_128_concat) T::AssetId => u32;
+        /// Map from (era index, controller account) to the social token id, for the eras of
+        /// guardians that unregistered since.
         pub GuardianDetailHistory get(fn champion_detail_history): double_map hasher(twox_64_concat) EraIndex, hasher(twox_64_concat) T::AccountId => T::AssetId;
+        /// Oldest era of `GuardianDetailHistory` not pruned yet.
+        PruneEra get(fn prune_era): EraIndex;
+        /// Controller accounts the owner of a social token approved as its guardians.
+        pub Approvals get(fn is_approved): double_map hasher(blake2_128_concat) T::AssetId, hasher(blake2_128_concat) T::AccountId => bool;
+        /// Deposit reserved from a guardian when it registered.
+        pub Deposits get(fn deposit_of): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+        /// Raw key of `GuardianOf` after which the guardians registered before deposits are
+        /// removed, until all of them are.
+        LegacyGuardiansCursor: Option<Vec<u8>>;
+
+        /// Storage version of the pallet.
+        ///
+        /// New networks start with last version.
+        StorageVersion build(|_| Releases::V3): Releases;
     }
 }
 
@@ -40,6 +99,10 @@ decl_event!(
     {
         Registered(AccountId, AssetId),
         Unregistered(AccountId, AssetId),
+        /// The owner of the social token approved the controller as a guardian.
+        Approved(AssetId, AccountId),
+        /// The owner of the social token revoked the controller as a guardian.
+        Revoked(AssetId, AccountId),
     }
 );
 
@@ -47,6 +110,12 @@ decl_error! {
     pub enum Error for Module<T: Config> {
         AlreadyRegistered,
         NotFound,
+        /// Origin is not the owner of the social token.
+        NotAssetOwner,
+        /// The owner of the social token didn't approve the controller.
+        NotApproved,
+        /// The social token already has the maximum number of guardians.
+        TooManyGuardians,
     }
 }
 
@@ -56,70 +125,242 @@ decl_module! {
 
         fn deposit_event() = default;
 
-        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        const GuardianDeposit: BalanceOf<T> = T::GuardianDeposit::get();
+        const MaxGuardians: u32 = T::MaxGuardians::get();
+        const MaxPrunedPerBlock: u32 = T::MaxPrunedPerBlock::get();
+
+        fn on_runtime_upgrade() -> Weight {
+            Self::migrate_v1_to_v2().saturating_add(Self::migrate_v2_to_v3())
+        }
+
+        /// Register the sender as a guardian of a social token, reserving `GuardianDeposit`.
+        /// The owner of the token must have approved the sender, unless it is the owner.
+        #[weight = <T as Config>::WeightInfo::register()]
         pub fn register(origin, social_token_id: T::AssetId) -> dispatch::DispatchResult {
             let validator = ensure_signed(origin)?;
 
             <pallet_assets::Module<T>>::validate_asset_id(social_token_id)?;
             ensure!(!<GuardianOf<T>>::contains_key(&validator), Error::<T>::AlreadyRegistered);
+            ensure!(
+                Self::is_approved(social_token_id, &validator)
+                    || <pallet_assets::Module<T>>::owner(social_token_id).as_ref() == Some(&validator),
+                Error::<T>::NotApproved
+            );
+            ensure!(
+                Self::guardian_count(social_token_id) < T::MaxGuardians::get(),
+                Error::<T>::TooManyGuardians
+            );
 
-            <GuardianOf<T>>::insert(&validator, social_token_id);
-            <GuardiansOfSocialToken<T>>::mutate(social_token_id, |validators| {
-                validators.push(validator.clone())
-            });
-            <Guardians<T>>::mutate(|validators| {
-                validators.push(validator.clone())
-            });
+            let deposit = T::GuardianDeposit::get();
+            <T as Config>::Currency::reserve(&validator, deposit)?;
+            <Deposits<T>>::insert(&validator, deposit);
+            <Approvals<T>>::remove(social_token_id, &validator);
+            let since = pallet_staking::CurrentEra::get().unwrap_or(0);
+            <GuardianOf<T>>::insert(&validator, Guardian { social_token_id, since });
+            <TokenGuardians<T>>::insert(social_token_id, &validator, true);
+            <GuardianCount<T>>::mutate(social_token_id, |count| *count += 1);
 
             Self::deposit_event(RawEvent::Registered(validator, social_token_id));
             Ok(())
         }
 
-        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        /// Unregister the sender and return its deposit.
+        #[weight = <T as Config>::WeightInfo::unregister(pallet_staking::Module::<T>::history_depth())]
         pub fn unregister(origin) -> dispatch::DispatchResult {
             let validator = ensure_signed(origin)?;
-            let social_token_id = <GuardianOf<T>>::try_get(&validator)
-                .map_err(|_| Error::<T>::NotFound)?;
-
-            <GuardianOf<T>>::remove(&validator);
-            <GuardiansOfSocialToken<T>>::mutate(social_token_id, |validators| {
-                validators.retain(|account_id| account_id != &validator)
-            });
-            <Guardians<T>>::mutate(|validators| {
-                validators.retain(|account_id| account_id != &validator)
-            });
-
-            Self::deposit_event(RawEvent::Unregistered(validator, social_token_id));
+            let guardian = <GuardianOf<T>>::get(&validator).ok_or(Error::<T>::NotFound)?;
+
+            Self::remove_guardian(&validator, guardian.clone());
+
+            Self::deposit_event(RawEvent::Unregistered(validator, guardian.social_token_id));
             Ok(())
         }
 
-        fn on_finalize() {
-            let current_era = pallet_staking::CurrentEra::get().unwrap_or(0);
-            Self::clean_history(current_era);
-            Self::update_history(current_era);
+        /// Allow a controller to register as a guardian of a social token owned by the sender.
+        #[weight = <T as Config>::WeightInfo::approve()]
+        pub fn approve(origin, social_token_id: T::AssetId, validator: T::AccountId) -> dispatch::DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Self::ensure_owner(social_token_id, &owner)?;
+
+            <Approvals<T>>::insert(social_token_id, &validator, true);
+
+            Self::deposit_event(RawEvent::Approved(social_token_id, validator));
+            Ok(())
+        }
+
+        /// Withdraw the approval of a controller and unregister it if it is a guardian of the
+        /// social token owned by the sender, returning its deposit.
+        #[weight = <T as Config>::WeightInfo::revoke(pallet_staking::Module::<T>::history_depth())]
+        pub fn revoke(origin, social_token_id: T::AssetId, validator: T::AccountId) -> dispatch::DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Self::ensure_owner(social_token_id, &owner)?;
+
+            let guardian = <GuardianOf<T>>::get(&validator)
+                .filter(|guardian| guardian.social_token_id == social_token_id);
+            ensure!(guardian.is_some() || Self::is_approved(social_token_id, &validator), Error::<T>::NotFound);
+            <Approvals<T>>::remove(social_token_id, &validator);
+            if let Some(guardian) = guardian {
+                Self::remove_guardian(&validator, guardian);
+            }
+
+            Self::deposit_event(RawEvent::Revoked(social_token_id, validator));
+            Ok(())
         }
 
+        fn on_initialize() -> Weight {
+            let current_era = pallet_staking::CurrentEra::get().unwrap_or(0);
+            Self::prune_history(current_era).saturating_add(Self::remove_legacy_guardians())
+        }
     }
 }
 
 impl<T: Config> Module<T> {
-    fn clean_history(current_era: EraIndex) {
+    /// The social token the controller was a guardian of in the era.
+    pub fn guardian_at(era: EraIndex, controller: &T::AccountId) -> Option<T::AssetId> {
+        match <GuardianOf<T>>::get(controller) {
+            Some(guardian) if guardian.since <= era => Some(guardian.social_token_id),
+            _ => <GuardianDetailHistory<T>>::try_get(era, controller).ok(),
+        }
+    }
+
+    /// Controller accounts of the guardians of a social token.
+    pub fn guardians_of_social_token(social_token_id: T::AssetId) -> Vec<T::AccountId> {
+        <TokenGuardians<T>>::iter_prefix(social_token_id)
+            .map(|(validator, _)| validator)
+            .collect()
+    }
+
+    fn ensure_owner(social_token_id: T::AssetId, who: &T::AccountId) -> dispatch::DispatchResult {
+        let owner = <pallet_assets::Module<T>>::owner(social_token_id)
+            .ok_or(pallet_assets::Error::<T>::Unknown)?;
+        ensure!(&owner == who, Error::<T>::NotAssetOwner);
+        Ok(())
+    }
+
+    /// Removes a guardian, keeping the eras it was registered in within `HistoryDepth`.
+    /// Returns the number of eras kept.
+    fn remove_guardian(validator: &T::AccountId, guardian: Guardian<T::AssetId>) -> u32 {
+        let current_era = pallet_staking::CurrentEra::get().unwrap_or(0);
         let history_depth = pallet_staking::Module::<T>::history_depth();
-        match current_era.checked_sub(history_depth) {
-            Some(era) => {
-                <GuardianHistory<T>>::remove(era);
-                <GuardianDetailHistory<T>>::remove_prefix(era);
+        let first_era = guardian.since.max(current_era.saturating_sub(history_depth));
+        for era in first_era..=current_era {
+            <GuardianDetailHistory<T>>::insert(era, validator, guardian.social_token_id);
+        }
+
+        <T as Config>::Currency::unreserve(validator, <Deposits<T>>::take(validator));
+        <GuardianOf<T>>::remove(validator);
+        <TokenGuardians<T>>::remove(guardian.social_token_id, validator);
+        <GuardianCount<T>>::mutate(guardian.social_token_id, |count| *count = count.saturating_sub(1));
+        current_era.saturating_sub(first_era) + 1
+    }
+
+    /// Removes the history of eras older than `HistoryDepth`, at most `MaxPrunedPerBlock`
+    /// entries or eras per call.
+    fn prune_history(current_era: EraIndex) -> Weight {
+        let oldest_era = current_era.saturating_sub(pallet_staking::Module::<T>::history_depth());
+        let mut era = Self::prune_era();
+        if era >= oldest_era {
+            return <T as Config>::WeightInfo::prune_history(0);
+        }
+
+        let mut budget = T::MaxPrunedPerBlock::get() as usize;
+        let mut pruned = 0;
+        while era < oldest_era && budget > 0 {
+            let removed = <GuardianDetailHistory<T>>::drain_prefix(era).take(budget).count();
+            pruned += removed;
+            if removed == budget {
+                break;
             }
-            None => (),
+            budget -= removed + 1;
+            era += 1;
         }
+        <PruneEra>::put(era);
+
+        <T as Config>::WeightInfo::prune_history(pruned as u32)
     }
 
-    fn update_history(current_era: EraIndex) {
-        let guardians = <Guardians<T>>::get();
-        guardians.iter().for_each(|champion| {
-            let social_token_id = <GuardianOf<T>>::get(champion);
-            <GuardianDetailHistory<T>>::insert(current_era, champion, social_token_id)
+    /// Moves the guardian sets from vectors to maps and drops the per era vectors of guardians.
+    fn migrate_v1_to_v2() -> Weight {
+        if StorageVersion::get() != Releases::V1 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let current_era = pallet_staking::CurrentEra::get().unwrap_or(0);
+        let mut guardians = 0u64;
+        <GuardianOf<T>>::translate::<T::AssetId, _>(|validator, social_token_id| {
+            <TokenGuardians<T>>::insert(social_token_id, &validator, true);
+            <GuardianCount<T>>::mutate(social_token_id, |count| *count += 1);
+            guardians += 1;
+            Some(Guardian { social_token_id, since: current_era })
         });
-        <GuardianHistory<T>>::insert(current_era, guardians);
+        migration::remove_storage_prefix(b"ValidatorRegistry", b"GuardiansOfSocialToken", &[]);
+        migration::remove_storage_prefix(b"ValidatorRegistry", b"Guardians", &[]);
+        migration::remove_storage_prefix(b"ValidatorRegistry", b"GuardianHistory", &[]);
+
+        // `GuardianDetailHistory` of the older eras was removed by the previous version
+        let history_depth = pallet_staking::Module::<T>::history_depth();
+        <PruneEra>::put(current_era.saturating_sub(history_depth));
+        StorageVersion::put(Releases::V2);
+
+        T::DbWeight::get().reads_writes(2 + guardians * 2, 5 + guardians * 3)
+    }
+
+    /// Starts removing the guardians registered without the approval of the owner and a
+    /// deposit, in batches from `on_initialize`.
+    fn migrate_v2_to_v3() -> Weight {
+        if StorageVersion::get() != Releases::V2 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        LegacyGuardiansCursor::put(<GuardianOf<T>>::final_prefix().to_vec());
+        StorageVersion::put(Releases::V3);
+
+        T::DbWeight::get().reads_writes(1, 2)
+    }
+
+    /// Removes the guardians without a deposit among the next `MaxPrunedPerBlock` ones,
+    /// keeping the eras they were registered in.
+    fn remove_legacy_guardians() -> Weight {
+        let mut key = match LegacyGuardiansCursor::get() {
+            Some(key) => key,
+            None => return T::DbWeight::get().reads(1),
+        };
+        let prefix = <GuardianOf<T>>::final_prefix();
+        let (mut reads, mut writes) = (1u64, 1u64);
+        let mut checked = 0;
+        let mut done = false;
+        while checked < T::MaxPrunedPerBlock::get() {
+            let next = match sp_io::storage::next_key(&key).filter(|next| next.starts_with(&prefix)) {
+                Some(next) => next,
+                None => {
+                    done = true;
+                    break;
+                }
+            };
+            checked += 1;
+            reads += 2;
+            // the key ends with the blake2_128 hash of the account followed by the account
+            let validator = next
+                .get(prefix.len() + 16..)
+                .and_then(|mut account| T::AccountId::decode(&mut account).ok());
+            let guardian = unhashed::get::<Guardian<T::AssetId>>(&next);
+            if let (Some(validator), Some(guardian)) = (validator, guardian) {
+                if !<Deposits<T>>::contains_key(&validator) {
+                    let social_token_id = guardian.social_token_id;
+                    let eras = Self::remove_guardian(&validator, guardian);
+                    reads += 4;
+                    writes += 5 + eras as u64;
+                    Self::deposit_event(RawEvent::Unregistered(validator, social_token_id));
+                }
+            }
+            key = next;
+        }
+
+        if done {
+            LegacyGuardiansCursor::kill();
+        } else {
+            LegacyGuardiansCursor::put(key);
+        }
+        T::DbWeight::get().reads_writes(reads, writes)
     }
 }
@@ -76,6 +76,7 @@ parameter_types! {
     pub const StringLimit: u32 = 50;
     pub const MetadataDepositBase: u64 = 1;
     pub const MetadataDepositPerByte: u64 = 1;
+    pub const ApprovalDeposit: u64 = 1;
 }
 
 impl pallet_assets::Config for Test {
@@ -89,6 +90,7 @@ impl pallet_assets::Config for Test {
     type StringLimit = StringLimit;
     type MetadataDepositBase = MetadataDepositBase;
     type MetadataDepositPerByte = MetadataDepositPerByte;
+    type ApprovalDeposit = ApprovalDeposit;
     type WeightInfo = ();
 }
 
@@ -193,8 +195,19 @@ impl pallet_staking::Config for Test {
     type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const GuardianDeposit: u64 = 10;
+    pub const MaxGuardians: u32 = 2;
+    pub const MaxPrunedPerBlock: u32 = 3;
+}
+
 impl pallet_social_guardians::Config for Test {
     type Event = Event;
+    type Currency = Balances;
+    type GuardianDeposit = GuardianDeposit;
+    type MaxGuardians = MaxGuardians;
+    type MaxPrunedPerBlock = MaxPrunedPerBlock;
+    type WeightInfo = ();
 }
 
 pub type ValidatorRegistry = pallet_social_guardians::Module<Test>;
@@ -212,6 +225,11 @@ impl ExtBuilder {
         let mut t = frame_system::GenesisConfig::default()
             .build_storage::<Test>()
             .unwrap();
+        pallet_balances::GenesisConfig::<Test> {
+            balances: (1..=4).map(|account| (account, 100)).collect(),
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
         let (owner, admin, max_zombies, min_balance) = (1, 1, 0, 100);
         pallet_assets::GenesisConfig::<Test> {
             assets: (1..=17)
@@ -1,33 +1,44 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+    mock::*, Error, Guardian, GuardianCount, GuardianOf, LegacyGuardiansCursor, RawEvent, Releases,
+    StorageVersion, TokenGuardians,
+};
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::migration::{get_storage_value, put_storage_value},
+    traits::{OnInitialize, OnRuntimeUpgrade},
+    Blake2_128Concat, StorageHasher,
+};
 
 #[test]
 fn registration_and_unregistration_should_work() {
     new_test_ext().execute_with(|| {
-        assert_eq!(ValidatorRegistry::champion_of(1), 0);
+        assert_eq!(ValidatorRegistry::champion_of(1), None);
         assert_eq!(
             ValidatorRegistry::guardians_of_social_token(10),
             Vec::<u64>::new()
         );
-        assert_eq!(ValidatorRegistry::guardians(), Vec::<u64>::new());
+        assert_eq!(ValidatorRegistry::guardian_count(10), 0);
         assert_ok!(ValidatorRegistry::register(Origin::signed(1), 10));
-        assert_eq!(ValidatorRegistry::champion_of(1), 10);
+        assert_eq!(ValidatorRegistry::champion_of(1), Some(Guardian { social_token_id: 10, since: 0 }));
         assert_eq!(ValidatorRegistry::guardians_of_social_token(10), vec![1]);
-        assert_eq!(ValidatorRegistry::guardians(), vec![1]);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 1);
+        assert_ok!(ValidatorRegistry::approve(Origin::signed(1), 10, 2));
         assert_ok!(ValidatorRegistry::register(Origin::signed(2), 10));
-        assert_eq!(ValidatorRegistry::champion_of(2), 10);
-        assert_eq!(ValidatorRegistry::guardians_of_social_token(10), vec![1, 2]);
-        assert_eq!(ValidatorRegistry::guardians(), vec![1, 2]);
+        assert_eq!(ValidatorRegistry::champion_of(2), Some(Guardian { social_token_id: 10, since: 0 }));
+        assert!(ValidatorRegistry::is_guardian_of(10, 1));
+        assert!(ValidatorRegistry::is_guardian_of(10, 2));
+        assert_eq!(ValidatorRegistry::guardian_count(10), 2);
         assert_ok!(ValidatorRegistry::unregister(Origin::signed(2)));
         assert_eq!(ValidatorRegistry::guardians_of_social_token(10), vec![1]);
-        assert_eq!(ValidatorRegistry::guardians(), vec![1]);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 1);
         assert_ok!(ValidatorRegistry::unregister(Origin::signed(1)));
-        assert_eq!(ValidatorRegistry::champion_of(1), 0);
+        assert_eq!(ValidatorRegistry::champion_of(1), None);
         assert_eq!(
             ValidatorRegistry::guardians_of_social_token(10),
             Vec::<u64>::new()
         );
-        assert_eq!(ValidatorRegistry::guardians(), Vec::<u64>::new());
+        assert_eq!(ValidatorRegistry::guardian_count(10), 0);
     });
 }
 
@@ -35,15 +46,15 @@ fn registration_and_unregistration_should_work() {
 fn re_registration_should_not_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(ValidatorRegistry::register(Origin::signed(1), 10));
-        assert_eq!(ValidatorRegistry::champion_of(1), 10);
+        assert_eq!(ValidatorRegistry::champion_of(1), Some(Guardian { social_token_id: 10, since: 0 }));
         assert_eq!(ValidatorRegistry::guardians_of_social_token(10), vec![1]);
-        assert_eq!(ValidatorRegistry::guardians(), vec![1]);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 1);
         assert_noop!(
             ValidatorRegistry::register(Origin::signed(1), 11),
             Error::<Test>::AlreadyRegistered
         );
         assert_eq!(ValidatorRegistry::guardians_of_social_token(10), vec![1]);
-        assert_eq!(ValidatorRegistry::guardians(), vec![1]);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 1);
     });
 }
 
@@ -70,3 +81,202 @@ fn unregistration_for_unregistered_should_not_work() {
         );
     });
 }
+
+#[test]
+fn registration_should_need_approval_and_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ValidatorRegistry::register(Origin::signed(2), 10),
+            Error::<Test>::NotApproved
+        );
+        assert_noop!(
+            ValidatorRegistry::approve(Origin::signed(2), 10, 2),
+            Error::<Test>::NotAssetOwner
+        );
+
+        assert_ok!(ValidatorRegistry::approve(Origin::signed(1), 10, 2));
+        assert!(ValidatorRegistry::is_approved(10, 2));
+        assert_ok!(ValidatorRegistry::register(Origin::signed(2), 10));
+        assert!(!ValidatorRegistry::is_approved(10, 2));
+        assert_eq!(Balances::reserved_balance(2), 10);
+        assert_eq!(ValidatorRegistry::deposit_of(2), 10);
+
+        assert_ok!(ValidatorRegistry::unregister(Origin::signed(2)));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(ValidatorRegistry::deposit_of(2), 0);
+    });
+}
+
+#[test]
+fn registration_over_max_guardians_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorRegistry::register(Origin::signed(1), 10));
+        assert_ok!(ValidatorRegistry::approve(Origin::signed(1), 10, 2));
+        assert_ok!(ValidatorRegistry::approve(Origin::signed(1), 10, 3));
+        assert_ok!(ValidatorRegistry::register(Origin::signed(2), 10));
+        assert_noop!(
+            ValidatorRegistry::register(Origin::signed(3), 10),
+            Error::<Test>::TooManyGuardians
+        );
+    });
+}
+
+#[test]
+fn revoke_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorRegistry::approve(Origin::signed(1), 10, 2));
+        assert_ok!(ValidatorRegistry::approve(Origin::signed(1), 10, 3));
+        assert_ok!(ValidatorRegistry::register(Origin::signed(2), 10));
+
+        assert_noop!(
+            ValidatorRegistry::revoke(Origin::signed(2), 10, 2),
+            Error::<Test>::NotAssetOwner
+        );
+        assert_noop!(
+            ValidatorRegistry::revoke(Origin::signed(1), 10, 4),
+            Error::<Test>::NotFound
+        );
+
+        assert_ok!(ValidatorRegistry::revoke(Origin::signed(1), 10, 2));
+        assert_eq!(ValidatorRegistry::champion_of(2), None);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 0);
+        assert_eq!(Balances::reserved_balance(2), 0);
+
+        assert_ok!(ValidatorRegistry::revoke(Origin::signed(1), 10, 3));
+        assert_noop!(
+            ValidatorRegistry::register(Origin::signed(3), 10),
+            Error::<Test>::NotApproved
+        );
+    });
+}
+
+#[test]
+fn guardian_at_should_keep_history() {
+    new_test_ext().execute_with(|| {
+        pallet_staking::CurrentEra::put(1);
+        assert_ok!(ValidatorRegistry::register(Origin::signed(1), 10));
+        assert_eq!(ValidatorRegistry::guardian_at(0, &1), None);
+        assert_eq!(ValidatorRegistry::guardian_at(1, &1), Some(10));
+
+        pallet_staking::CurrentEra::put(3);
+        assert_eq!(ValidatorRegistry::guardian_at(2, &1), Some(10));
+        assert_ok!(ValidatorRegistry::unregister(Origin::signed(1)));
+        assert_eq!(ValidatorRegistry::guardian_at(0, &1), None);
+        assert_eq!(ValidatorRegistry::guardian_at(1, &1), Some(10));
+        assert_eq!(ValidatorRegistry::guardian_at(3, &1), Some(10));
+        assert_eq!(ValidatorRegistry::guardian_at(4, &1), None);
+    });
+}
+
+#[test]
+fn history_should_be_pruned_in_batches() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorRegistry::approve(Origin::signed(1), 10, 2));
+        assert_ok!(ValidatorRegistry::register(Origin::signed(1), 10));
+        assert_ok!(ValidatorRegistry::register(Origin::signed(2), 10));
+        pallet_staking::CurrentEra::put(1);
+        assert_ok!(ValidatorRegistry::unregister(Origin::signed(1)));
+        assert_ok!(ValidatorRegistry::unregister(Origin::signed(2)));
+
+        // eras 0 and 1 are within the history depth of 84 eras
+        ValidatorRegistry::on_initialize(1);
+        assert_eq!(ValidatorRegistry::guardian_at(0, &1), Some(10));
+
+        // four entries are out of the history depth, at most three are pruned per block
+        pallet_staking::CurrentEra::put(86);
+        ValidatorRegistry::on_initialize(2);
+        assert_eq!(ValidatorRegistry::prune_era(), 1);
+        ValidatorRegistry::on_initialize(3);
+        assert_eq!(ValidatorRegistry::prune_era(), 2);
+        for validator in &[1, 2] {
+            assert_eq!(ValidatorRegistry::guardian_at(0, validator), None);
+            assert_eq!(ValidatorRegistry::guardian_at(1, validator), None);
+        }
+    });
+}
+
+#[test]
+fn guardians_without_deposit_should_be_removed_after_upgrade() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::put(Releases::V2);
+        assert_ok!(ValidatorRegistry::register(Origin::signed(1), 10));
+        // registered before approvals and deposits
+        for validator in 2..5u64 {
+            <GuardianOf<Test>>::insert(validator, Guardian { social_token_id: 10, since: 0 });
+            <TokenGuardians<Test>>::insert(10u32, validator, true);
+            <GuardianCount<Test>>::mutate(10u32, |count| *count += 1);
+        }
+
+        pallet_staking::CurrentEra::put(1);
+        ValidatorRegistry::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get(), Releases::V3);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 4);
+
+        // at most three guardians are checked per block
+        ValidatorRegistry::on_initialize(1);
+        assert!(LegacyGuardiansCursor::get().is_some());
+        assert!(ValidatorRegistry::guardian_count(10) < 4);
+        ValidatorRegistry::on_initialize(2);
+        assert_eq!(LegacyGuardiansCursor::get(), None);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 1);
+        assert_eq!(ValidatorRegistry::guardians_of_social_token(10), vec![1]);
+        assert_eq!(Balances::reserved_balance(1), 10);
+        assert_eq!(ValidatorRegistry::champion_of(2), None);
+        assert!(!ValidatorRegistry::is_guardian_of(10, 2));
+        assert_eq!(ValidatorRegistry::guardian_at(1, &2), Some(10));
+        assert!(System::events().iter().any(|record| record.event ==
+            Event::pallet_social_guardians(RawEvent::Unregistered(2, 10))));
+
+        // it needs the approval of the owner to register again
+        assert_noop!(
+            ValidatorRegistry::register(Origin::signed(2), 10),
+            Error::<Test>::NotApproved
+        );
+    });
+}
+
+#[test]
+fn guardians_should_be_migrated_from_v1() {
+    new_test_ext().execute_with(|| {
+        let put = |item: &[u8], key: Vec<u8>, value: Vec<u64>| {
+            put_storage_value(b"ValidatorRegistry", item, &Blake2_128Concat::hash(&key), value)
+        };
+        for (validator, social_token_id) in vec![(2u64, 10u32), (3, 11)] {
+            put_storage_value(
+                b"ValidatorRegistry",
+                b"GuardianOf",
+                &Blake2_128Concat::hash(&validator.encode()),
+                social_token_id,
+            );
+            put(b"GuardiansOfSocialToken", social_token_id.encode(), vec![validator]);
+        }
+        put_storage_value(b"ValidatorRegistry", b"Guardians", &[], vec![2u64, 3]);
+        put(b"GuardianHistory", 89u32.encode(), vec![2, 3]);
+
+        pallet_staking::CurrentEra::put(90);
+        ValidatorRegistry::migrate_v1_to_v2();
+        assert_eq!(StorageVersion::get(), Releases::V2);
+        assert_eq!(ValidatorRegistry::champion_of(2), Some(Guardian { social_token_id: 10, since: 90 }));
+        assert_eq!(ValidatorRegistry::champion_of(3), Some(Guardian { social_token_id: 11, since: 90 }));
+        assert_eq!(ValidatorRegistry::guardians_of_social_token(10), vec![2]);
+        assert_eq!(ValidatorRegistry::guardians_of_social_token(11), vec![3]);
+        assert_eq!(ValidatorRegistry::guardian_count(10), 1);
+        assert_eq!(ValidatorRegistry::guardian_count(11), 1);
+        // eras older than the history depth of 84 eras were pruned by the previous version
+        assert_eq!(ValidatorRegistry::prune_era(), 6);
+
+        let get = |item: &[u8], key: Vec<u8>| {
+            get_storage_value::<Vec<u64>>(b"ValidatorRegistry", item, &Blake2_128Concat::hash(&key))
+        };
+        assert_eq!(get(b"GuardiansOfSocialToken", 10u32.encode()), None);
+        assert_eq!(get(b"GuardianHistory", 89u32.encode()), None);
+        assert_eq!(get_storage_value::<Vec<u64>>(b"ValidatorRegistry", b"Guardians", &[]), None);
+
+        // they registered without approval and deposit
+        ValidatorRegistry::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get(), Releases::V3);
+        ValidatorRegistry::on_initialize(1);
+        assert_eq!(ValidatorRegistry::champion_of(2), None);
+        assert_eq!(ValidatorRegistry::guardian_at(90, &2), Some(10));
+    });
+}
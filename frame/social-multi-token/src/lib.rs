@@ -0,0 +1,319 @@
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Semi-fungible tokens in the style of ERC-1155: every token id has its own balances, so editions
+//! and in-game items don't need a separate nft per copy.
+//!
+//! Token ids with the top bit set are created on this chain against a deposit, the other ids are
+//! reserved for the tokens arriving over the bridge.
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::{Currency, Get, ReservableCurrency}, transactional, Parameter,
+};
+use frame_system::{self as system, ensure_signed};
+use sp_core::U256;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Member, Zero},
+    RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub type TokenId = U256;
+
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as system::Config>::AccountId>>::Balance;
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct MultiToken<AccountId, Balance> {
+    /// Account allowed to mint and to change the uri
+    pub creator: AccountId,
+    pub uri: Vec<u8>,
+    pub total_supply: Balance,
+}
+
+pub trait Config: system::Config {
+    type Event: From<Event<Self>> + Into<<Self as system::Config>::Event>;
+    /// The units in which token balances are recorded.
+    type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
+    /// The maximum length of a token uri.
+    type UriLimit: Get<u32>;
+    /// The maximum number of tokens in a batch transfer.
+    type MaxBatchSize: Get<u32>;
+    /// The currency in which the deposit for creating a token is reserved.
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// The deposit reserved from the creator of a token id until the token is destroyed.
+    type CreateDeposit: Get<BalanceOf<Self>>;
+}
+
+decl_event! {
+    pub enum Event<T>
+    where
+        <T as system::Config>::AccountId,
+        Balance = <T as Config>::Balance,
+    {
+        /// New token id created by the creator
+        Created(TokenId, AccountId),
+        /// Token id without supply destroyed by the creator
+        Destroyed(TokenId, AccountId),
+        /// Tokens minted to the account
+        Minted(TokenId, AccountId, Balance),
+        /// Tokens burned from the account
+        Burned(TokenId, AccountId, Balance),
+        /// Tokens moved by the operator (operator, from, to, id, amount)
+        TransferSingle(AccountId, AccountId, AccountId, TokenId, Balance),
+        /// Batch of tokens moved by the operator (operator, from, to, ids and amounts)
+        TransferBatch(AccountId, AccountId, AccountId, Vec<(TokenId, Balance)>),
+        /// Operator approval of an owner changed (owner, operator, approved)
+        ApprovalForAll(AccountId, AccountId, bool),
+        /// Uri of the token changed
+        URI(TokenId, Vec<u8>),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// Token id already created
+        TokenAlreadyExists,
+        /// Token id not recognized
+        TokenNotExists,
+        /// Origin is not the creator of the token
+        NotCreator,
+        /// Origin is neither the owner nor an approved operator
+        NotApproved,
+        /// Balance too low
+        BalanceLow,
+        /// Balance or supply would overflow
+        Overflow,
+        /// Uri longer than the limit
+        UriTooLong,
+        /// Batch longer than the limit
+        BatchTooLarge,
+        /// Token id is reserved for the bridge or for tokens created on this chain
+        ReservedId,
+        /// Token still has supply
+        TokenInUse,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as SocialMultiToken {
+        /// Maps token id to its creator, uri and supply
+        pub Tokens get(fn tokens): map hasher(blake2_128_concat) TokenId => Option<MultiToken<T::AccountId, T::Balance>>;
+        /// Balance of an account in a token id
+        pub Balances get(fn balance_of): double_map hasher(blake2_128_concat) TokenId,
+            hasher(blake2_128_concat) T::AccountId => T::Balance;
+        /// Operators allowed to move all tokens of an owner
+        pub OperatorApprovals get(fn is_approved_for_all): double_map hasher(blake2_128_concat) T::AccountId,
+            hasher(blake2_128_concat) T::AccountId => bool;
+        /// Deposit reserved from the creator of a token id
+        pub Deposits get(fn deposit_of): map hasher(blake2_128_concat) TokenId => BalanceOf<T>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+        fn deposit_event() = default;
+
+        const UriLimit: u32 = T::UriLimit::get();
+        const MaxBatchSize: u32 = T::MaxBatchSize::get();
+        const CreateDeposit: BalanceOf<T> = T::CreateDeposit::get();
+
+        /// Create a token id owned by the sender, reserving the `CreateDeposit`
+        ///
+        /// The id must have its top bit set, the other ids are reserved for the bridge.
+        #[weight = 195_000_000]
+        #[transactional]
+        pub fn create(origin, id: TokenId, uri: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(!Self::is_bridged_id(id), Error::<T>::ReservedId);
+            Self::create_token(sender.clone(), id, uri)?;
+            let deposit = T::CreateDeposit::get();
+            T::Currency::reserve(&sender, deposit)?;
+            Deposits::<T>::insert(id, deposit);
+
+            Ok(())
+        }
+
+        /// Remove a token id without supply and return the deposit, only the creator can destroy it
+        #[weight = 195_000_000]
+        pub fn destroy(origin, id: TokenId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let token = Self::tokens(id).ok_or(Error::<T>::TokenNotExists)?;
+            ensure!(token.creator == sender, Error::<T>::NotCreator);
+            ensure!(token.total_supply.is_zero(), Error::<T>::TokenInUse);
+            Tokens::<T>::remove(id);
+            T::Currency::unreserve(&sender, Deposits::<T>::take(id));
+            Self::deposit_event(RawEvent::Destroyed(id, sender));
+
+            Ok(())
+        }
+
+        /// Mint new copies of a token, only the creator can mint
+        #[weight = 195_000_000]
+        pub fn mint(origin, to: T::AccountId, id: TokenId, amount: T::Balance) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let token = Self::tokens(id).ok_or(Error::<T>::TokenNotExists)?;
+            ensure!(token.creator == sender, Error::<T>::NotCreator);
+            Self::mint_tokens(to, id, amount)?;
+
+            Ok(())
+        }
+
+        /// Burn copies of a token held by the sender
+        #[weight = 195_000_000]
+        pub fn burn(origin, id: TokenId, amount: T::Balance) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::burn_tokens(sender, id, amount)?;
+
+            Ok(())
+        }
+
+        /// Replace the uri of a token, only the creator can change it
+        #[weight = 195_000_000]
+        pub fn set_uri(origin, id: TokenId, uri: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(uri.len() as u32 <= T::UriLimit::get(), Error::<T>::UriTooLong);
+            Tokens::<T>::try_mutate(id, |maybe_token| -> DispatchResult {
+                let token = maybe_token.as_mut().ok_or(Error::<T>::TokenNotExists)?;
+                ensure!(token.creator == sender, Error::<T>::NotCreator);
+                token.uri = uri.clone();
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::URI(id, uri));
+
+            Ok(())
+        }
+
+        /// Allow or disallow an operator to move all tokens of the sender
+        #[weight = 195_000_000]
+        pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            if approved {
+                OperatorApprovals::<T>::insert(&sender, &operator, true);
+            } else {
+                OperatorApprovals::<T>::remove(&sender, &operator);
+            }
+            Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+
+            Ok(())
+        }
+
+        /// Move tokens of `from`, the sender is the owner or an approved operator
+        #[weight = 195_000_000]
+        pub fn safe_transfer_from(origin, from: T::AccountId, to: T::AccountId, id: TokenId, amount: T::Balance) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::ensure_approved(&from, &sender)?;
+            Self::transfer_tokens(&from, &to, id, amount)?;
+            Self::deposit_event(RawEvent::TransferSingle(sender, from, to, id, amount));
+
+            Ok(())
+        }
+
+        /// Move several tokens of `from` at once, all or nothing
+        #[weight = 195_000_000]
+        #[transactional]
+        pub fn safe_batch_transfer_from(origin, from: T::AccountId, to: T::AccountId, tokens: Vec<(TokenId, T::Balance)>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(tokens.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+            Self::ensure_approved(&from, &sender)?;
+            for (id, amount) in tokens.iter() {
+                Self::transfer_tokens(&from, &to, *id, *amount)?;
+            }
+            Self::deposit_event(RawEvent::TransferBatch(sender, from, to, tokens));
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Whether the id is reserved for tokens arriving over the bridge.
+    pub fn is_bridged_id(id: TokenId) -> bool {
+        !id.bit(255)
+    }
+
+    /// Create a token id arriving over the bridge, without a deposit.
+    pub fn create_bridged_token(creator: T::AccountId, id: TokenId, uri: Vec<u8>) -> DispatchResult {
+        ensure!(Self::is_bridged_id(id), Error::<T>::ReservedId);
+        Self::create_token(creator, id, uri)
+    }
+
+    fn create_token(creator: T::AccountId, id: TokenId, uri: Vec<u8>) -> DispatchResult {
+        ensure!(!Tokens::<T>::contains_key(id), Error::<T>::TokenAlreadyExists);
+        ensure!(uri.len() as u32 <= T::UriLimit::get(), Error::<T>::UriTooLong);
+
+        let token = MultiToken { creator: creator.clone(), uri, total_supply: Zero::zero() };
+        Tokens::<T>::insert(id, token);
+        Self::deposit_event(RawEvent::Created(id, creator));
+        Ok(())
+    }
+
+    /// Add copies of an existing token to an account.
+    pub fn mint_tokens(to: T::AccountId, id: TokenId, amount: T::Balance) -> DispatchResult {
+        Tokens::<T>::try_mutate(id, |maybe_token| -> DispatchResult {
+            let token = maybe_token.as_mut().ok_or(Error::<T>::TokenNotExists)?;
+            let balance = Self::balance_of(id, &to).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+            token.total_supply = token.total_supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+            Self::set_balance(id, &to, balance);
+            Ok(())
+        })?;
+        Self::deposit_event(RawEvent::Minted(id, to, amount));
+        Ok(())
+    }
+
+    /// Remove copies of a token from an account.
+    pub fn burn_tokens(from: T::AccountId, id: TokenId, amount: T::Balance) -> DispatchResult {
+        Tokens::<T>::try_mutate(id, |maybe_token| -> DispatchResult {
+            let token = maybe_token.as_mut().ok_or(Error::<T>::TokenNotExists)?;
+            let balance = Self::balance_of(id, &from).checked_sub(&amount).ok_or(Error::<T>::BalanceLow)?;
+            token.total_supply = token.total_supply.saturating_sub(amount);
+            Self::set_balance(id, &from, balance);
+            Ok(())
+        })?;
+        Self::deposit_event(RawEvent::Burned(id, from, amount));
+        Ok(())
+    }
+
+    /// Move copies of a token between accounts without any approval check.
+    pub fn transfer_tokens(from: &T::AccountId, to: &T::AccountId, id: TokenId, amount: T::Balance) -> DispatchResult {
+        ensure!(Tokens::<T>::contains_key(id), Error::<T>::TokenNotExists);
+        let from_balance = Self::balance_of(id, from).checked_sub(&amount).ok_or(Error::<T>::BalanceLow)?;
+        if from == to {
+            return Ok(());
+        }
+        let to_balance = Self::balance_of(id, to).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+
+        Self::set_balance(id, from, from_balance);
+        Self::set_balance(id, to, to_balance);
+        Ok(())
+    }
+
+    fn ensure_approved(owner: &T::AccountId, operator: &T::AccountId) -> DispatchResult {
+        ensure!(owner == operator || Self::is_approved_for_all(owner, operator), Error::<T>::NotApproved);
+        Ok(())
+    }
+
+    fn set_balance(id: TokenId, who: &T::AccountId, balance: T::Balance) {
+        if balance.is_zero() {
+            Balances::<T>::remove(id, who);
+        } else {
+            Balances::<T>::insert(id, who, balance);
+        }
+    }
+}
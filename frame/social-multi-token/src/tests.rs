@@ -0,0 +1,145 @@
+use crate::{mock::*, Error};
+use frame_support::{assert_noop, assert_ok};
+use sp_core::U256;
+use super::*;
+
+/// Id of a token created on this chain
+fn local_id(id: u64) -> TokenId {
+	(U256::one() << 255) + U256::from(id)
+}
+
+fn create_edition(id: u64, amount: u64) -> TokenId {
+	let id = local_id(id);
+	assert_ok!(MultiToken::create(Origin::signed(CREATOR), id, b"ipfs://badge".to_vec()));
+	assert_ok!(MultiToken::mint(Origin::signed(CREATOR), ALICE, id, amount));
+	id
+}
+
+#[test]
+fn create_and_mint_should_work() {
+	new_test_ext().execute_with(|| {
+		let id = create_edition(1, 500);
+		assert_eq!(MultiToken::balance_of(id, ALICE), 500);
+		assert_eq!(MultiToken::tokens(id).unwrap().total_supply, 500);
+
+		assert_noop!(
+			MultiToken::create(Origin::signed(ALICE), id, vec![]),
+			Error::<Test>::TokenAlreadyExists
+		);
+		assert_noop!(
+			MultiToken::create(Origin::signed(ALICE), local_id(2), vec![0; 17]),
+			Error::<Test>::UriTooLong
+		);
+		assert_noop!(
+			MultiToken::mint(Origin::signed(ALICE), ALICE, id, 1),
+			Error::<Test>::NotCreator
+		);
+		assert_noop!(
+			MultiToken::mint(Origin::signed(CREATOR), ALICE, local_id(2), 1),
+			Error::<Test>::TokenNotExists
+		);
+
+		assert_ok!(MultiToken::burn(Origin::signed(ALICE), id, 100));
+		assert_eq!(MultiToken::balance_of(id, ALICE), 400);
+		assert_eq!(MultiToken::tokens(id).unwrap().total_supply, 400);
+		assert_noop!(MultiToken::burn(Origin::signed(ALICE), id, 401), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn create_should_reserve_a_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			MultiToken::create(Origin::signed(CREATOR), U256::from(1), vec![]),
+			Error::<Test>::ReservedId
+		);
+		assert_noop!(
+			MultiToken::create(Origin::signed(BOB), local_id(1), vec![]),
+			pallet_balances::Error::<Test, _>::InsufficientBalance
+		);
+
+		let id = create_edition(1, 5);
+		assert_eq!(NativeBalances::reserved_balance(CREATOR), 10);
+		assert_eq!(MultiToken::deposit_of(id), 10);
+
+		assert_noop!(MultiToken::destroy(Origin::signed(ALICE), id), Error::<Test>::NotCreator);
+		assert_noop!(MultiToken::destroy(Origin::signed(CREATOR), id), Error::<Test>::TokenInUse);
+		assert_ok!(MultiToken::burn(Origin::signed(ALICE), id, 5));
+		assert_ok!(MultiToken::destroy(Origin::signed(CREATOR), id));
+		assert_eq!(MultiToken::tokens(id), None);
+		assert_eq!(NativeBalances::reserved_balance(CREATOR), 0);
+
+		// the bridge creates the other ids without a deposit
+		assert_noop!(
+			MultiToken::create_bridged_token(ALICE, local_id(2), vec![]),
+			Error::<Test>::ReservedId
+		);
+		assert_ok!(MultiToken::create_bridged_token(ALICE, U256::from(2), vec![]));
+		assert_eq!(NativeBalances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn set_uri_should_work() {
+	new_test_ext().execute_with(|| {
+		let id = create_edition(1, 1);
+		assert_noop!(MultiToken::set_uri(Origin::signed(ALICE), id, vec![1]), Error::<Test>::NotCreator);
+		assert_ok!(MultiToken::set_uri(Origin::signed(CREATOR), id, vec![1]));
+		assert_eq!(MultiToken::tokens(id).unwrap().uri, vec![1]);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_multi_token(RawEvent::URI(id, vec![1]))));
+	});
+}
+
+#[test]
+fn transfer_with_approval_should_work() {
+	new_test_ext().execute_with(|| {
+		let id = create_edition(1, 500);
+		assert_ok!(MultiToken::safe_transfer_from(Origin::signed(ALICE), ALICE, BOB, id, 200));
+		assert_eq!(MultiToken::balance_of(id, ALICE), 300);
+		assert_eq!(MultiToken::balance_of(id, BOB), 200);
+
+		assert_noop!(
+			MultiToken::safe_transfer_from(Origin::signed(BOB), ALICE, BOB, id, 1),
+			Error::<Test>::NotApproved
+		);
+		assert_ok!(MultiToken::set_approval_for_all(Origin::signed(ALICE), BOB, true));
+		assert_ok!(MultiToken::safe_transfer_from(Origin::signed(BOB), ALICE, BOB, id, 300));
+		assert_eq!(MultiToken::balance_of(id, BOB), 500);
+		assert!(!Balances::<Test>::contains_key(id, ALICE));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_multi_token(RawEvent::TransferSingle(BOB, ALICE, BOB, id, 300))));
+
+		assert_ok!(MultiToken::set_approval_for_all(Origin::signed(BOB), ALICE, true));
+		assert_ok!(MultiToken::set_approval_for_all(Origin::signed(BOB), ALICE, false));
+		assert_noop!(
+			MultiToken::safe_transfer_from(Origin::signed(ALICE), BOB, ALICE, id, 1),
+			Error::<Test>::NotApproved
+		);
+		assert_noop!(
+			MultiToken::safe_transfer_from(Origin::signed(BOB), BOB, ALICE, id, 501),
+			Error::<Test>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn batch_transfer_should_be_atomic() {
+	new_test_ext().execute_with(|| {
+		let badge = create_edition(1, 500);
+		let sword = create_edition(2, 3);
+
+		assert_ok!(MultiToken::safe_batch_transfer_from(Origin::signed(ALICE), ALICE, BOB, vec![(badge, 100), (sword, 1)]));
+		assert_eq!(MultiToken::balance_of(badge, BOB), 100);
+		assert_eq!(MultiToken::balance_of(sword, BOB), 1);
+
+		assert_noop!(
+			MultiToken::safe_batch_transfer_from(Origin::signed(ALICE), ALICE, BOB, vec![(badge, 100), (sword, 3)]),
+			Error::<Test>::BalanceLow
+		);
+		assert_noop!(
+			MultiToken::safe_batch_transfer_from(Origin::signed(ALICE), ALICE, BOB, vec![(badge, 1); 4]),
+			Error::<Test>::BatchTooLarge
+		);
+	});
+}
@@ -0,0 +1,33 @@
+//! Runtime API definition required by Social NFT RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding Social NFT ownership and marketplace queries.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+pub use pallet_social_nft::{NftId, TokenDetails};
+
+sp_api::decl_runtime_apis! {
+	/// The API to query tokens, owners and the market of the social nft module.
+	pub trait SocialNftApi<AccountId, AssetId, Balance, Moment> where
+		AccountId: Codec,
+		AssetId: Codec,
+		Balance: Codec,
+		Moment: Codec,
+	{
+		/// Tokens held by `owner`, skipping the first `start` and returning at most `limit`.
+		fn tokens_of_owner(owner: AccountId, start: u32, limit: u32) -> Vec<NftId>;
+
+		/// Metadata, creator, owner and royalty recipients of a token.
+		fn token_details(id: NftId) -> Option<TokenDetails<AccountId>>;
+
+		/// Asks of the current owner of a token, per payment asset.
+		fn asks(id: NftId) -> Vec<(AssetId, Balance)>;
+
+		/// Escrowed bids for a token which haven't expired: bidder, asset, amount and deadline.
+		fn bids(id: NftId) -> Vec<(AccountId, AssetId, Balance, Moment)>;
+	}
+}
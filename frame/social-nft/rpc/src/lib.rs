@@ -0,0 +1,241 @@
+//! Node-specific RPC methods for ownership and market queries of the social nft module.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT, PerThing};
+
+pub use pallet_social_nft_rpc_runtime_api::{
+	NftId, SocialNftApi as SocialNftRuntimeApi, TokenDetails,
+};
+pub use self::gen_client::Client as SocialNftClient;
+
+/// An RPC serializable token of the social nft module.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenDetails<AccountId> {
+	/// The token id.
+	pub id: NftId,
+	/// The token metadata.
+	pub metadata: Vec<u8>,
+	/// The account which minted the token.
+	pub creator: AccountId,
+	/// The account holding the token.
+	pub owner: AccountId,
+	/// The collection of the token, if any.
+	pub collection: Option<u64>,
+	/// Royalty recipients and their share of a sale, in percent.
+	pub royalty: Vec<(AccountId, u8)>,
+}
+
+impl<AccountId> From<TokenDetails<AccountId>> for RpcTokenDetails<AccountId> {
+	fn from(details: TokenDetails<AccountId>) -> Self {
+		RpcTokenDetails {
+			id: details.id,
+			metadata: details.metadata,
+			creator: details.creator,
+			owner: details.owner,
+			collection: details.collection,
+			royalty: details.royalty.into_iter()
+				.map(|(recipient, share)| (recipient, share.deconstruct()))
+				.collect(),
+		}
+	}
+}
+
+/// An RPC serializable ask for a token.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAsk<AssetId> {
+	/// The asset the token is priced in.
+	pub asset_id: AssetId,
+	/// The price.
+	pub amount: NumberOrHex,
+}
+
+/// An RPC serializable escrowed bid for a token.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBid<AccountId, AssetId, Moment> {
+	/// The bidding account.
+	pub bidder: AccountId,
+	/// The asset the bid is escrowed in.
+	pub asset_id: AssetId,
+	/// The escrowed amount.
+	pub amount: NumberOrHex,
+	/// Timestamp after which the bid can't be accepted.
+	pub dead_line: Moment,
+}
+
+/// Social nft RPC methods.
+#[rpc]
+pub trait SocialNftApi<BlockHash, AccountId, AssetId, Balance, Moment> {
+	/// Tokens held by `owner`, skipping the first `start` and returning at most `limit`.
+	#[rpc(name = "socialNft_tokensOfOwner")]
+	fn tokens_of_owner(
+		&self,
+		owner: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<NftId>>;
+
+	/// Metadata, creator, owner and royalty recipients of a token.
+	#[rpc(name = "socialNft_tokenDetails")]
+	fn token_details(
+		&self,
+		id: NftId,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcTokenDetails<AccountId>>>;
+
+	/// Asks of the current owner of a token.
+	#[rpc(name = "socialNft_asks")]
+	fn asks(
+		&self,
+		id: NftId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RpcAsk<AssetId>>>;
+
+	/// Escrowed bids for a token which haven't expired.
+	#[rpc(name = "socialNft_bids")]
+	fn bids(
+		&self,
+		id: NftId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RpcBid<AccountId, AssetId, Moment>>>;
+}
+
+/// A struct that implements the [`SocialNftApi`].
+pub struct SocialNft<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> SocialNft<C, B> {
+	/// Create new `SocialNft` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		SocialNft { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Runtime error".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+impl<C, Block, AccountId, AssetId, Balance, Moment> SocialNftApi<
+	<Block as BlockT>::Hash,
+	AccountId,
+	AssetId,
+	Balance,
+	Moment,
+> for SocialNft<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SocialNftRuntimeApi<Block, AccountId, AssetId, Balance, Moment>,
+	AccountId: Codec,
+	AssetId: Codec,
+	Balance: Codec + Into<NumberOrHex>,
+	Moment: Codec,
+{
+	fn tokens_of_owner(
+		&self,
+		owner: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<NftId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.tokens_of_owner(&at, owner, start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn token_details(
+		&self,
+		id: NftId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcTokenDetails<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let details = api.token_details(&at, id)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(details.map(Into::into))
+	}
+
+	fn asks(
+		&self,
+		id: NftId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RpcAsk<AssetId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let asks = api.asks(&at, id)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(asks.into_iter()
+			.map(|(asset_id, amount)| RpcAsk { asset_id, amount: amount.into() })
+			.collect())
+	}
+
+	fn bids(
+		&self,
+		id: NftId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RpcBid<AccountId, AssetId, Moment>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let bids = api.bids(&at, id)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(bids.into_iter()
+			.map(|(bidder, asset_id, amount, dead_line)| RpcBid {
+				bidder,
+				asset_id,
+				amount: amount.into(),
+				dead_line,
+			})
+			.collect())
+	}
+}
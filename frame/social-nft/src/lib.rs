@@ -4,13 +4,14 @@
 use codec::{Decode, Encode};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
-    traits::Get,
+    storage::{unhashed, StoragePrefixedMap}, traits::Get, transactional, weights::Weight,
 };
 use frame_system::{self as system, ensure_signed};
+use pallet_assets::IssueAndBurn;
 use sp_core::U256;
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{ModuleId, Percent, PerThing, RuntimeDebug};
 use sp_std::prelude::*;
-use sp_runtime::traits::{Zero, One};
+use sp_runtime::traits::{Zero, One, AccountIdConversion, SaturatedConversion};
 use sp_runtime::{
 	traits::Saturating,
 };
@@ -19,21 +20,93 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-type NftId = U256;
+pub type NftId = U256;
+pub type CollectionId = u64;
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 pub struct Erc721Token<T: Config> {
     pub id: NftId,
     pub metadata: Vec<u8>,
+	/// Share of every auction sale paid to the creator, in percent
 	pub royalty: T::Balance,
 }
 
+/// A token as returned by the runtime api
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct TokenDetails<AccountId> {
+	pub id: NftId,
+	pub metadata: Vec<u8>,
+	pub creator: AccountId,
+	pub owner: AccountId,
+	pub collection: Option<CollectionId>,
+	/// Recipients of the royalty on a sale and their share of the price
+	pub royalty: Vec<(AccountId, Percent)>,
+}
+
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 pub struct BidToken<T: Config> {
+	/// Amount escrowed until the bid is accepted, removed or expires
 	pub amount: T::Balance,
 	pub token_id: T::AssetId,
 	pub dead_line: T::Moment,
-	pub is_active: bool
+}
+
+// A value placed in storage that represents the current version of the storage. This value
+// is used by the `on_runtime_upgrade` logic to determine whether we run storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// Bids are flagged active and not escrowed
+	V1,
+	/// Bids are escrowed
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum AuctionKind<Balance> {
+	/// Ascending auction, the highest bid when the auction ends wins
+	English,
+	/// Descending auction starting at the given price, the first bid at the current price wins
+	Dutch(Balance),
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct Auction<T: Config> {
+	pub seller: T::AccountId,
+	pub kind: AuctionKind<T::Balance>,
+	pub asset_id: T::AssetId,
+	pub reserve: T::Balance,
+	pub start: T::BlockNumber,
+	pub end: T::BlockNumber,
+	/// Escrowed bid of the current winner
+	pub highest_bid: Option<(T::AccountId, T::Balance)>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum MintPolicy {
+	/// Only the collection owner mints
+	Issuer,
+	/// Anyone mints, up to the max supply
+	Public,
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct Collection<T: Config> {
+	pub owner: T::AccountId,
+	pub metadata: Vec<u8>,
+	/// Recipients of every sale of a collection token and their share of the price
+	pub royalty: Vec<(T::AccountId, Percent)>,
+	pub max_supply: Option<u32>,
+	/// Tokens minted so far, also the index of the next token
+	pub minted: u32,
+	pub mint_policy: MintPolicy,
+	/// Metadata and royalties can't be changed anymore
+	pub is_frozen: bool,
 }
 
 pub trait Config: system::Config + pallet_assets::Config + pallet_timestamp::Config {
@@ -41,12 +114,26 @@ pub trait Config: system::Config + pallet_assets::Config + pallet_timestamp::Con
     /// Some identifier for this token type, possibly the originating ethereum address.
     /// This is not explicitly used for anything, but may reflect the bridge's notion of resource ID.
     type Identifier: Get<[u8; 32]>;
+    /// The nft module id, used for deriving the account which escrows auction bids.
+    type ModuleId: Get<ModuleId>;
+    /// An english auction receiving a bid within this many blocks of its end is extended.
+    type SoftCloseWindow: Get<Self::BlockNumber>;
+    /// The maximum number of royalty recipients of a collection.
+    type MaxRoyaltyRecipients: Get<u32>;
+    /// The maximum number of ended auctions settled at the start of a block, the rest wait for the next blocks.
+    type MaxAuctionsSettledPerBlock: Get<u32>;
+    /// The maximum number of escrowed bids for a token.
+    type MaxBidsPerToken: Get<u32>;
+    /// The maximum number of tokens added to the owner index in a block while it is being built.
+    type MaxOwnersIndexedPerBlock: Get<u32>;
 }
 
 decl_event! {
     pub enum Event<T>
     where
         <T as system::Config>::AccountId,
+        <T as system::Config>::BlockNumber,
+        Balance = <T as pallet_assets::Config>::Balance,
     {
         /// New token created
         Minted(AccountId, NftId),
@@ -58,6 +145,28 @@ decl_event! {
 		SetAskAmount(NftId),
 		/// Set Ask Amount
 		SetBidAmount(NftId),
+		/// Bid withdrawn and refunded to the bidder
+		BidRemoved(NftId, AccountId),
+		/// Token sold from the seller to the buyer through an ask or a bid
+		Traded(NftId, AccountId, AccountId, Balance),
+		/// Auction created by the seller, ending at the given block
+		AuctionCreated(NftId, AccountId, BlockNumber),
+		/// Bid escrowed for an auction
+		AuctionBid(NftId, AccountId, Balance),
+		/// Auction end moved by a late bid
+		AuctionExtended(NftId, BlockNumber),
+		/// Auction settled, the token went to the winner for the price
+		AuctionSettled(NftId, AccountId, Balance),
+		/// Auction closed without a sale
+		AuctionCancelled(NftId),
+		/// New collection created
+		CollectionCreated(CollectionId, AccountId),
+		/// Collection metadata changed
+		CollectionMetadataSet(CollectionId),
+		/// Collection royalties changed
+		CollectionRoyaltySet(CollectionId),
+		/// Collection metadata and royalties locked
+		CollectionFrozen(CollectionId),
     }
 }
 
@@ -71,6 +180,46 @@ decl_error! {
         NotOwner,
 		/// Not For Sale
         NotForSale,
+		/// No bid from the bidder
+		BidNotExists,
+		/// Bid deadline has passed
+		BidExpired,
+		/// Bid deadline has not passed yet
+		BidNotExpired,
+		/// Token is already in an auction
+		AuctionAlreadyExists,
+		/// No auction for the token
+		AuctionNotExists,
+		/// Auction is not accepting bids
+		AuctionNotActive,
+		/// Auction already has bids
+		AuctionHasBids,
+		/// Token is locked in an auction
+		TokenInAuction,
+		/// End must be after start and not in the past
+		InvalidAuctionPeriod,
+		/// Dutch start price is below the reserve
+		InvalidAuctionPrice,
+		/// Payment asset does not exist
+		AssetNotExists,
+		/// Bid is below the reserve, the current price or the highest bid
+		BidTooLow,
+		/// Collection not recognized
+		CollectionNotExists,
+		/// Collection reached its max supply
+		CollectionFull,
+		/// Collection metadata and royalties are frozen
+		CollectionFrozen,
+		/// Sender may not mint in the collection
+		MintNotAllowed,
+		/// Royalty shares add up to more than the price
+		RoyaltyTooHigh,
+		/// More royalty recipients than allowed
+		TooManyRoyaltyRecipients,
+		/// Token id is reserved for collection tokens
+		InvalidTokenId,
+		/// Token has the maximum number of escrowed bids
+		TooManyBids,
     }
 }
 
@@ -87,10 +236,37 @@ decl_storage! {
 		/// Set ask amount for token id
 		pub TokenAskAmount get(fn ask_token): double_map hasher(opaque_blake2_256) NftId,
 		hasher(twox_64_concat) T::AssetId => (T::Balance, T::AccountId);
-		 /// Maps tokenId to owner
+		/// Escrowed bids for token id
         pub TokenBidAmount get(fn bid_token): double_map hasher(opaque_blake2_256) NftId,
 		hasher(twox_64_concat) T::AccountId =>
 		Option<BidToken<T>>;
+		/// Number of escrowed bids for token id
+		pub BidCount get(fn bid_count): map hasher(opaque_blake2_256) NftId => u32;
+		/// Running auction for token id
+		pub Auctions get(fn auctions): map hasher(opaque_blake2_256) NftId => Option<Auction<T>>;
+		/// Auctions to settle once the block has passed
+		pub AuctionEndsAt get(fn auction_ends_at): double_map hasher(twox_64_concat) T::BlockNumber,
+		hasher(blake2_128_concat) NftId => ();
+		/// All auctions ending before this block are settled
+		pub AuctionsSettledBefore get(fn auctions_settled_before): T::BlockNumber;
+		/// Id of the next collection, collection tokens take their ids from the collection id
+		pub NextCollectionId get(fn next_collection_id): CollectionId = 1;
+		/// Maps collection id to the collection
+		pub Collections get(fn collections): map hasher(twox_64_concat) CollectionId => Option<Collection<T>>;
+		/// Maps tokenId to its collection
+		pub TokenCollection get(fn token_collection): map hasher(opaque_blake2_256) NftId => Option<CollectionId>;
+		/// Tokens held by an account
+		pub TokensOfOwner: double_map hasher(blake2_128_concat) T::AccountId,
+		hasher(blake2_128_concat) NftId => ();
+		/// Whether the owner index was built for the tokens minted before it existed
+		OwnerIndexBuilt: bool;
+		/// Storage key of the last token added to the owner index while it is being built
+		OwnerIndexCursor: Option<Vec<u8>>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_| Releases::V2): Releases;
     }
 }
 
@@ -99,7 +275,36 @@ decl_module! {
         type Error = Error<T>;
         fn deposit_event() = default;
 
+        const ModuleId: ModuleId = T::ModuleId::get();
+        const SoftCloseWindow: T::BlockNumber = T::SoftCloseWindow::get();
+        const MaxRoyaltyRecipients: u32 = T::MaxRoyaltyRecipients::get();
+        const MaxAuctionsSettledPerBlock: u32 = T::MaxAuctionsSettledPerBlock::get();
+        const MaxBidsPerToken: u32 = T::MaxBidsPerToken::get();
+        const MaxOwnersIndexedPerBlock: u32 = T::MaxOwnersIndexedPerBlock::get();
+
+        fn on_runtime_upgrade() -> Weight {
+            let mut bids = 0;
+            if StorageVersion::get() == Releases::V1 {
+                bids = Self::remove_unescrowed_bids();
+            }
+            // auctions up to now were settled by the previous runtime
+            if !AuctionsSettledBefore::<T>::exists() {
+                AuctionsSettledBefore::<T>::put(<system::Module<T>>::block_number());
+            }
+            T::DbWeight::get().reads_writes(2 + bids, 3 + bids)
+        }
+
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            let mut weight = Self::settle_ended_auctions(n);
+            if !OwnerIndexBuilt::get() {
+                weight = weight.saturating_add(Self::index_owners());
+            }
+            weight.saturating_add(T::DbWeight::get().reads(1))
+        }
+
         /// Creates a new token with the given token ID and metadata, and gives ownership to owner
+        ///
+        /// Ids from 2^128 up belong to collection tokens and are rejected.
         #[weight = 195_000_000]
         pub fn mint(origin, owner: T::AccountId, id: NftId, metadata: Vec<u8>, royalty: T::Balance) -> DispatchResult {
             let _sender = ensure_signed(origin)?;
@@ -132,7 +337,9 @@ decl_module! {
             Ok(())
         }
 
+		/// Set the price of a token in an asset, settles with the best crossing bid
 		#[weight = 195_000_000]
+		#[transactional]
         pub fn set_ask(origin, nft_id: NftId, token_id: T::AssetId, amount: T::Balance) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
@@ -144,24 +351,186 @@ decl_module! {
             Ok(())
         }
 
+		/// Bid for a token, buys it if an ask is crossed and otherwise escrows the amount
 		#[weight = 195_000_000]
+		#[transactional]
         pub fn set_bid(origin, nft_id: NftId, token_id: T::AssetId, amount: T::Balance, dead_line: T::Moment) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
             ensure!(TokenCreatorAndOwner::<T>::contains_key(nft_id), Error::<T>::NftIdDoesNotExist);
+            ensure!(!Auctions::<T>::contains_key(nft_id), Error::<T>::TokenInAuction);
 
             Self::set_bid_token(sender, nft_id, token_id, amount, dead_line)?;
 
             Ok(())
         }
 
+		/// Withdraw a bid and refund the escrow
 		#[weight = 195_000_000]
         pub fn remove_bid(origin, nft_id: NftId) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
+            Self::remove_bid_token(sender, nft_id)?;
+
+            Ok(())
+        }
+
+		/// Sell the token to a bidder at the bid amount
+		#[weight = 195_000_000]
+		#[transactional]
+        pub fn accept_bid(origin, nft_id: NftId, bidder: T::AccountId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
             ensure!(TokenCreatorAndOwner::<T>::contains_key(nft_id), Error::<T>::NftIdDoesNotExist);
+        	let (_, owner) = Self::owner_of(nft_id);
+			ensure!(owner == sender, Error::<T>::NotOwner);
+            ensure!(!Auctions::<T>::contains_key(nft_id), Error::<T>::TokenInAuction);
 
-            Self::remove_bid_token(sender, nft_id)?;
+            let bid = Self::bid_token(nft_id, &bidder).ok_or(Error::<T>::BidNotExists)?;
+            ensure!(bid.dead_line >= <pallet_timestamp::Module<T>>::now(), Error::<T>::BidExpired);
+            Self::fill_bid(owner, bidder, nft_id, bid)?;
+
+            Ok(())
+        }
+
+		/// Refund a bid past its deadline, callable by anyone
+		#[weight = 195_000_000]
+        pub fn remove_expired_bid(origin, nft_id: NftId, bidder: T::AccountId) -> DispatchResult {
+            let _sender = ensure_signed(origin)?;
+
+            let bid = Self::bid_token(nft_id, &bidder).ok_or(Error::<T>::BidNotExists)?;
+            ensure!(bid.dead_line < <pallet_timestamp::Module<T>>::now(), Error::<T>::BidNotExpired);
+            Self::refund_bid(nft_id, bidder, bid)?;
+
+            Ok(())
+        }
+
+        /// Put a token up for auction, paid in `asset_id` and settled once block `end` has passed
+        #[weight = 195_000_000]
+        pub fn create_auction(
+            origin,
+            nft_id: NftId,
+            kind: AuctionKind<T::Balance>,
+            asset_id: T::AssetId,
+            reserve: T::Balance,
+            start: T::BlockNumber,
+            end: T::BlockNumber
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::create_auction_token(sender, nft_id, kind, asset_id, reserve, start, end)?;
+
+            Ok(())
+        }
+
+        /// Bid on an auction, the bid is escrowed until it is outbid or the auction settles
+        ///
+        /// For a dutch auction `amount` is the most the sender will pay, the current price is taken.
+        #[weight = 195_000_000]
+        #[transactional]
+        pub fn bid_auction(origin, nft_id: NftId, amount: T::Balance) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::bid_auction_token(sender, nft_id, amount)?;
+
+            Ok(())
+        }
+
+        /// Close an auction which has no bids
+        #[weight = 195_000_000]
+        pub fn cancel_auction(origin, nft_id: NftId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let auction = Self::auctions(nft_id).ok_or(Error::<T>::AuctionNotExists)?;
+            ensure!(auction.seller == sender, Error::<T>::NotOwner);
+            ensure!(auction.highest_bid.is_none(), Error::<T>::AuctionHasBids);
+
+            Auctions::<T>::remove(nft_id);
+            AuctionEndsAt::<T>::remove(auction.end, nft_id);
+            Self::deposit_event(RawEvent::AuctionCancelled(nft_id));
+
+            Ok(())
+        }
+
+        /// Create a collection owned by the sender
+        #[weight = 195_000_000]
+        pub fn create_collection(
+            origin,
+            metadata: Vec<u8>,
+            royalty: Vec<(T::AccountId, Percent)>,
+            max_supply: Option<u32>,
+            mint_policy: MintPolicy
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::ensure_royalty_valid(&royalty)?;
+            let collection_id = Self::next_collection_id();
+            let collection = Collection {
+                owner: sender.clone(),
+                metadata,
+                royalty,
+                max_supply,
+                minted: 0,
+                mint_policy,
+                is_frozen: false,
+            };
+            Collections::<T>::insert(collection_id, collection);
+            NextCollectionId::put(collection_id.saturating_add(1));
+
+            Self::deposit_event(RawEvent::CollectionCreated(collection_id, sender));
+
+            Ok(())
+        }
+
+        /// Mint the next token of a collection to owner
+        #[weight = 195_000_000]
+        pub fn mint_in_collection(origin, collection_id: CollectionId, owner: T::AccountId, metadata: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::mint_collection_token(sender, collection_id, owner, metadata)?;
+
+            Ok(())
+        }
+
+        /// Replace the metadata of a collection which isn't frozen
+        #[weight = 195_000_000]
+        pub fn set_collection_metadata(origin, collection_id: CollectionId, metadata: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::mutate_collection(sender, collection_id, |collection| {
+                collection.metadata = metadata;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::CollectionMetadataSet(collection_id));
+
+            Ok(())
+        }
+
+        /// Replace the royalty recipients of a collection which isn't frozen
+        #[weight = 195_000_000]
+        pub fn set_collection_royalty(origin, collection_id: CollectionId, royalty: Vec<(T::AccountId, Percent)>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::ensure_royalty_valid(&royalty)?;
+            Self::mutate_collection(sender, collection_id, |collection| {
+                collection.royalty = royalty;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::CollectionRoyaltySet(collection_id));
+
+            Ok(())
+        }
+
+        /// Lock the metadata and royalties of a collection for good
+        #[weight = 195_000_000]
+        pub fn freeze_collection(origin, collection_id: CollectionId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::mutate_collection(sender, collection_id, |collection| {
+                collection.is_frozen = true;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::CollectionFrozen(collection_id));
 
             Ok(())
         }
@@ -169,14 +538,21 @@ decl_module! {
 }
 
 impl<T: Config> Module<T> {
-    /// Creates a new token in the system.
+    /// Creates a new token in the system, outside of any collection.
     pub fn mint_token(owner: T::AccountId, id: NftId, metadata: Vec<u8>, royalty: T::Balance) -> DispatchResult {
+        // the upper 128 bits hold the collection id of collection tokens
+        ensure!((id >> 128).is_zero(), Error::<T>::InvalidTokenId);
+        Self::do_mint(owner.clone(), owner, id, metadata, royalty)
+    }
+
+    fn do_mint(creator: T::AccountId, owner: T::AccountId, id: NftId, metadata: Vec<u8>, royalty: T::Balance) -> DispatchResult {
         ensure!(!<Tokens<T>>::contains_key(id), Error::<T>::TokenAlreadyExists);
 
         let new_token = Erc721Token { id, metadata, royalty };
 
 		<Tokens<T>>::insert(&id, new_token);
-        <TokenCreatorAndOwner<T>>::insert(&id, (owner.clone(), owner.clone()));
+        <TokenCreatorAndOwner<T>>::insert(&id, (creator, owner.clone()));
+        <TokensOfOwner<T>>::insert(&owner, &id, ());
         let new_total = <TokenCount>::get().saturating_add(U256::one());
         <TokenCount>::put(new_total);
         if <MaxTokenId>::get() < id {
@@ -194,8 +570,9 @@ impl<T: Config> Module<T> {
 		ensure!(TokenCreatorAndOwner::<T>::contains_key(id), Error::<T>::NftIdDoesNotExist);
         let (_, owner) = Self::owner_of(id);
         ensure!(owner == from, Error::<T>::NotOwner);
+        ensure!(!Auctions::<T>::contains_key(id), Error::<T>::TokenInAuction);
         // Update owner
-		TokenCreatorAndOwner::<T>::mutate(id, |(_, owner)| *owner = to.clone());
+		Self::set_owner(id, to.clone());
 
         Self::deposit_event(RawEvent::Transferred(from, to, id));
 
@@ -207,9 +584,12 @@ impl<T: Config> Module<T> {
 		ensure!(TokenCreatorAndOwner::<T>::contains_key(id), Error::<T>::NftIdDoesNotExist);
 		let (_, owner) = Self::owner_of(id);
         ensure!(owner == from, Error::<T>::NotOwner);
+        ensure!(!Auctions::<T>::contains_key(id), Error::<T>::TokenInAuction);
 
 		<Tokens<T>>::remove(&id);
         <TokenCreatorAndOwner<T>>::remove(&id);
+        <TokensOfOwner<T>>::remove(&owner, &id);
+        <TokenCollection>::remove(&id);
         let new_total = <TokenCount>::get().saturating_sub(U256::one());
         <TokenCount>::put(new_total);
 
@@ -218,30 +598,63 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+	fn set_owner(id: NftId, new_owner: T::AccountId) {
+		TokenCreatorAndOwner::<T>::mutate(id, |(_, owner)| {
+			<TokensOfOwner<T>>::remove(&*owner, &id);
+			<TokensOfOwner<T>>::insert(&new_owner, &id, ());
+			*owner = new_owner;
+		});
+	}
+
 	pub fn set_ask_token(owner: T::AccountId, id: NftId, token_id: T::AssetId, amount: T::Balance) -> DispatchResult {
+		ensure!(!Auctions::<T>::contains_key(id), Error::<T>::TokenInAuction);
 
-		<TokenAskAmount<T>>::insert(&id, token_id, (amount, owner));
+		<TokenAskAmount<T>>::insert(&id, token_id, (amount, owner.clone()));
 		Self::deposit_event(RawEvent::SetAskAmount(id));
+
+		if amount.is_zero() {
+			return Ok(());
+		}
+		// the best live bid at or above the ask takes the token at its own price,
+		// there are at most `MaxBidsPerToken` bids to look at
+		let now_timestamp = <pallet_timestamp::Module<T>>::now();
+		let best = TokenBidAmount::<T>::iter_prefix(id)
+			.filter(|(_, bid)| bid.token_id == token_id && bid.amount >= amount && bid.dead_line >= now_timestamp)
+			.max_by_key(|(_, bid)| bid.amount);
+		if let Some((bidder, bid)) = best {
+			Self::fill_bid(owner, bidder, id, bid)?;
+		}
 		Ok(())
 	}
 
 	pub fn set_bid_token(sender: T::AccountId, id: NftId, token_id: T::AssetId, amount: T::Balance, dead_line: T::Moment) -> DispatchResult {
-
-		let (ask_token, _) = TokenAskAmount::<T>::get(id, token_id);
-
-		ensure!(!ask_token.is_zero(), Error::<T>::NotForSale);
+		let (ask_token, asker) = TokenAskAmount::<T>::get(id, token_id);
+		let (_, owner) = Self::owner_of(id);
+		// asks left behind by a previous owner don't count
+		ensure!(!ask_token.is_zero() && asker == owner, Error::<T>::NotForSale);
 		let now_timestamp = <pallet_timestamp::Module<T>>::now();
+		ensure!(dead_line >= now_timestamp, Error::<T>::BidExpired);
+
+		if let Some(bid) = Self::bid_token(id, &sender) {
+			Self::refund_bid(id, sender.clone(), bid)?;
+		}
 
-		if amount > ask_token && dead_line >=now_timestamp {
-			Self::execute_trade(sender, id, token_id, amount);
+		if amount >= ask_token {
+			// the bidder pays the ask straight away
+			Self::pay_sale(id, &sender, &owner, token_id, ask_token)?;
+			Self::settle_trade(id, owner, sender, ask_token);
 		} else {
+			let count = Self::bid_count(id);
+			ensure!(count < T::MaxBidsPerToken::get(), Error::<T>::TooManyBids);
+			<pallet_assets::Module<T>>::do_transfer(token_id, sender.clone(), Self::account_id(), amount)
+				.map_err(|e| e.error)?;
 			let bid = BidToken{
 				amount,
 				token_id,
 				dead_line,
-				is_active: true
 			};
 			<TokenBidAmount<T>>::insert(&id, &sender, bid);
+			<BidCount>::insert(&id, count.saturating_add(1));
 			Self::deposit_event(RawEvent::SetBidAmount(id));
 		}
 
@@ -249,24 +662,398 @@ impl<T: Config> Module<T> {
 	}
 
 	pub fn remove_bid_token(sender: T::AccountId, id: NftId) -> DispatchResult {
-		ensure!(TokenBidAmount::<T>::contains_key(id, sender.clone()), Error::<T>::NftIdDoesNotExist);
-		TokenBidAmount::<T>::mutate(id, &sender, |bid|
-			if let Some(b) = bid {
-				b.is_active = false
+		let bid = Self::bid_token(id, &sender).ok_or(Error::<T>::BidNotExists)?;
+		Self::refund_bid(id, sender, bid)
+	}
+
+	fn refund_bid(id: NftId, bidder: T::AccountId, bid: BidToken<T>) -> DispatchResult {
+		<pallet_assets::Module<T>>::do_transfer(bid.token_id, Self::account_id(), bidder.clone(), bid.amount)
+			.map_err(|e| e.error)?;
+		Self::remove_bid(id, &bidder);
+		Self::deposit_event(RawEvent::BidRemoved(id, bidder));
+		Ok(())
+	}
+
+	fn remove_bid(id: NftId, bidder: &T::AccountId) {
+		<TokenBidAmount<T>>::remove(&id, bidder);
+		<BidCount>::mutate(&id, |count| *count = count.saturating_sub(1));
+	}
+
+	/// Drop the bids of the previous runtime, which were never escrowed, returning their number
+	fn remove_unescrowed_bids() -> u64 {
+		let prefix = TokenBidAmount::<T>::final_prefix();
+		let mut key = prefix.to_vec();
+		let mut bids = 0u64;
+		while let Some(next) = sp_io::storage::next_key(&key).filter(|next| next.starts_with(&prefix)) {
+			unhashed::kill(&next);
+			bids += 1;
+			key = next;
+		}
+		StorageVersion::put(Releases::V2);
+		bids
+	}
+
+	/// Sell the token to an escrowed bid
+	fn fill_bid(owner: T::AccountId, bidder: T::AccountId, id: NftId, bid: BidToken<T>) -> DispatchResult {
+		Self::remove_bid(id, &bidder);
+		Self::pay_sale(id, &Self::account_id(), &owner, bid.token_id, bid.amount)?;
+		Self::settle_trade(id, owner, bidder, bid.amount);
+		Ok(())
+	}
+
+	fn settle_trade(id: NftId, seller: T::AccountId, buyer: T::AccountId, price: T::Balance) {
+		Self::set_owner(id, buyer.clone());
+		<TokenAskAmount<T>>::remove_prefix(&id);
+		Self::deposit_event(RawEvent::Traded(id, seller, buyer, price));
+	}
+
+	/// Id of the token at `index` in a collection, the collection id sits in the upper 128 bits
+	pub fn collection_token_id(collection_id: CollectionId, index: u32) -> NftId {
+		(U256::from(collection_id) << 128) + U256::from(index)
+	}
+
+	pub fn mint_collection_token(
+		sender: T::AccountId,
+		collection_id: CollectionId,
+		owner: T::AccountId,
+		metadata: Vec<u8>,
+	) -> DispatchResult {
+		let mut collection = Self::collections(collection_id).ok_or(Error::<T>::CollectionNotExists)?;
+		if collection.mint_policy == MintPolicy::Issuer {
+			ensure!(collection.owner == sender, Error::<T>::MintNotAllowed);
+		}
+		if let Some(max_supply) = collection.max_supply {
+			ensure!(collection.minted < max_supply, Error::<T>::CollectionFull);
+		}
+
+		let id = Self::collection_token_id(collection_id, collection.minted);
+		// the royalty of collection tokens is paid from the collection
+		Self::do_mint(sender, owner, id, metadata, Zero::zero())?;
+		TokenCollection::insert(id, collection_id);
+		collection.minted = collection.minted.saturating_add(1);
+		Collections::<T>::insert(collection_id, collection);
+		Ok(())
+	}
+
+	/// Mint a token arriving over the bridge, into its collection if it has one
+	///
+	/// Collection tokens keep their id across the bridge, the collection counts them as minted.
+	pub fn mint_bridged_token(
+		owner: T::AccountId,
+		id: NftId,
+		metadata: Vec<u8>,
+		royalty: T::Balance,
+		collection_id: Option<CollectionId>,
+	) -> DispatchResult {
+		let collection_id = match collection_id {
+			Some(collection_id) => collection_id,
+			None => return Self::mint_token(owner, id, metadata, royalty),
+		};
+		let mut collection = Self::collections(collection_id).ok_or(Error::<T>::CollectionNotExists)?;
+		ensure!(id >> 128 == U256::from(collection_id), Error::<T>::InvalidTokenId);
+		let index = id.low_u128();
+		ensure!(index <= u32::max_value() as u128, Error::<T>::InvalidTokenId);
+		let index = index as u32;
+		if index >= collection.minted {
+			if let Some(max_supply) = collection.max_supply {
+				ensure!(index < max_supply, Error::<T>::CollectionFull);
 			}
-		);
+			collection.minted = index.saturating_add(1);
+		}
 
+		Self::do_mint(owner.clone(), owner, id, metadata, Zero::zero())?;
+		TokenCollection::insert(id, collection_id);
+		Collections::<T>::insert(collection_id, collection);
 		Ok(())
 	}
 
-	fn execute_trade(sender: T::AccountId, id: NftId, token_id: T::AssetId, amount: T::Balance) -> DispatchResult {
-		let nft = Tokens::<T>::get(id).ok_or(Error::<T>::NftIdDoesNotExist)?;
-		let (creator, owner) = Self::owner_of(id);
-		if nft.royalty.is_zero() {
-			<pallet_assets::Module<T>>::do_transfer(token_id, sender.clone(), creator, amount.saturating_mul(nft.royalty));
-			<pallet_assets::Module<T>>::do_transfer(token_id, sender.clone(), owner, amount.saturating_mul(T::Balance::one().saturating_sub(nft.royalty)));
-			TokenCreatorAndOwner::<T>::mutate(id, |(_, owner)| *owner = sender);
+	fn mutate_collection(
+		sender: T::AccountId,
+		collection_id: CollectionId,
+		f: impl FnOnce(&mut Collection<T>) -> DispatchResult,
+	) -> DispatchResult {
+		Collections::<T>::try_mutate(collection_id, |maybe_collection| {
+			let collection = maybe_collection.as_mut().ok_or(Error::<T>::CollectionNotExists)?;
+			ensure!(collection.owner == sender, Error::<T>::NotOwner);
+			ensure!(!collection.is_frozen, Error::<T>::CollectionFrozen);
+			f(collection)
+		})
+	}
+
+	fn ensure_royalty_valid(royalty: &[(T::AccountId, Percent)]) -> DispatchResult {
+		ensure!(royalty.len() as u32 <= T::MaxRoyaltyRecipients::get(), Error::<T>::TooManyRoyaltyRecipients);
+		let total = royalty.iter().fold(0u32, |total, (_, share)| total.saturating_add(share.deconstruct().into()));
+		ensure!(total <= 100, Error::<T>::RoyaltyTooHigh);
+		Ok(())
+	}
+
+	/// The account escrowing auction bids
+	pub fn account_id() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	pub fn create_auction_token(
+		seller: T::AccountId,
+		id: NftId,
+		kind: AuctionKind<T::Balance>,
+		asset_id: T::AssetId,
+		reserve: T::Balance,
+		start: T::BlockNumber,
+		end: T::BlockNumber,
+	) -> DispatchResult {
+		ensure!(TokenCreatorAndOwner::<T>::contains_key(id), Error::<T>::NftIdDoesNotExist);
+		let (_, owner) = Self::owner_of(id);
+		ensure!(owner == seller, Error::<T>::NotOwner);
+		ensure!(!Auctions::<T>::contains_key(id), Error::<T>::AuctionAlreadyExists);
+		ensure!(<pallet_assets::Module<T>>::exists(&asset_id), Error::<T>::AssetNotExists);
+		let now = <system::Module<T>>::block_number();
+		ensure!(start < end && end >= now, Error::<T>::InvalidAuctionPeriod);
+		if let AuctionKind::Dutch(start_price) = kind {
+			ensure!(start_price >= reserve, Error::<T>::InvalidAuctionPrice);
 		}
+
+		let auction = Auction { seller: seller.clone(), kind, asset_id, reserve, start, end, highest_bid: None };
+		Auctions::<T>::insert(id, auction);
+		AuctionEndsAt::<T>::insert(end, id, ());
+		Self::deposit_event(RawEvent::AuctionCreated(id, seller, end));
 		Ok(())
 	}
+
+	pub fn bid_auction_token(bidder: T::AccountId, id: NftId, amount: T::Balance) -> DispatchResult {
+		let mut auction = Self::auctions(id).ok_or(Error::<T>::AuctionNotExists)?;
+		let now = <system::Module<T>>::block_number();
+		ensure!(now >= auction.start && now <= auction.end, Error::<T>::AuctionNotActive);
+
+		let pot = Self::account_id();
+		let (paid, end) = match auction.kind {
+			AuctionKind::English => {
+				ensure!(amount >= auction.reserve, Error::<T>::BidTooLow);
+				if let Some((_, highest)) = auction.highest_bid {
+					ensure!(amount > highest, Error::<T>::BidTooLow);
+				}
+				<pallet_assets::Module<T>>::do_transfer(auction.asset_id, bidder.clone(), pot.clone(), amount)
+					.map_err(|e| e.error)?;
+				if let Some((outbid, highest)) = auction.highest_bid.take() {
+					<pallet_assets::Module<T>>::do_transfer(auction.asset_id, pot, outbid, highest)
+						.map_err(|e| e.error)?;
+				}
+				// a bid close to the end gives the others time to answer
+				let window = T::SoftCloseWindow::get();
+				let end = if auction.end.saturating_sub(now) < window {
+					now.saturating_add(window)
+				} else {
+					auction.end
+				};
+				(amount, end)
+			}
+			AuctionKind::Dutch(start_price) => {
+				ensure!(auction.highest_bid.is_none(), Error::<T>::AuctionNotActive);
+				let price = Self::dutch_price(start_price, auction.reserve, auction.start, auction.end, now);
+				ensure!(amount >= price, Error::<T>::BidTooLow);
+				<pallet_assets::Module<T>>::do_transfer(auction.asset_id, bidder.clone(), pot, price)
+					.map_err(|e| e.error)?;
+				// first taker wins, settle in this block
+				(price, now)
+			}
+		};
+
+		auction.highest_bid = Some((bidder.clone(), paid));
+		if end != auction.end {
+			AuctionEndsAt::<T>::remove(auction.end, id);
+			AuctionEndsAt::<T>::insert(end, id, ());
+			auction.end = end;
+			if let AuctionKind::English = auction.kind {
+				Self::deposit_event(RawEvent::AuctionExtended(id, end));
+			}
+		}
+		Auctions::<T>::insert(id, auction);
+		Self::deposit_event(RawEvent::AuctionBid(id, bidder, paid));
+		Ok(())
+	}
+
+	/// Price of a dutch auction at block `now`, falling linearly from `start_price` to `reserve`
+	pub fn dutch_price(
+		start_price: T::Balance,
+		reserve: T::Balance,
+		start: T::BlockNumber,
+		end: T::BlockNumber,
+		now: T::BlockNumber,
+	) -> T::Balance {
+		if now <= start {
+			return start_price;
+		}
+		if now >= end {
+			return reserve;
+		}
+		let elapsed: u128 = now.saturating_sub(start).saturated_into();
+		let duration: u128 = end.saturating_sub(start).saturated_into();
+		let range: u128 = start_price.saturating_sub(reserve).saturated_into();
+		let drop: T::Balance = (range.saturating_mul(elapsed) / duration).saturated_into();
+		start_price.saturating_sub(drop)
+	}
+
+	/// Settle the auctions which ended before block `n`, up to `MaxAuctionsSettledPerBlock`,
+	/// carrying the rest over to the next blocks
+	fn settle_ended_auctions(n: T::BlockNumber) -> Weight {
+		let max = T::MaxAuctionsSettledPerBlock::get() as usize;
+		let mut block = Self::auctions_settled_before();
+		let mut blocks = 0u64;
+		let mut settled = 0usize;
+		while block < n {
+			blocks += 1;
+			let remaining = max.saturating_sub(settled);
+			let ended: Vec<NftId> = AuctionEndsAt::<T>::iter_prefix(block)
+				.map(|(id, _)| id)
+				.take(remaining.saturating_add(1))
+				.collect();
+			for id in ended.iter().take(remaining) {
+				AuctionEndsAt::<T>::remove(block, id);
+				Self::settle_auction(*id);
+				settled += 1;
+			}
+			if ended.len() > remaining {
+				break;
+			}
+			block = block.saturating_add(One::one());
+		}
+		AuctionsSettledBefore::<T>::put(block);
+
+		// every settlement pays the seller and the royalty recipients out of the escrow
+		let transfers = T::MaxRoyaltyRecipients::get() as u64 + 1;
+		let settled = settled as u64;
+		T::DbWeight::get().reads_writes(
+			1 + blocks + settled * (5 + 3 * transfers),
+			1 + settled * (4 + 2 * transfers),
+		)
+	}
+
+	fn settle_auction(id: NftId) {
+		let auction = match Auctions::<T>::take(id) {
+			Some(auction) => auction,
+			None => return,
+		};
+		let (winner, price) = match auction.highest_bid {
+			Some(bid) => bid,
+			None => {
+				Self::deposit_event(RawEvent::AuctionCancelled(id));
+				return;
+			}
+		};
+
+		if Self::pay_sale(id, &Self::account_id(), &auction.seller, auction.asset_id, price).is_err() {
+			// the seller can't be paid, give the winner their bid back
+			let _ = <pallet_assets::Module<T>>::do_transfer(auction.asset_id, Self::account_id(), winner, price);
+			Self::deposit_event(RawEvent::AuctionCancelled(id));
+			return;
+		}
+		Self::set_owner(id, winner.clone());
+		Self::deposit_event(RawEvent::AuctionSettled(id, winner, price));
+	}
+
+	/// Pay the royalties and the rest of the price from the buyer or the escrow to the seller
+	#[transactional]
+	fn pay_sale(id: NftId, from: &T::AccountId, seller: &T::AccountId, asset_id: T::AssetId, price: T::Balance) -> DispatchResult {
+		let mut rest = price;
+		for (recipient, royalty) in Self::royalties(id, seller, price) {
+			let royalty = royalty.min(rest);
+			if royalty.is_zero() {
+				continue;
+			}
+			<pallet_assets::Module<T>>::do_transfer(asset_id, from.clone(), recipient, royalty)
+				.map_err(|e| e.error)?;
+			rest = rest.saturating_sub(royalty);
+		}
+		if !rest.is_zero() {
+			<pallet_assets::Module<T>>::do_transfer(asset_id, from.clone(), seller.clone(), rest)
+				.map_err(|e| e.error)?;
+		}
+		Ok(())
+	}
+
+	/// Royalties due on a sale of the token, split between the collection recipients or paid to the creator
+	fn royalties(id: NftId, seller: &T::AccountId, price: T::Balance) -> Vec<(T::AccountId, T::Balance)> {
+		if let Some(collection) = Self::token_collection(id).and_then(Self::collections) {
+			return collection.royalty.into_iter()
+				.map(|(recipient, share)| (recipient, share.mul_floor(price)))
+				.collect();
+		}
+
+		let (creator, _) = Self::owner_of(id);
+		match Tokens::<T>::get(id) {
+			Some(nft) if &creator != seller =>
+				vec![(creator, price.saturating_mul(nft.royalty) / T::Balance::from(100u32))],
+			_ => Vec::new(),
+		}
+	}
+
+	/// Add the next `MaxOwnersIndexedPerBlock` tokens minted before the owner index existed to it
+	fn index_owners() -> Weight {
+		let prefix = Tokens::<T>::final_prefix();
+		let mut key = OwnerIndexCursor::get().unwrap_or_else(|| prefix.to_vec());
+		let mut tokens = 0u64;
+		let mut built = false;
+		while tokens < T::MaxOwnersIndexedPerBlock::get() as u64 {
+			match sp_io::storage::next_key(&key).filter(|next| next.starts_with(&prefix)) {
+				Some(next) => {
+					if let Some(token) = unhashed::get::<Erc721Token<T>>(&next) {
+						let (_, owner) = Self::owner_of(token.id);
+						TokensOfOwner::<T>::insert(owner, token.id, ());
+					}
+					key = next;
+					tokens += 1;
+				}
+				None => {
+					built = true;
+					break;
+				}
+			}
+		}
+
+		if built {
+			OwnerIndexBuilt::put(true);
+			OwnerIndexCursor::kill();
+		} else {
+			OwnerIndexCursor::put(key);
+		}
+		T::DbWeight::get().reads_writes(2 * tokens + 2, tokens + 2)
+	}
+
+	/// Tokens held by `owner`, skipping the first `start` and returning at most `limit`
+	pub fn tokens_of_owner(owner: T::AccountId, start: u32, limit: u32) -> Vec<NftId> {
+		TokensOfOwner::<T>::iter_prefix(owner)
+			.skip(start as usize)
+			.take(limit as usize)
+			.map(|(id, _)| id)
+			.collect()
+	}
+
+	pub fn token_details(id: NftId) -> Option<TokenDetails<T::AccountId>> {
+		let token = Self::tokens(id)?;
+		let (creator, owner) = Self::owner_of(id);
+		let collection = Self::token_collection(id);
+		let royalty = match collection.and_then(Self::collections) {
+			Some(collection) => collection.royalty,
+			None if token.royalty.is_zero() => Vec::new(),
+			None => vec![(creator.clone(), Percent::from_percent(token.royalty.saturated_into::<u8>()))],
+		};
+		Some(TokenDetails { id, metadata: token.metadata, creator, owner, collection, royalty })
+	}
+
+	/// Asks of the current owner of a token
+	pub fn asks(id: NftId) -> Vec<(T::AssetId, T::Balance)> {
+		let (_, owner) = Self::owner_of(id);
+		TokenAskAmount::<T>::iter_prefix(id)
+			.filter(|(_, (amount, asker))| !amount.is_zero() && asker == &owner)
+			.map(|(asset_id, (amount, _))| (asset_id, amount))
+			.collect()
+	}
+
+	/// Escrowed bids for a token which haven't expired
+	pub fn bids(id: NftId) -> Vec<(T::AccountId, T::AssetId, T::Balance, T::Moment)> {
+		let now_timestamp = <pallet_timestamp::Module<T>>::now();
+		TokenBidAmount::<T>::iter_prefix(id)
+			.filter(|(_, bid)| bid.dead_line >= now_timestamp)
+			.map(|(bidder, bid)| (bidder, bid.token_id, bid.amount, bid.dead_line))
+			.collect()
+	}
 }
@@ -7,7 +7,7 @@ use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
+    BuildStorage, ModuleId,
 };
 
 use crate::{self as pallet_social_nft, Config};
@@ -97,6 +97,7 @@ parameter_types! {
 		pub const StringLimit: u32 = 50;
 		pub const MetadataDepositBase: u64 = 1;
 		pub const MetadataDepositPerByte: u64 = 1;
+		pub const ApprovalDeposit: u64 = 1;
 	}
 
 impl pallet_assets::Config for Test {
@@ -110,16 +111,29 @@ impl pallet_assets::Config for Test {
 	type StringLimit = StringLimit;
 	type MetadataDepositBase = MetadataDepositBase;
 	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
 	type WeightInfo = ();
 }
 
 parameter_types! {
     pub Erc721Id: bridge::ResourceId = bridge::derive_resource_id(1, &blake2_128(b"NFT"));
+    pub const NftModuleId: ModuleId = ModuleId(*b"py/nftau");
+    pub const SoftCloseWindow: u64 = 5;
+    pub const MaxRoyaltyRecipients: u32 = 3;
+    pub const MaxAuctionsSettledPerBlock: u32 = 2;
+    pub const MaxBidsPerToken: u32 = 2;
+    pub const MaxOwnersIndexedPerBlock: u32 = 2;
 }
 
 impl Config for Test {
     type Event = Event;
     type Identifier = Erc721Id;
+    type ModuleId = NftModuleId;
+    type SoftCloseWindow = SoftCloseWindow;
+    type MaxRoyaltyRecipients = MaxRoyaltyRecipients;
+    type MaxAuctionsSettledPerBlock = MaxAuctionsSettledPerBlock;
+    type MaxBidsPerToken = MaxBidsPerToken;
+    type MaxOwnersIndexedPerBlock = MaxOwnersIndexedPerBlock;
 }
 
 pub const USER_A: u64 = 0x1;
@@ -134,5 +148,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		balances: vec![(USER_A, ENDOWED_BALANCE)],
 	}.assimilate_storage(&mut t).unwrap();
 
-	t.into()
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
 }
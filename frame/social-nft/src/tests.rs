@@ -1,8 +1,9 @@
 #![cfg(test)]
 
-use super::mock::{new_test_ext, SocialNft, Origin, Test, USER_A, USER_B, USER_C, ROYALTY};
+use super::mock::{new_test_ext, Assets, Event, SocialNft, System, Timestamp, Origin, Test, USER_A, USER_B, USER_C, ROYALTY};
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, storage::unhashed, traits::{OnInitialize, OnRuntimeUpgrade}};
+use pallet_assets::TokenDossier;
 use sp_core::U256;
 
 #[test]
@@ -130,73 +131,499 @@ fn set_ask_tokens() {
 #[test]
 fn set_bid_tokens() {
 	new_test_ext().execute_with(|| {
-		let id_a: U256 = 1.into();
-		let metadata_a: Vec<u8> = vec![1, 2, 3];
+		let (id, asset_id) = setup_sale();
 		assert_noop!(
-            SocialNft::set_bid(Origin::signed(1), id_a, 1, 1, 1),
+            SocialNft::set_bid(Origin::signed(USER_C), 2.into(), asset_id, 1, 1),
             Error::<Test>::NftIdDoesNotExist
         );
 
-		assert_ok!(SocialNft::mint(
-            Origin::signed(1), // Origin::root(),
-            USER_A,
-            id_a,
-            metadata_a.clone(),
-			ROYALTY
-        ));
-
 		assert_ok!(
-            SocialNft::set_ask(Origin::signed(USER_A), id_a, 1, 0)
+            SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 0)
         );
 
 		assert_noop!(
-            SocialNft::set_bid(Origin::signed(USER_A), id_a, 1, 1, 1),
+            SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 1, 1),
             Error::<Test>::NotForSale
         );
 
 		assert_ok!(
-            SocialNft::set_ask(Origin::signed(USER_A), id_a, 1, 1)
+            SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 500)
+        );
+		Timestamp::set_timestamp(10);
+		assert_noop!(
+            SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 400, 5),
+            Error::<Test>::BidExpired
         );
 
+		// bids below the ask are escrowed
+		assert_ok!(
+            SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 400, 100)
+        );
+		assert_eq!(Assets::balance(asset_id, USER_C), 9_600);
+		assert_eq!(Assets::balance(asset_id, SocialNft::account_id()), 400);
 		assert_ok!(
-            SocialNft::set_bid(Origin::signed(USER_A), id_a, 1, 1, 1)
+            SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 450, 100)
         );
+		assert_eq!(Assets::balance(asset_id, USER_C), 9_550);
+		assert_eq!(SocialNft::bid_token(id, USER_C).unwrap().amount, 450);
 
-		assert!(<TokenBidAmount<Test>>::contains_key(id_a, 1));
+		// a crossing bid buys at the ask
+		assert_ok!(
+            SocialNft::set_bid(Origin::signed(USER_D), id, asset_id, 600, 100)
+        );
+		assert_eq!(SocialNft::owner_of(id).1, USER_D);
+		assert_eq!(Assets::balance(asset_id, USER_D), 9_500);
+		assert_eq!(Assets::balance(asset_id, USER_A), 10);
+		assert_eq!(Assets::balance(asset_id, USER_B), 490);
+		assert!(!<TokenAskAmount<Test>>::contains_key(id, asset_id));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_nft(RawEvent::Traded(id, USER_B, USER_D, 500))));
 	})
 }
 
 #[test]
 fn remove_bid_tokens() {
 	new_test_ext().execute_with(|| {
-		let id_a: U256 = 1.into();
-		let metadata_a: Vec<u8> = vec![1, 2, 3];
+		let (id, asset_id) = setup_sale();
 
 		assert_noop!(
-            SocialNft::remove_bid(Origin::signed(1), id_a),
-            Error::<Test>::NftIdDoesNotExist
+            SocialNft::remove_bid(Origin::signed(USER_C), id),
+            Error::<Test>::BidNotExists
         );
 
-		assert_ok!(SocialNft::mint(
-            Origin::signed(1), // Origin::root(),
-            USER_A,
-            id_a,
-            metadata_a.clone(),
-			ROYALTY
-        ));
-
 		assert_ok!(
-            SocialNft::set_ask(Origin::signed(USER_A), id_a, 1, 1)
+            SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 500)
         );
 
 		assert_ok!(
-            SocialNft::set_bid(Origin::signed(USER_A), id_a, 1, 1, 1)
+            SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 400, 100)
         );
 
 		assert_ok!(
-            SocialNft::remove_bid(Origin::signed(1), id_a),
+            SocialNft::remove_bid(Origin::signed(USER_C), id),
         );
 
-		assert!(!SocialNft::bid_token(id_a, 1).unwrap().is_active);
+		assert_eq!(SocialNft::bid_token(id, USER_C), None);
+		assert_eq!(Assets::balance(asset_id, USER_C), 10_000);
+	})
+}
+
+#[test]
+fn accept_bid_should_work() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		assert_ok!(SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 1_000));
+		assert_ok!(SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 400, 100));
+		assert_ok!(SocialNft::set_bid(Origin::signed(USER_D), id, asset_id, 300, 100));
+		assert_eq!(SocialNft::bid_count(id), 2);
+		assert_noop!(SocialNft::set_bid(Origin::signed(USER_A), id, asset_id, 200, 100), Error::<Test>::TooManyBids);
+
+		assert_noop!(SocialNft::accept_bid(Origin::signed(USER_C), id, USER_D), Error::<Test>::NotOwner);
+		assert_noop!(SocialNft::accept_bid(Origin::signed(USER_B), id, USER_A), Error::<Test>::BidNotExists);
+
+		// lowering the ask below the best bid sells at the bid
+		assert_ok!(SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 350));
+		assert_eq!(SocialNft::owner_of(id).1, USER_C);
+		assert_eq!(Assets::balance(asset_id, USER_A), 8);
+		assert_eq!(Assets::balance(asset_id, USER_B), 392);
+		assert_eq!(SocialNft::bid_token(id, USER_C), None);
+		assert_eq!(SocialNft::bid_count(id), 1);
+
+		assert_ok!(SocialNft::accept_bid(Origin::signed(USER_C), id, USER_D));
+		assert_eq!(SocialNft::bid_count(id), 0);
+		assert_eq!(SocialNft::owner_of(id).1, USER_D);
+		assert_eq!(Assets::balance(asset_id, USER_A), 14);
+		assert_eq!(Assets::balance(asset_id, USER_C), 9_894);
+		assert_eq!(Assets::balance(asset_id, SocialNft::account_id()), 0);
+	})
+}
+
+#[test]
+fn expired_bids_should_be_refunded() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		assert_ok!(SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 1_000));
+		assert_ok!(SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 400, 100));
+		assert_noop!(
+			SocialNft::remove_expired_bid(Origin::signed(USER_A), id, USER_C),
+			Error::<Test>::BidNotExpired
+		);
+
+		Timestamp::set_timestamp(101);
+		assert_noop!(SocialNft::accept_bid(Origin::signed(USER_B), id, USER_C), Error::<Test>::BidExpired);
+		assert_ok!(SocialNft::remove_expired_bid(Origin::signed(USER_A), id, USER_C));
+		assert_eq!(SocialNft::bid_token(id, USER_C), None);
+		assert_eq!(Assets::balance(asset_id, USER_C), 10_000);
+	})
+}
+
+#[test]
+fn runtime_upgrade_should_drop_unescrowed_bids() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		// a bid of the previous runtime, flagged active and not escrowed
+		unhashed::put(&<TokenBidAmount<Test>>::hashed_key_for(id, USER_C), &(400u128, asset_id, 100u128, true));
+		StorageVersion::put(Releases::V1);
+
+		SocialNft::on_runtime_upgrade();
+		assert_eq!(SocialNft::bid_token(id, USER_C), None);
+		assert_eq!(StorageVersion::get(), Releases::V2);
+
+		// escrowed bids survive later upgrades
+		assert_ok!(SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 1_000));
+		assert_ok!(SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 400, 100));
+		SocialNft::on_runtime_upgrade();
+		assert_eq!(SocialNft::bid_token(id, USER_C).unwrap().amount, 400);
+	})
+}
+
+const USER_D: u64 = 0x4;
+
+/// Mint token 1 created by `USER_A` and owned by `USER_B`, and a payment asset held by the buyers
+fn setup_sale() -> (U256, u32) {
+	let id: U256 = 1.into();
+	assert_ok!(SocialNft::mint(Origin::signed(1), USER_A, id, vec![1, 2, 3], ROYALTY));
+	assert_ok!(SocialNft::transfer(Origin::signed(USER_A), USER_B, id));
+
+	let asset_id = <Assets as IssueAndBurn<_, _>>::create_new_asset(&USER_A, TokenDossier::default(), 10, 1).unwrap();
+	assert_ok!(<Assets as IssueAndBurn<_, _>>::issue(&asset_id, &USER_C, 10_000));
+	assert_ok!(<Assets as IssueAndBurn<_, _>>::issue(&asset_id, &USER_D, 10_000));
+	(id, asset_id)
+}
+
+#[test]
+fn create_auction_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		assert_noop!(
+			SocialNft::create_auction(Origin::signed(USER_B), 2.into(), AuctionKind::English, asset_id, 100, 1, 10),
+			Error::<Test>::NftIdDoesNotExist
+		);
+		assert_noop!(
+			SocialNft::create_auction(Origin::signed(USER_A), id, AuctionKind::English, asset_id, 100, 1, 10),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, 42, 100, 1, 10),
+			Error::<Test>::AssetNotExists
+		);
+		assert_noop!(
+			SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, asset_id, 100, 10, 10),
+			Error::<Test>::InvalidAuctionPeriod
+		);
+		assert_noop!(
+			SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::Dutch(99), asset_id, 100, 1, 10),
+			Error::<Test>::InvalidAuctionPrice
+		);
+
+		assert_ok!(SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, asset_id, 100, 1, 10));
+		assert!(<AuctionEndsAt<Test>>::contains_key(10, id));
+		assert_noop!(
+			SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, asset_id, 100, 1, 10),
+			Error::<Test>::AuctionAlreadyExists
+		);
+		assert_noop!(
+			SocialNft::transfer(Origin::signed(USER_B), USER_C, id),
+			Error::<Test>::TokenInAuction
+		);
+		assert_noop!(
+			SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 1),
+			Error::<Test>::TokenInAuction
+		);
+	})
+}
+
+#[test]
+fn english_auction_should_work() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		let pot = SocialNft::account_id();
+		assert_ok!(SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, asset_id, 100, 1, 20));
+
+		assert_noop!(SocialNft::bid_auction(Origin::signed(USER_C), id, 99), Error::<Test>::BidTooLow);
+		assert_ok!(SocialNft::bid_auction(Origin::signed(USER_C), id, 100));
+		assert_eq!(Assets::balance(asset_id, USER_C), 9_900);
+		assert_eq!(Assets::balance(asset_id, pot), 100);
+
+		// outbidding refunds the previous bid
+		assert_noop!(SocialNft::bid_auction(Origin::signed(USER_D), id, 100), Error::<Test>::BidTooLow);
+		assert_ok!(SocialNft::bid_auction(Origin::signed(USER_D), id, 150));
+		assert_eq!(Assets::balance(asset_id, USER_C), 10_000);
+		assert_eq!(Assets::balance(asset_id, pot), 150);
+
+		// a late bid extends the auction
+		System::set_block_number(18);
+		assert_ok!(SocialNft::bid_auction(Origin::signed(USER_C), id, 200));
+		assert_eq!(SocialNft::auctions(id).unwrap().end, 23);
+		assert!(!<AuctionEndsAt<Test>>::contains_key(20, id));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_nft(RawEvent::AuctionExtended(id, 23))));
+		assert_noop!(SocialNft::cancel_auction(Origin::signed(USER_B), id), Error::<Test>::AuctionHasBids);
+
+		SocialNft::on_initialize(21);
+		assert_eq!(SocialNft::owner_of(id).1, USER_B);
+
+		SocialNft::on_initialize(24);
+		assert_eq!(SocialNft::owner_of(id), (USER_A, USER_C));
+		assert_eq!(SocialNft::auctions(id), None);
+		assert_eq!(Assets::balance(asset_id, USER_A), 4);
+		assert_eq!(Assets::balance(asset_id, USER_B), 196);
+		assert_eq!(Assets::balance(asset_id, USER_C), 9_800);
+		assert_eq!(Assets::balance(asset_id, USER_D), 10_000);
+		assert_eq!(Assets::balance(asset_id, pot), 0);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_nft(RawEvent::AuctionSettled(id, USER_C, 200))));
+	})
+}
+
+#[test]
+fn dutch_auction_should_work() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		assert_ok!(SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::Dutch(1_000), asset_id, 100, 10, 20));
+		assert_noop!(SocialNft::bid_auction(Origin::signed(USER_C), id, 1_000), Error::<Test>::AuctionNotActive);
+
+		System::set_block_number(15);
+		assert_eq!(SocialNft::dutch_price(1_000, 100, 10, 20, 15), 550);
+		assert_noop!(SocialNft::bid_auction(Origin::signed(USER_C), id, 500), Error::<Test>::BidTooLow);
+		// the current price is paid, not the limit
+		assert_ok!(SocialNft::bid_auction(Origin::signed(USER_C), id, 600));
+		assert_eq!(Assets::balance(asset_id, USER_C), 9_450);
+		assert_noop!(SocialNft::bid_auction(Origin::signed(USER_D), id, 1_000), Error::<Test>::AuctionNotActive);
+
+		SocialNft::on_initialize(16);
+		assert_eq!(SocialNft::owner_of(id).1, USER_C);
+		assert_eq!(Assets::balance(asset_id, USER_A), 11);
+		assert_eq!(Assets::balance(asset_id, USER_B), 539);
+		assert!(!<AuctionEndsAt<Test>>::contains_key(20, id));
+	})
+}
+
+#[test]
+fn auction_without_bids_should_close() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		assert_ok!(SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, asset_id, 100, 1, 10));
+
+		SocialNft::on_initialize(11);
+		assert_eq!(SocialNft::auctions(id), None);
+		assert_eq!(SocialNft::owner_of(id).1, USER_B);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_nft(RawEvent::AuctionCancelled(id))));
+
+		assert_ok!(SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, asset_id, 100, 1, 10));
+		assert_noop!(SocialNft::cancel_auction(Origin::signed(USER_C), id), Error::<Test>::NotOwner);
+		assert_ok!(SocialNft::cancel_auction(Origin::signed(USER_B), id));
+		assert!(!<AuctionEndsAt<Test>>::contains_key(10, id));
+		assert_ok!(SocialNft::transfer(Origin::signed(USER_B), USER_C, id));
+	})
+}
+
+#[test]
+fn auction_settlement_should_carry_over() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		let ids: Vec<U256> = vec![id, 2.into(), 3.into()];
+		for id in &ids[1..] {
+			assert_ok!(SocialNft::mint(Origin::signed(1), USER_B, *id, vec![], 0));
+		}
+		for id in &ids {
+			assert_ok!(SocialNft::create_auction(Origin::signed(USER_B), *id, AuctionKind::English, asset_id, 100, 1, 10));
+		}
+
+		// nothing is settled before the end block has passed
+		SocialNft::on_initialize(10);
+		assert_eq!(<Auctions<Test>>::iter().count(), 3);
+		assert_eq!(SocialNft::auctions_settled_before(), 10);
+
+		// at most two auctions are settled in a block
+		SocialNft::on_initialize(11);
+		assert_eq!(<Auctions<Test>>::iter().count(), 1);
+		assert_eq!(SocialNft::auctions_settled_before(), 10);
+
+		SocialNft::on_initialize(12);
+		assert_eq!(<Auctions<Test>>::iter().count(), 0);
+		assert_eq!(<AuctionEndsAt<Test>>::iter_prefix(10).count(), 0);
+		assert_eq!(SocialNft::auctions_settled_before(), 12);
+	})
+}
+
+#[test]
+fn collection_mint_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SocialNft::create_collection(Origin::signed(USER_A), vec![1], vec![(USER_A, Percent::from_percent(1)); 4], None, MintPolicy::Issuer),
+			Error::<Test>::TooManyRoyaltyRecipients
+		);
+		assert_noop!(
+			SocialNft::create_collection(
+				Origin::signed(USER_A),
+				vec![1],
+				vec![(USER_A, Percent::from_percent(60)), (USER_B, Percent::from_percent(41))],
+				None,
+				MintPolicy::Issuer
+			),
+			Error::<Test>::RoyaltyTooHigh
+		);
+		assert_noop!(
+			SocialNft::mint_in_collection(Origin::signed(USER_A), 1, USER_A, vec![1]),
+			Error::<Test>::CollectionNotExists
+		);
+
+		assert_ok!(SocialNft::create_collection(Origin::signed(USER_A), vec![1], vec![], Some(2), MintPolicy::Issuer));
+		assert_eq!(SocialNft::next_collection_id(), 2);
+		assert_noop!(
+			SocialNft::mint_in_collection(Origin::signed(USER_B), 1, USER_B, vec![1]),
+			Error::<Test>::MintNotAllowed
+		);
+		assert_ok!(SocialNft::mint_in_collection(Origin::signed(USER_A), 1, USER_B, vec![1]));
+		assert_ok!(SocialNft::mint_in_collection(Origin::signed(USER_A), 1, USER_C, vec![2]));
+		assert_noop!(
+			SocialNft::mint_in_collection(Origin::signed(USER_A), 1, USER_C, vec![3]),
+			Error::<Test>::CollectionFull
+		);
+
+		let first = SocialNft::collection_token_id(1, 0);
+		let second = SocialNft::collection_token_id(1, 1);
+		assert_eq!(second, (U256::one() << 128) + U256::one());
+		// plain tokens can't take the ids of collection tokens
+		assert_noop!(
+			SocialNft::mint(Origin::signed(USER_A), USER_A, SocialNft::collection_token_id(3, 0), vec![], 0),
+			Error::<Test>::InvalidTokenId
+		);
+		assert_eq!(SocialNft::owner_of(first), (USER_A, USER_B));
+		assert_eq!(SocialNft::owner_of(second), (USER_A, USER_C));
+		assert_eq!(SocialNft::token_collection(second), Some(1));
+		assert_eq!(SocialNft::collections(1).unwrap().minted, 2);
+
+		// anyone mints in a public collection and is the creator
+		assert_ok!(SocialNft::create_collection(Origin::signed(USER_A), vec![2], vec![], None, MintPolicy::Public));
+		assert_ok!(SocialNft::mint_in_collection(Origin::signed(USER_B), 2, USER_C, vec![1]));
+		assert_eq!(SocialNft::owner_of(SocialNft::collection_token_id(2, 0)), (USER_B, USER_C));
+
+		assert_ok!(SocialNft::burn(Origin::signed(1), second));
+		assert_eq!(SocialNft::token_collection(second), None);
+	})
+}
+
+#[test]
+fn freeze_collection_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SocialNft::create_collection(Origin::signed(USER_A), vec![1], vec![], None, MintPolicy::Issuer));
+		assert_noop!(
+			SocialNft::set_collection_metadata(Origin::signed(USER_B), 1, vec![2]),
+			Error::<Test>::NotOwner
+		);
+		assert_ok!(SocialNft::set_collection_metadata(Origin::signed(USER_A), 1, vec![2]));
+		assert_ok!(SocialNft::set_collection_royalty(Origin::signed(USER_A), 1, vec![(USER_B, Percent::from_percent(5))]));
+		assert_eq!(SocialNft::collections(1).unwrap().metadata, vec![2]);
+		assert_eq!(SocialNft::collections(1).unwrap().royalty, vec![(USER_B, Percent::from_percent(5))]);
+
+		assert_noop!(SocialNft::freeze_collection(Origin::signed(USER_B), 1), Error::<Test>::NotOwner);
+		assert_ok!(SocialNft::freeze_collection(Origin::signed(USER_A), 1));
+		assert_noop!(
+			SocialNft::set_collection_metadata(Origin::signed(USER_A), 1, vec![3]),
+			Error::<Test>::CollectionFrozen
+		);
+		assert_noop!(
+			SocialNft::set_collection_royalty(Origin::signed(USER_A), 1, vec![]),
+			Error::<Test>::CollectionFrozen
+		);
+		// minting continues in a frozen collection
+		assert_ok!(SocialNft::mint_in_collection(Origin::signed(USER_A), 1, USER_B, vec![1]));
+	})
+}
+
+#[test]
+fn collection_royalty_should_be_split() {
+	new_test_ext().execute_with(|| {
+		let (_, asset_id) = setup_sale();
+		let royalty = vec![(USER_A, Percent::from_percent(10)), (USER_D, Percent::from_percent(5))];
+		assert_ok!(SocialNft::create_collection(Origin::signed(USER_A), vec![1], royalty, None, MintPolicy::Issuer));
+		assert_ok!(SocialNft::mint_in_collection(Origin::signed(USER_A), 1, USER_B, vec![1]));
+		let id = SocialNft::collection_token_id(1, 0);
+
+		assert_ok!(SocialNft::create_auction(Origin::signed(USER_B), id, AuctionKind::English, asset_id, 100, 1, 10));
+		assert_ok!(SocialNft::bid_auction(Origin::signed(USER_C), id, 1_000));
+		SocialNft::on_initialize(11);
+
+		assert_eq!(SocialNft::owner_of(id).1, USER_C);
+		assert_eq!(Assets::balance(asset_id, USER_A), 100);
+		assert_eq!(Assets::balance(asset_id, USER_D), 10_050);
+		assert_eq!(Assets::balance(asset_id, USER_B), 850);
+	})
+}
+
+#[test]
+fn owner_index_should_follow_tokens() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		assert_ok!(SocialNft::mint(Origin::signed(1), USER_B, 2.into(), vec![4], ROYALTY));
+		assert!(!<TokensOfOwner<Test>>::contains_key(USER_A, id));
+		let mut owned = SocialNft::tokens_of_owner(USER_B, 0, 10);
+		owned.sort();
+		assert_eq!(owned, vec![id, 2.into()]);
+		assert_eq!(SocialNft::tokens_of_owner(USER_B, 1, 10).len(), 1);
+		assert_eq!(SocialNft::tokens_of_owner(USER_B, 0, 1).len(), 1);
+
+		// sales move the token in the index
+		assert_ok!(SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 500));
+		assert_ok!(SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 500, 100));
+		assert_eq!(SocialNft::tokens_of_owner(USER_B, 0, 10), vec![U256::from(2)]);
+		assert_eq!(SocialNft::tokens_of_owner(USER_C, 0, 10), vec![id]);
+
+		assert_ok!(SocialNft::burn(Origin::signed(1), id));
+		assert!(SocialNft::tokens_of_owner(USER_C, 0, 10).is_empty());
+	})
+}
+
+#[test]
+fn owner_index_should_be_built_in_batches() {
+	new_test_ext().execute_with(|| {
+		for id in 1..4u32 {
+			assert_ok!(SocialNft::mint(Origin::signed(1), USER_B, id.into(), vec![], 0));
+		}
+		// tokens minted before the index existed
+		<TokensOfOwner<Test>>::remove_prefix(USER_B);
+		OwnerIndexBuilt::put(false);
+
+		SocialNft::on_initialize(2);
+		assert_eq!(SocialNft::tokens_of_owner(USER_B, 0, 10).len(), 2);
+		assert!(!OwnerIndexBuilt::get());
+		assert!(OwnerIndexCursor::get().is_some());
+
+		SocialNft::on_initialize(3);
+		assert_eq!(SocialNft::tokens_of_owner(USER_B, 0, 10).len(), 3);
+		assert!(OwnerIndexBuilt::get());
+		assert_eq!(OwnerIndexCursor::get(), None);
+	})
+}
+
+#[test]
+fn market_queries_should_work() {
+	new_test_ext().execute_with(|| {
+		let (id, asset_id) = setup_sale();
+		assert_eq!(SocialNft::token_details(2.into()), None);
+		assert_eq!(SocialNft::token_details(id), Some(TokenDetails {
+			id,
+			metadata: vec![1, 2, 3],
+			creator: USER_A,
+			owner: USER_B,
+			collection: None,
+			royalty: vec![(USER_A, Percent::from_percent(2))],
+		}));
+
+		assert_ok!(SocialNft::set_ask(Origin::signed(USER_B), id, asset_id, 500));
+		assert_ok!(SocialNft::set_bid(Origin::signed(USER_C), id, asset_id, 400, 100));
+		assert_ok!(SocialNft::set_bid(Origin::signed(USER_D), id, asset_id, 300, 10));
+		assert_eq!(SocialNft::asks(id), vec![(asset_id, 500)]);
+		assert_eq!(SocialNft::bids(id).len(), 2);
+
+		Timestamp::set_timestamp(50);
+		assert_eq!(SocialNft::bids(id), vec![(USER_C, asset_id, 400, 100)]);
+
+		// asks of a previous owner are not listed
+		assert_ok!(SocialNft::transfer(Origin::signed(USER_B), USER_D, id));
+		assert!(SocialNft::asks(id).is_empty());
 	})
 }
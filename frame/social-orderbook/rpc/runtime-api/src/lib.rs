@@ -0,0 +1,25 @@
+//! Runtime API definition of the Social Orderbook.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to expose the depth of the order books
+//! of social tokens.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the order books of the social orderbook module.
+	pub trait SocialOrderbookApi<AssetId, Balance, Price> where
+		AssetId: Codec,
+		Balance: Codec,
+		Price: Codec,
+	{
+		/// Remaining `base` tokens and their total at every price of the book of `base`
+		/// against `quote`, best first, for at most `levels` prices of each side.
+		///
+		/// Returns `(bids, asks)`.
+		fn depth(base: AssetId, quote: AssetId, levels: u32) -> (Vec<(Price, Balance)>, Vec<(Price, Balance)>);
+	}
+}
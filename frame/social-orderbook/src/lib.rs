@@ -0,0 +1,415 @@
+//! An order book of limit orders between any two `pallet_assets` tokens.
+//!
+//! The assets of every order are escrowed in the pot of this module until the order
+//! is filled, cancelled or expires. A new order is matched within its extrinsic against
+//! the opposite side of the book with price-time priority, always at the price of the
+//! resting order, and the rest of it is kept on the book. A single extrinsic fills at
+//! most `MaxFills` resting orders so its weight stays bounded.
+//!
+//! Prices are fixed point numbers of quote tokens per base token, the quote tokens of a
+//! fill are rounded down.
+//!
+//! An order resting on the book reserves `OrderDeposit` of the native currency of its
+//! owner until it is filled, cancelled or expires, so filling a side of the book with
+//! dust orders is not free. A full side of the book only takes an order priced better
+//! than its worst resting order, which is evicted and refunded.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure,
+    traits::{Currency, Get, ReservableCurrency},
+    transactional,
+};
+use frame_system::ensure_signed;
+use pallet_assets::{Fungible, IssueAndBurn};
+use sp_runtime::{
+    traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
+    DispatchResult, FixedPointNumber, FixedU128, ModuleId, RuntimeDebug,
+};
+use sp_std::{cmp, convert::TryInto, prelude::*};
+
+pub type BalanceOf<T> = <<T as Config>::FungibleToken as Fungible<
+    <T as pallet_assets::Config>::AssetId,
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+pub type DepositOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type OrderId = u64;
+pub type OrderOf<T> = Order<
+    <T as frame_system::Config>::AccountId,
+    <T as pallet_assets::Config>::AssetId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+/// A side of the book of a pair, `(base, quote, side)`
+pub type BookIdOf<T> = (
+    <T as pallet_assets::Config>::AssetId,
+    <T as pallet_assets::Config>::AssetId,
+    OrderSide,
+);
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum OrderSide {
+    /// buy base tokens with quote tokens
+    Buy,
+    /// sell base tokens for quote tokens
+    Sell,
+}
+
+impl OrderSide {
+    pub fn opposite(self) -> Self {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Order<AccountId, AssetId, Balance, BlockNumber> {
+    pub owner: AccountId,
+    pub base: AssetId,
+    pub quote: AssetId,
+    pub side: OrderSide,
+    /// quote tokens paid for one base token
+    pub price: FixedU128,
+    /// base tokens ordered
+    pub amount: Balance,
+    /// base tokens not filled yet
+    pub remaining: Balance,
+    /// the order can not be filled from this block on
+    pub expiry: BlockNumber,
+}
+
+impl<AccountId, AssetId, Balance, BlockNumber> Order<AccountId, AssetId, Balance, BlockNumber> {
+    /// Whether this order can be filled by a resting order at `price`
+    fn crosses(&self, price: &FixedU128) -> bool {
+        match self.side {
+            OrderSide::Buy => *price <= self.price,
+            OrderSide::Sell => *price >= self.price,
+        }
+    }
+}
+
+pub trait Config: frame_system::Config + pallet_assets::Config {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// pot escrowing the assets of the orders
+    type ModuleId: Get<ModuleId>;
+    type FungibleToken: IssueAndBurn<Self::AssetId, Self::AccountId>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// native currency reserved for every order resting on the book
+    type OrderDeposit: Get<DepositOf<Self>>;
+    /// maximum number of resting orders on one side of the book of a pair, beyond which the
+    /// worst one is evicted by better orders
+    type MaxOrdersPerSide: Get<u32>;
+    /// maximum number of resting orders filled or expired by one order
+    type MaxFills: Get<u32>;
+}
+
+decl_event! {
+    pub enum Event<T> where
+        <T as frame_system::Config>::AccountId,
+        <T as pallet_assets::Config>::AssetId,
+        Balance = BalanceOf<T>,
+    {
+        /// A limit order placed. [order_id, owner, base, quote, side, price, amount]
+        OrderPlaced(OrderId, AccountId, AssetId, AssetId, OrderSide, FixedU128, Balance),
+        /// A resting order filled by a new one. [maker_order_id, taker_order_id, maker, taker, price, amount]
+        OrderFilled(OrderId, OrderId, AccountId, AccountId, FixedU128, Balance),
+        /// An order cancelled, its escrow refunded. [order_id, owner, remaining]
+        OrderCancelled(OrderId, AccountId, Balance),
+        /// An expired order removed from the book, its escrow refunded. [order_id, owner, remaining]
+        OrderExpired(OrderId, AccountId, Balance),
+        /// The worst order of a full side of the book evicted by a better one, its escrow refunded.
+        /// [order_id, owner, remaining]
+        OrderEvicted(OrderId, AccountId, Balance),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// base and quote are the same asset
+        IdenticalAssets,
+        /// base or quote does not exist
+        AssetNotExists,
+        /// price, amount or the quote tokens they are worth is zero
+        ZeroAmount,
+        /// expiry is not in the future
+        InvalidExpiry,
+        OrderNotExists,
+        /// only the owner can cancel an order before it expires
+        NotOrderOwner,
+        /// the side of the book is full and the order is not better than its worst one
+        BookFull,
+        OverFlow,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as SocialOrderbook {
+        pub Orders get(fn orders): map hasher(twox_64_concat) OrderId => Option<OrderOf<T>>;
+
+        /// Resting orders of a side of a book as `(price, order_id)`, best first
+        pub Book get(fn book): map hasher(blake2_128_concat) BookIdOf<T> => Vec<(FixedU128, OrderId)>;
+
+        pub NextOrderId get(fn next_order_id): OrderId;
+
+        /// Native currency reserved by the owner of a resting order
+        pub Deposits get(fn deposit_of): map hasher(twox_64_concat) OrderId => DepositOf<T>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+
+        fn deposit_event() = default;
+
+        /// Buy or sell `amount` base tokens at `price` quote tokens each, or better.
+        /// The order is matched right away, whatever is left rests on the book until `expiry`.
+        #[weight = 50_000_000 + T::DbWeight::get().reads_writes(
+            8 + 3 * T::MaxFills::get() as u64,
+            11 + 6 * T::MaxFills::get() as u64,
+        )]
+        #[transactional]
+        fn place_limit_order(
+            origin,
+            base: T::AssetId,
+            quote: T::AssetId,
+            side: OrderSide,
+            price: FixedU128,
+            amount: BalanceOf<T>,
+            expiry: T::BlockNumber
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            ensure!(base != quote, Error::<T>::IdenticalAssets);
+            ensure!(T::FungibleToken::exists(&base) && T::FungibleToken::exists(&quote), Error::<T>::AssetNotExists);
+            ensure!(!price.is_zero() && !amount.is_zero(), Error::<T>::ZeroAmount);
+            ensure!(expiry > <frame_system::Module<T>>::block_number(), Error::<T>::InvalidExpiry);
+
+            let mut order = Order { owner, base, quote, side, price, amount, remaining: amount, expiry };
+            let (escrow_asset, escrow) = Self::escrow_of(&order)?;
+            ensure!(!escrow.is_zero(), Error::<T>::ZeroAmount);
+            T::FungibleToken::transfer(&escrow_asset, &order.owner, &Self::account_id(), escrow)?;
+
+            let order_id = Self::next_order_id();
+            <NextOrderId>::put(order_id.checked_add(1).ok_or(Error::<T>::OverFlow)?);
+            Self::deposit_event(RawEvent::OrderPlaced(
+                order_id, order.owner.clone(), base, quote, side, price, amount));
+
+            Self::match_order(order_id, &mut order)?;
+
+            if !order.remaining.is_zero() {
+                let book_id = (base, quote, side);
+                let mut book = Self::book(&book_id);
+                if book.len() >= T::MaxOrdersPerSide::get() as usize {
+                    let (worst_price, worst_id) = *book.last().ok_or(Error::<T>::BookFull)?;
+                    let better = match side {
+                        OrderSide::Buy => price > worst_price,
+                        OrderSide::Sell => price < worst_price,
+                    };
+                    ensure!(better, Error::<T>::BookFull);
+                    book.pop();
+                    if let Some(worst) = Self::orders(worst_id) {
+                        Self::refund(&worst)?;
+                        Self::remove_order(worst_id, &worst.owner);
+                        Self::deposit_event(RawEvent::OrderEvicted(worst_id, worst.owner, worst.remaining));
+                    }
+                }
+                // behind every order at the same price or better
+                let position = book
+                    .iter()
+                    .position(|(p, _)| match side {
+                        OrderSide::Buy => *p < price,
+                        OrderSide::Sell => *p > price,
+                    })
+                    .unwrap_or(book.len());
+                let deposit = T::OrderDeposit::get();
+                <T as Config>::Currency::reserve(&order.owner, deposit)?;
+                <Deposits<T>>::insert(order_id, deposit);
+                book.insert(position, (price, order_id));
+                <Book<T>>::insert(&book_id, book);
+                <Orders<T>>::insert(order_id, order);
+            }
+
+            Ok(())
+        }
+
+        /// Cancel an order and refund what is left of its escrow.
+        /// Anyone can remove an order once it expired.
+        #[weight = 30_000_000 + T::DbWeight::get().reads_writes(5, 6)]
+        #[transactional]
+        fn cancel_order(origin, order_id: OrderId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let order = Self::orders(order_id).ok_or(Error::<T>::OrderNotExists)?;
+            let expired = order.expiry <= <frame_system::Module<T>>::block_number();
+            ensure!(order.owner == who || expired, Error::<T>::NotOrderOwner);
+
+            Self::refund(&order)?;
+            Self::remove_order(order_id, &order.owner);
+            <Book<T>>::mutate((order.base, order.quote, order.side), |book| {
+                book.retain(|(_, id)| *id != order_id)
+            });
+
+            if expired {
+                Self::deposit_event(RawEvent::OrderExpired(order_id, order.owner, order.remaining));
+            } else {
+                Self::deposit_event(RawEvent::OrderCancelled(order_id, order.owner, order.remaining));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// The account id of the pot escrowing the orders
+    pub fn account_id() -> T::AccountId {
+        T::ModuleId::get().into_account()
+    }
+
+    /// Remaining base tokens and their total at every price of the book of a pair,
+    /// best first, for at most `levels` prices of each side. `(bids, asks)`
+    pub fn depth(
+        base: T::AssetId,
+        quote: T::AssetId,
+        levels: u32,
+    ) -> (Vec<(FixedU128, BalanceOf<T>)>, Vec<(FixedU128, BalanceOf<T>)>) {
+        let now = <frame_system::Module<T>>::block_number();
+        let side_depth = |side: OrderSide| {
+            let mut depth: Vec<(FixedU128, BalanceOf<T>)> = Vec::new();
+            for (price, order_id) in Self::book((base, quote, side)) {
+                let remaining = match Self::orders(order_id) {
+                    Some(order) if order.expiry > now => order.remaining,
+                    _ => continue,
+                };
+                if let Some((p, total)) = depth.last_mut() {
+                    if *p == price {
+                        *total = total.saturating_add(remaining);
+                        continue;
+                    }
+                }
+                if depth.len() >= levels as usize {
+                    break;
+                }
+                depth.push((price, remaining));
+            }
+            depth
+        };
+        (side_depth(OrderSide::Buy), side_depth(OrderSide::Sell))
+    }
+
+    /// Fill `order` against the opposite side of its book, best resting orders first
+    fn match_order(order_id: OrderId, order: &mut OrderOf<T>) -> DispatchResult {
+        let book_id = (order.base, order.quote, order.side.opposite());
+        let mut book = Self::book(&book_id);
+        let now = <frame_system::Module<T>>::block_number();
+        let mut fills = 0u32;
+        // resting orders at the front of the book which are gone
+        let mut consumed = 0usize;
+
+        while consumed < book.len() && !order.remaining.is_zero() && fills < T::MaxFills::get() {
+            let (maker_price, maker_id) = book[consumed];
+            if !order.crosses(&maker_price) {
+                break;
+            }
+            fills += 1;
+            let mut maker = match Self::orders(maker_id) {
+                Some(maker) => maker,
+                None => {
+                    consumed += 1;
+                    continue;
+                }
+            };
+            if maker.expiry <= now {
+                Self::refund(&maker)?;
+                Self::remove_order(maker_id, &maker.owner);
+                consumed += 1;
+                Self::deposit_event(RawEvent::OrderExpired(maker_id, maker.owner, maker.remaining));
+                continue;
+            }
+
+            let fill = cmp::min(order.remaining, maker.remaining);
+            Self::settle(order, &maker, fill)?;
+            order.remaining -= fill;
+            maker.remaining -= fill;
+            Self::deposit_event(RawEvent::OrderFilled(
+                maker_id, order_id, maker.owner.clone(), order.owner.clone(), maker_price, fill));
+
+            if maker.remaining.is_zero() {
+                Self::remove_order(maker_id, &maker.owner);
+                consumed += 1;
+            } else {
+                <Orders<T>>::insert(maker_id, maker);
+            }
+        }
+
+        if consumed > 0 {
+            book.drain(..consumed);
+            <Book<T>>::insert(&book_id, book);
+        }
+        Ok(())
+    }
+
+    /// Pay out `fill` base tokens traded between a new order and a resting one,
+    /// at the price of the resting order
+    fn settle(taker: &OrderOf<T>, maker: &OrderOf<T>, fill: BalanceOf<T>) -> DispatchResult {
+        let this = Self::account_id();
+        let (buy, seller) = match taker.side {
+            OrderSide::Buy => (taker, &maker.owner),
+            OrderSide::Sell => (maker, &taker.owner),
+        };
+        let cost = Self::cost(fill, maker.price)?;
+        // the escrow of the buyer for `fill`, which is at its own price and rounded down
+        // with what is left of the order
+        let released = Self::cost(buy.remaining, buy.price)?
+            .saturating_sub(Self::cost(buy.remaining - fill, buy.price)?);
+
+        T::FungibleToken::transfer(&taker.base, &this, &buy.owner, fill)?;
+        T::FungibleToken::transfer(&taker.quote, &this, seller, cost)?;
+        let improvement = released.saturating_sub(cost);
+        if !improvement.is_zero() {
+            T::FungibleToken::transfer(&taker.quote, &this, &buy.owner, improvement)?;
+        }
+        Ok(())
+    }
+
+    /// Quote tokens paid for `amount` base tokens at `price`, rounded down
+    fn cost(amount: BalanceOf<T>, price: FixedU128) -> Result<BalanceOf<T>, Error<T>> {
+        price
+            .checked_mul_int(amount.saturated_into::<u128>())
+            .and_then(|cost| cost.try_into().ok())
+            .ok_or(Error::<T>::OverFlow)
+    }
+
+    /// The asset and amount escrowed for the unfilled part of an order
+    fn escrow_of(order: &OrderOf<T>) -> Result<(T::AssetId, BalanceOf<T>), Error<T>> {
+        match order.side {
+            OrderSide::Buy => Ok((order.quote, Self::cost(order.remaining, order.price)?)),
+            OrderSide::Sell => Ok((order.base, order.remaining)),
+        }
+    }
+
+    /// Remove a resting order and release its deposit
+    fn remove_order(order_id: OrderId, owner: &T::AccountId) {
+        <Orders<T>>::remove(order_id);
+        <T as Config>::Currency::unreserve(owner, <Deposits<T>>::take(order_id));
+    }
+
+    fn refund(order: &OrderOf<T>) -> DispatchResult {
+        let (asset, amount) = Self::escrow_of(order)?;
+        if !amount.is_zero() {
+            T::FungibleToken::transfer(&asset, &Self::account_id(), &order.owner, amount)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use crate as pallet_social_orderbook;
+use sp_core::H256;
+use frame_support::parameter_types;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, ModuleId,
+};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+		Orderbook: pallet_social_orderbook::{Module, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = Call;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const AssetDepositBase: u64 = 1;
+	pub const AssetDepositPerZombie: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+}
+
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDepositBase = AssetDepositBase;
+	type AssetDepositPerZombie = AssetDepositPerZombie;
+	type StringLimit = StringLimit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const OrderbookModuleId: ModuleId = ModuleId(*b"py/ordbk");
+	pub const MaxOrdersPerSide: u32 = 3;
+	pub const MaxFills: u32 = 2;
+	pub const OrderDeposit: u64 = 10;
+}
+
+impl pallet_social_orderbook::Config for Test {
+	type Event = Event;
+	type ModuleId = OrderbookModuleId;
+	type FungibleToken = Assets;
+	type Currency = Balances;
+	type OrderDeposit = OrderDeposit;
+	type MaxOrdersPerSide = MaxOrdersPerSide;
+	type MaxFills = MaxFills;
+}
+
+pub const BASE: u32 = 1;
+pub const QUOTE: u32 = 2;
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const INITIAL_BALANCE: u64 = 1_000_000;
+pub const MAX_ZOMBIES: u32 = 10;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, INITIAL_BALANCE), (BOB, INITIAL_BALANCE), (CHARLIE, INITIAL_BALANCE)],
+	}.assimilate_storage(&mut t).unwrap();
+
+	pallet_assets::GenesisConfig::<Test> {
+		assets: vec![(BASE, ALICE, ALICE, MAX_ZOMBIES, 1), (QUOTE, ALICE, ALICE, MAX_ZOMBIES, 1)],
+		accounts: vec![
+			(BASE, ALICE, INITIAL_BALANCE),
+			(BASE, BOB, INITIAL_BALANCE),
+			(BASE, CHARLIE, INITIAL_BALANCE),
+			(QUOTE, ALICE, INITIAL_BALANCE),
+			(QUOTE, BOB, INITIAL_BALANCE),
+			(QUOTE, CHARLIE, INITIAL_BALANCE),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
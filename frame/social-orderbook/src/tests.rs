@@ -0,0 +1,238 @@
+use crate::{mock::*, Error};
+use frame_support::{assert_noop, assert_ok};
+use super::*;
+
+fn balance(asset: u32, who: u64) -> u64 {
+	pallet_assets::Module::<Test>::balance(asset, who)
+}
+
+fn price(quote: u128) -> FixedU128 {
+	FixedU128::saturating_from_integer(quote)
+}
+
+#[test]
+fn test_place_limit_order_should_rest_on_book() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(3), 100, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(2), 100, 10));
+
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Sell)), vec![(price(3), 0)]);
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Buy)), vec![(price(2), 1)]);
+		assert_eq!(Orderbook::orders(0).unwrap().remaining, 100);
+		assert_eq!(balance(BASE, Orderbook::account_id()), 100);
+		assert_eq!(balance(QUOTE, Orderbook::account_id()), 200);
+		assert_eq!(Orderbook::next_order_id(), 2);
+	});
+}
+
+#[test]
+fn test_place_limit_order_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, BASE, OrderSide::Buy, price(1), 1, 10),
+			Error::<Test>::IdenticalAssets
+		);
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, 42, OrderSide::Buy, price(1), 1, 10),
+			Error::<Test>::AssetNotExists
+		);
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(0), 1, 10),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(1), 1, 1),
+			Error::<Test>::InvalidExpiry
+		);
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(2), INITIAL_BALANCE, 10),
+			pallet_assets::Error::<Test>::BalanceLow
+		);
+
+		for _ in 0..3 {
+			assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(1), 1, 10));
+		}
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(1), 1, 10),
+			Error::<Test>::BookFull
+		);
+	});
+}
+
+#[test]
+fn test_better_order_should_evict_the_worst() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(5), 10, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(9), 10, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(9), 10, 10));
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Sell, price(9), 10, 10),
+			Error::<Test>::BookFull
+		);
+
+		// the latest of the worst priced orders goes
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Sell, price(6), 10, 10));
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Sell)), vec![(price(5), 0), (price(6), 3), (price(9), 1)]);
+		assert_eq!(Orderbook::orders(2), None);
+		assert_eq!(balance(BASE, BOB), INITIAL_BALANCE - 20);
+		assert_eq!(Balances::reserved_balance(BOB), 20);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_orderbook(RawEvent::OrderEvicted(2, BOB, 10))));
+	});
+}
+
+#[test]
+fn test_orders_should_partially_fill_at_maker_price() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(3), 100, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(CHARLIE), BASE, QUOTE, OrderSide::Sell, price(2), 50, 10));
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Sell)), vec![(price(2), 1), (price(3), 0)]);
+
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(3), 120, 10));
+
+		// 50 at 2 from charlie then 70 at 3 from bob, the improvement is refunded
+		assert_eq!(balance(BASE, ALICE), INITIAL_BALANCE + 120);
+		assert_eq!(balance(QUOTE, ALICE), INITIAL_BALANCE - 100 - 210);
+		assert_eq!(balance(QUOTE, CHARLIE), INITIAL_BALANCE + 100);
+		assert_eq!(balance(QUOTE, BOB), INITIAL_BALANCE + 210);
+		assert_eq!(balance(BASE, Orderbook::account_id()), 30);
+
+		assert_eq!(Orderbook::orders(1), None);
+		assert_eq!(Orderbook::orders(2), None);
+		assert_eq!(Orderbook::orders(0).unwrap().remaining, 30);
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Sell)), vec![(price(3), 0)]);
+		assert!(Orderbook::book((BASE, QUOTE, OrderSide::Buy)).is_empty());
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_orderbook(RawEvent::OrderFilled(1, 2, CHARLIE, ALICE, price(2), 50))));
+	});
+}
+
+#[test]
+fn test_orders_should_fill_in_time_priority() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(3), 50, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Buy, price(3), 50, 10));
+
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(CHARLIE), BASE, QUOTE, OrderSide::Sell, price(1), 60, 10));
+
+		assert_eq!(Orderbook::orders(0), None);
+		assert_eq!(Orderbook::orders(1).unwrap().remaining, 40);
+		assert_eq!(balance(BASE, ALICE), INITIAL_BALANCE + 50);
+		assert_eq!(balance(BASE, BOB), INITIAL_BALANCE + 10);
+		assert_eq!(balance(QUOTE, CHARLIE), INITIAL_BALANCE + 180);
+	});
+}
+
+#[test]
+fn test_matching_should_be_bounded() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..3 {
+			assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(3), 10, 10));
+		}
+
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(3), 30, 10));
+
+		assert_eq!(balance(BASE, ALICE), INITIAL_BALANCE + 20);
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Sell)), vec![(price(3), 2)]);
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Buy)), vec![(price(3), 3)]);
+		assert_eq!(Orderbook::orders(3).unwrap().remaining, 10);
+	});
+}
+
+#[test]
+fn test_expired_orders_should_not_fill() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(3), 50, 5));
+		System::set_block_number(5);
+
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(3), 50, 10));
+
+		assert_eq!(Orderbook::orders(0), None);
+		assert_eq!(balance(BASE, BOB), INITIAL_BALANCE);
+		assert_eq!(balance(BASE, ALICE), INITIAL_BALANCE);
+		assert!(Orderbook::book((BASE, QUOTE, OrderSide::Sell)).is_empty());
+		assert_eq!(Orderbook::book((BASE, QUOTE, OrderSide::Buy)), vec![(price(3), 1)]);
+	});
+}
+
+#[test]
+fn test_cancel_order_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(2), 100, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(3), 100, 10));
+
+		assert_noop!(Orderbook::cancel_order(Origin::signed(BOB), 0), Error::<Test>::NotOrderOwner);
+		assert_noop!(Orderbook::cancel_order(Origin::signed(ALICE), 7), Error::<Test>::OrderNotExists);
+
+		assert_ok!(Orderbook::cancel_order(Origin::signed(ALICE), 0));
+		assert_eq!(balance(QUOTE, ALICE), INITIAL_BALANCE);
+		assert_eq!(Orderbook::orders(0), None);
+		assert!(Orderbook::book((BASE, QUOTE, OrderSide::Buy)).is_empty());
+
+		// anyone can clean up an expired order
+		System::set_block_number(10);
+		assert_ok!(Orderbook::cancel_order(Origin::signed(ALICE), 1));
+		assert_eq!(balance(BASE, BOB), INITIAL_BALANCE);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_orderbook(RawEvent::OrderExpired(1, BOB, 100))));
+	});
+}
+
+#[test]
+fn test_resting_orders_should_reserve_a_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, price(3), 50, 10));
+		assert_eq!(Balances::reserved_balance(BOB), 10);
+		assert_eq!(Orderbook::deposit_of(0), 10);
+
+		// a fully filled order does not rest, its maker gets its deposit back
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(3), 50, 10));
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::reserved_balance(BOB), 0);
+		assert_eq!(Orderbook::deposit_of(0), 0);
+
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(CHARLIE), BASE, QUOTE, OrderSide::Buy, price(1), 10, 10));
+		assert_eq!(Balances::reserved_balance(CHARLIE), 10);
+		assert_ok!(Orderbook::cancel_order(Origin::signed(CHARLIE), 2));
+		assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+
+		Balances::make_free_balance_be(&CHARLIE, 5);
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(CHARLIE), BASE, QUOTE, OrderSide::Buy, price(1), 10, 10),
+			pallet_balances::Error::<Test, _>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn test_depth_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, price(4), 50, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Buy, price(4), 30, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Buy, price(3), 20, 10));
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(CHARLIE), BASE, QUOTE, OrderSide::Sell, price(5), 10, 10));
+
+		assert_eq!(Orderbook::depth(BASE, QUOTE, 10), (vec![(price(4), 80), (price(3), 20)], vec![(price(5), 10)]));
+		assert_eq!(Orderbook::depth(BASE, QUOTE, 1), (vec![(price(4), 80)], vec![(price(5), 10)]));
+	});
+}
+
+#[test]
+fn test_prices_below_one_should_work() {
+	new_test_ext().execute_with(|| {
+		let half = FixedU128::saturating_from_rational(1, 2);
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(BOB), BASE, QUOTE, OrderSide::Sell, half, 100, 10));
+		assert_noop!(
+			Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, half, 1, 10),
+			Error::<Test>::ZeroAmount
+		);
+
+		// 75 quote tokens escrowed, 50 paid and the improvement refunded
+		let three_quarters = FixedU128::saturating_from_rational(3, 4);
+		assert_ok!(Orderbook::place_limit_order(Origin::signed(ALICE), BASE, QUOTE, OrderSide::Buy, three_quarters, 100, 10));
+		assert_eq!(balance(BASE, ALICE), INITIAL_BALANCE + 100);
+		assert_eq!(balance(QUOTE, ALICE), INITIAL_BALANCE - 50);
+		assert_eq!(balance(QUOTE, BOB), INITIAL_BALANCE + 50);
+		assert_eq!(balance(QUOTE, Orderbook::account_id()), 0);
+	});
+}
@@ -0,0 +1,47 @@
+//! Runtime API definition required by Social Swap RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding Social Swap quotes and exchange queries.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_social_swap::{Exchange, SwapDirection};
+
+sp_api::decl_runtime_apis! {
+	/// The API to query prices and exchanges of the social swap module.
+	pub trait SocialSwapApi<AssetId, Balance, ExchangeId> where
+		AssetId: Codec,
+		Balance: Codec,
+		ExchangeId: Codec,
+	{
+		/// Amount bought when selling exactly `amount_in` on the given exchange.
+		///
+		/// Returns `None` if the exchange doesn't exist or has no liquidity.
+		fn get_input_price(
+			exchange_id: ExchangeId,
+			amount_in: Balance,
+			direction: SwapDirection,
+		) -> Option<Balance>;
+
+		/// Amount to sell on the given exchange to buy exactly `amount_out`.
+		///
+		/// Returns `None` if the exchange doesn't exist or can't provide `amount_out`.
+		fn get_output_price(
+			exchange_id: ExchangeId,
+			amount_out: Balance,
+			direction: SwapDirection,
+		) -> Option<Balance>;
+
+		/// The exchange registered under `exchange_id`.
+		fn get_exchange(exchange_id: ExchangeId) -> Option<Exchange<AssetId, Balance, Balance>>;
+
+		/// The exchange trading `asset_id`, along with its id.
+		fn get_exchange_by_token(asset_id: AssetId) -> Option<(ExchangeId, Exchange<AssetId, Balance, Balance>)>;
+
+		/// Native currency and trade tokens redeemed by burning `liquidity` lp tokens of the
+		/// given exchange.
+		fn get_liquidity_value(exchange_id: ExchangeId, liquidity: Balance) -> Option<(Balance, Balance)>;
+	}
+}
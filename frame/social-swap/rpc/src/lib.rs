@@ -0,0 +1,257 @@
+//! Node-specific RPC methods for quotes and exchanges of the social swap module.
+
+use std::convert::{TryFrom, TryInto};
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_social_swap_rpc_runtime_api::{
+	Exchange, SocialSwapApi as SocialSwapRuntimeApi, SwapDirection,
+};
+pub use self::gen_client::Client as SocialSwapClient;
+
+/// An RPC serializable exchange of the social swap module.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcExchange<AssetId> {
+	/// The lp token minted to liquidity providers.
+	pub lp_token: AssetId,
+	/// The token traded against native currency.
+	pub trade_token: AssetId,
+	/// Trade token amount in the exchange.
+	pub trade_token_amount: NumberOrHex,
+	/// Native currency amount in the exchange.
+	pub native_token_amount: NumberOrHex,
+}
+
+impl<AssetId, Balance: Into<NumberOrHex>> From<Exchange<AssetId, Balance, Balance>> for RpcExchange<AssetId> {
+	fn from(exchange: Exchange<AssetId, Balance, Balance>) -> Self {
+		RpcExchange {
+			lp_token: exchange.lp_token,
+			trade_token: exchange.trade_token,
+			trade_token_amount: exchange.trade_token_amount.into(),
+			native_token_amount: exchange.native_token_amount.into(),
+		}
+	}
+}
+
+/// The underlying value of an amount of lp tokens.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLiquidityValue {
+	/// Native currency redeemed.
+	pub native_token_amount: NumberOrHex,
+	/// Trade tokens redeemed.
+	pub trade_token_amount: NumberOrHex,
+}
+
+/// Social swap RPC methods.
+#[rpc]
+pub trait SocialSwapApi<BlockHash, AssetId, Balance, ExchangeId> {
+	/// Amount bought when selling exactly `amount_in` on the given exchange.
+	#[rpc(name = "socialSwap_getInputPrice")]
+	fn get_input_price(
+		&self,
+		exchange_id: ExchangeId,
+		amount_in: NumberOrHex,
+		direction: SwapDirection,
+		at: Option<BlockHash>,
+	) -> Result<Option<NumberOrHex>>;
+
+	/// Amount to sell on the given exchange to buy exactly `amount_out`.
+	#[rpc(name = "socialSwap_getOutputPrice")]
+	fn get_output_price(
+		&self,
+		exchange_id: ExchangeId,
+		amount_out: NumberOrHex,
+		direction: SwapDirection,
+		at: Option<BlockHash>,
+	) -> Result<Option<NumberOrHex>>;
+
+	/// The exchange registered under `exchange_id`.
+	#[rpc(name = "socialSwap_getExchange")]
+	fn get_exchange(
+		&self,
+		exchange_id: ExchangeId,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcExchange<AssetId>>>;
+
+	/// The exchange trading `asset_id`, along with its id.
+	#[rpc(name = "socialSwap_getExchangeByToken")]
+	fn get_exchange_by_token(
+		&self,
+		asset_id: AssetId,
+		at: Option<BlockHash>,
+	) -> Result<Option<(ExchangeId, RpcExchange<AssetId>)>>;
+
+	/// Native currency and trade tokens redeemed by burning `liquidity` lp tokens of the
+	/// given exchange.
+	#[rpc(name = "socialSwap_getLiquidityValue")]
+	fn get_liquidity_value(
+		&self,
+		exchange_id: ExchangeId,
+		liquidity: NumberOrHex,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcLiquidityValue>>;
+}
+
+/// A struct that implements the [`SocialSwapApi`].
+pub struct SocialSwap<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> SocialSwap<C, B> {
+	/// Create new `SocialSwap` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		SocialSwap { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Runtime error".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+fn try_into_balance<Balance: TryFrom<NumberOrHex>>(value: NumberOrHex) -> Result<Balance> {
+	value.try_into().map_err(|_| RpcError {
+		code: ErrorCode::InvalidParams,
+		message: format!("{:?} doesn't fit into the balance type", value),
+		data: None,
+	})
+}
+
+impl<C, Block, AssetId, Balance, ExchangeId> SocialSwapApi<
+	<Block as BlockT>::Hash,
+	AssetId,
+	Balance,
+	ExchangeId,
+> for SocialSwap<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SocialSwapRuntimeApi<Block, AssetId, Balance, ExchangeId>,
+	AssetId: Codec,
+	Balance: Codec + TryFrom<NumberOrHex> + Into<NumberOrHex>,
+	ExchangeId: Codec,
+{
+	fn get_input_price(
+		&self,
+		exchange_id: ExchangeId,
+		amount_in: NumberOrHex,
+		direction: SwapDirection,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let amount_in: Balance = try_into_balance(amount_in)?;
+		let amount_out = api.get_input_price(&at, exchange_id, amount_in, direction)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(amount_out.map(Into::into))
+	}
+
+	fn get_output_price(
+		&self,
+		exchange_id: ExchangeId,
+		amount_out: NumberOrHex,
+		direction: SwapDirection,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let amount_out: Balance = try_into_balance(amount_out)?;
+		let amount_in = api.get_output_price(&at, exchange_id, amount_out, direction)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(amount_in.map(Into::into))
+	}
+
+	fn get_exchange(
+		&self,
+		exchange_id: ExchangeId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcExchange<AssetId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let exchange = api.get_exchange(&at, exchange_id)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(exchange.map(Into::into))
+	}
+
+	fn get_exchange_by_token(
+		&self,
+		asset_id: AssetId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<(ExchangeId, RpcExchange<AssetId>)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let exchange = api.get_exchange_by_token(&at, asset_id)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(exchange.map(|(exchange_id, exchange)| (exchange_id, exchange.into())))
+	}
+
+	fn get_liquidity_value(
+		&self,
+		exchange_id: ExchangeId,
+		liquidity: NumberOrHex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcLiquidityValue>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let liquidity: Balance = try_into_balance(liquidity)?;
+		let value = api.get_liquidity_value(&at, exchange_id, liquidity)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(value.map(|(native_token_amount, trade_token_amount)| RpcLiquidityValue {
+			native_token_amount: native_token_amount.into(),
+			trade_token_amount: trade_token_amount.into(),
+		}))
+	}
+}
@@ -5,21 +5,26 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 
 use frame_support::{
     debug, decl_error, decl_event, decl_module, decl_storage, ensure,
-    traits::{Currency, ExistenceRequirement, Get},
+    traits::{Currency, EnsureOrigin, ExistenceRequirement, Get},
+    transactional,
+    weights::Weight,
     IterableStorageDoubleMap, IterableStorageMap, Parameter,
 };
 use frame_system::ensure_signed;
 use pallet_assets::{Fungible, IssueAndBurn, TokenDossier};
+use sp_core::U256;
 use sp_runtime::{
     traits::{
         AccountIdConversion, AtLeast32Bit, AtLeast32BitUnsigned, Bounded, CheckedAdd, CheckedDiv,
         CheckedMul, CheckedSub, Convert, MaybeSerializeDeserialize, Member, One,
         SaturatedConversion, Saturating, UniqueSaturatedInto, Zero,
     },
-    DispatchError, DispatchResult, ModuleId, Perbill, RuntimeDebug,
+    DispatchError, DispatchResult, ModuleId, Perbill, Permill, RuntimeDebug,
 };
 use sp_std::{cmp, convert::TryFrom, fmt::Debug, result};
 use sp_std::{ops::Div, prelude::*};
@@ -32,12 +37,14 @@ pub type BalanceOf<T> = <<T as Config>::FungibleToken as Fungible<
     <T as frame_system::Config>::AccountId,
 >>::Balance;
 
+mod router;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
-#[derive(Encode, Decode)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct Exchange<AssetId, Balance, CurrencyOf> {
     pub lp_token: AssetId,
     pub trade_token: AssetId,
@@ -45,6 +52,51 @@ pub struct Exchange<AssetId, Balance, CurrencyOf> {
     pub trade_token_amount: Balance,
     /// native currency amount in the exchange
     pub native_token_amount: CurrencyOf,
+    /// protocol fees in trade tokens held by the pot, not part of the reserves
+    pub trade_protocol_fees: Balance,
+    /// protocol fees in native currency held by the pot, not part of the reserves
+    pub native_protocol_fees: CurrencyOf,
+}
+
+/// An exchange as stored before the protocol fees were collected
+#[derive(Encode, Decode)]
+struct ExchangeV1<AssetId, Balance, CurrencyOf> {
+    lp_token: AssetId,
+    trade_token: AssetId,
+    trade_token_amount: Balance,
+    native_token_amount: CurrencyOf,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+    V1,
+    V2,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
+/// Fees charged on the input of every swap of an exchange
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FeeRates {
+    /// total fee, the part not taken by the protocol stays with the liquidity providers
+    pub swap_fee: Permill,
+    /// share of the swap fee accruing to the protocol
+    pub protocol_share: Permill,
+}
+
+/// The side of an exchange being sold in a price quote
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum SwapDirection {
+    /// sell native currency, buy trade tokens
+    NativeToTrade,
+    /// sell trade tokens, buy native currency
+    TradeToNative,
 }
 
 impl<A, B: Zero, C: Zero> Exchange<A, B, C> {
@@ -54,6 +106,8 @@ impl<A, B: Zero, C: Zero> Exchange<A, B, C> {
             trade_token,
             trade_token_amount: Zero::zero(),
             native_token_amount: Zero::zero(),
+            trade_protocol_fees: Zero::zero(),
+            native_protocol_fees: Zero::zero(),
         }
     }
 }
@@ -66,6 +120,16 @@ pub trait Config: frame_system::Config + pallet_assets::Config {
     type FungibleToken: IssueAndBurn<Self::AssetId, Self::AccountId>;
     /// help to convert native token balance to fungible token balance
     type Handler: Convert<CurrencyOf<Self>, BalanceOf<Self>>;
+    /// default fee charged on the input of a swap
+    type SwapFee: Get<Permill>;
+    /// default share of the swap fee accruing to the protocol
+    type ProtocolFeeShare: Get<Permill>;
+    /// origin allowed to override the fees of an exchange
+    type FeeOrigin: EnsureOrigin<Self::Origin>;
+    /// account the protocol fees are swept to
+    type FeeTreasury: Get<Self::AccountId>;
+    /// maximum number of tokens in the path of a route
+    type MaxPathLength: Get<u32>;
 }
 
 decl_event! {
@@ -83,6 +147,16 @@ decl_event! {
         AddLiquidity(ExchangeId, AccountId, CurrencyOf, Balance, Balance),
         /// Remove liquidity [exchange_id, liquidity_burner, native_token, trade_token, liquidity_burned]
         RemoveLiquidity(ExchangeId, AccountId, CurrencyOf, Balance, Balance),
+        /// Tokens swapped along a route [buyer, path, amount_in, amount_out, recipient]
+        RouteSwapped(AccountId, Vec<AssetId>, Balance, Balance, AccountId),
+        /// Native currency sold for trade tokens [exchange_id, buyer, native_sold, trade_bought, fee, recipient]
+        NativeToTradeSwapped(ExchangeId, AccountId, CurrencyOf, Balance, CurrencyOf, AccountId),
+        /// Trade tokens sold for native currency [exchange_id, buyer, trade_sold, native_bought, fee, recipient]
+        TradeToNativeSwapped(ExchangeId, AccountId, Balance, CurrencyOf, Balance, AccountId),
+        /// Fees of an exchange changed [exchange_id, swap_fee, protocol_share]
+        FeesUpdated(ExchangeId, Permill, Permill),
+        /// Protocol fees sent to the treasury [exchange_id, treasury, native_token, trade_token]
+        ProtocolFeesSwept(ExchangeId, AccountId, CurrencyOf, Balance),
     }
 }
 
@@ -101,6 +175,16 @@ decl_error! {
         NotQualifiedBurn,
         NotEnoughLiquidity,
         TooLow,
+        /// route has less than two tokens or passes twice through a token
+        InvalidPath,
+        /// route has more than `MaxPathLength` tokens
+        PathTooLong,
+        /// route yields less than the minimum output
+        InsufficientOutputAmount,
+        /// route costs more than the maximum input
+        ExcessiveInputAmount,
+        /// swap fee must be below 100%
+        InvalidFee,
     }
 }
 
@@ -114,6 +198,11 @@ decl_storage! {
         pub LPTokenToExchange get(fn lp_to_exchange): map hasher(twox_64_concat) T::AssetId => T::ExchangeId;
         /// The next exchange identifier
         pub NextExchangeId get(fn next_exchange_id): T::ExchangeId;
+        /// Fees of the exchanges which do not use the default ones
+        pub ExchangeFees get(fn exchange_fees): map hasher(twox_64_concat) T::ExchangeId => Option<FeeRates>;
+
+        /// Storage version of the pallet, new networks start with the exchanges holding protocol fees
+        StorageVersion build(|_| Releases::V2): Releases;
 
     }
 }
@@ -123,6 +212,12 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        const MaxPathLength: u32 = T::MaxPathLength::get();
+
+        fn on_runtime_upgrade() -> Weight {
+            Self::migrate_v1_to_v2()
+        }
+
         #[weight = 0]
         fn create_exchange(
 			origin,
@@ -139,6 +234,7 @@ decl_module! {
             let exchange_id = Self::next_exchange_id();
 
             let lp_asset_id = Self::create_lp_token(exchange_id, max_zombies, min_balance)?;
+            <NextExchangeId<T>>::put(exchange_id.checked_add(&One::one()).ok_or(Error::<T>::OverFlow)?);
             let exchange_info = Exchange::new(lp_asset_id, token_id);
             // add new exchange info
             <Exchanges<T>>::insert(&exchange_id, exchange_info);
@@ -278,7 +374,7 @@ decl_module! {
         ) {
             let buyer = ensure_signed(origin)?;
             let mut exchange = Self::exchanges(&exchange_id).ok_or(Error::<T>::ExchangeNotExists)?;
-            Self::native_to_trade_input(&mut exchange, native_sold, min_trade_tokens, deadline, &buyer, &recipient);
+            Self::native_to_trade_input(exchange_id, &mut exchange, native_sold, min_trade_tokens, deadline, &buyer, &recipient);
         }
 
         #[weight = 0]
@@ -292,7 +388,7 @@ decl_module! {
         ) {
             let buyer = ensure_signed(origin)?;
             let mut exchange = Self::exchanges(&exchange_id).ok_or(Error::<T>::ExchangeNotExists)?;
-            Self::trade_to_native_input(&mut exchange, trade_token_sold, min_native_tokens, deadline, &buyer, &recipient);
+            Self::trade_to_native_input(exchange_id, &mut exchange, trade_token_sold, min_native_tokens, deadline, &buyer, &recipient);
         }
 
         #[weight = 0]
@@ -305,7 +401,7 @@ decl_module! {
         ) {
             let buyer = ensure_signed(origin)?;
             let mut exchange = Self::exchanges(&exchange_id).ok_or(Error::<T>::ExchangeNotExists)?;
-            Self::native_to_trade_output(&mut exchange, trade_token_bought, deadline, &buyer, &recipient);
+            Self::native_to_trade_output(exchange_id, &mut exchange, trade_token_bought, deadline, &buyer, &recipient);
         }
 
         #[weight = 0]
@@ -318,7 +414,80 @@ decl_module! {
         ) {
             let buyer = ensure_signed(origin)?;
             let mut exchange = Self::exchanges(&exchange_id).ok_or(Error::<T>::ExchangeNotExists)?;
-            Self::trade_to_native_output(&mut exchange, native_token_bought, deadline, &buyer, &recipient);
+            Self::trade_to_native_output(exchange_id, &mut exchange, native_token_bought, deadline, &buyer, &recipient);
+        }
+
+        /// Sell exactly `amount_in` of the first token of `path` for as many of the last one
+        /// as possible, going through the exchanges of every token in between.
+        /// The whole route fails if it yields less than `min_amount_out`.
+        #[weight = Module::<T>::route_weight(path.len())]
+        #[transactional]
+        fn swap_exact_tokens_for_tokens(
+            origin,
+            path: Vec<T::AssetId>,
+            amount_in: BalanceOf<T>,
+            min_amount_out: BalanceOf<T>,
+            deadline: T::BlockNumber,
+            recipient: T::AccountId
+        ) -> DispatchResult {
+            let buyer = ensure_signed(origin)?;
+            Self::swap_exact_in_along_path(path, amount_in, min_amount_out, deadline, &buyer, &recipient)
+        }
+
+        /// Buy exactly `amount_out` of the last token of `path` for as few of the first one
+        /// as possible, going through the exchanges of every token in between.
+        /// The whole route fails if it costs more than `max_amount_in`.
+        #[weight = Module::<T>::route_weight(path.len())]
+        #[transactional]
+        fn swap_tokens_for_exact_tokens(
+            origin,
+            path: Vec<T::AssetId>,
+            amount_out: BalanceOf<T>,
+            max_amount_in: BalanceOf<T>,
+            deadline: T::BlockNumber,
+            recipient: T::AccountId
+        ) -> DispatchResult {
+            let buyer = ensure_signed(origin)?;
+            Self::swap_exact_out_along_path(path, amount_out, max_amount_in, deadline, &buyer, &recipient)
+        }
+
+        /// Override the fees of an exchange, `None` restores the default ones.
+        #[weight = 0]
+        fn set_exchange_fees(origin, exchange_id: T::ExchangeId, fees: Option<FeeRates>) {
+            T::FeeOrigin::ensure_origin(origin)?;
+            ensure!(<Exchanges<T>>::contains_key(&exchange_id), Error::<T>::ExchangeNotExists);
+            if let Some(rates) = fees {
+                ensure!(rates.swap_fee < Permill::one(), Error::<T>::InvalidFee);
+            }
+
+            <ExchangeFees<T>>::mutate_exists(&exchange_id, |f| *f = fees);
+            let rates = Self::fee_rates(&exchange_id);
+            Self::deposit_event(RawEvent::FeesUpdated(exchange_id, rates.swap_fee, rates.protocol_share));
+        }
+
+        /// Send the protocol fees accrued by an exchange to the treasury.
+        #[weight = 0]
+        #[transactional]
+        fn sweep_protocol_fees(origin, exchange_id: T::ExchangeId) -> DispatchResult {
+            let _ = ensure_signed(origin)?;
+            let mut exchange = Self::exchanges(&exchange_id).ok_or(Error::<T>::ExchangeNotExists)?;
+            let this = Self::account_id();
+            let treasury = T::FeeTreasury::get();
+            let native_fees = exchange.native_protocol_fees;
+            let trade_fees = exchange.trade_protocol_fees;
+
+            if !native_fees.is_zero() {
+                <T as Config>::Currency::transfer(&this, &treasury, native_fees, ExistenceRequirement::KeepAlive)?;
+            }
+            if !trade_fees.is_zero() {
+                T::FungibleToken::transfer(&exchange.trade_token, &this, &treasury, trade_fees)?;
+            }
+            exchange.native_protocol_fees = Zero::zero();
+            exchange.trade_protocol_fees = Zero::zero();
+            <Exchanges<T>>::insert(&exchange_id, exchange);
+
+            Self::deposit_event(RawEvent::ProtocolFeesSwept(exchange_id, treasury, native_fees, trade_fees));
+            Ok(())
         }
 
     }
@@ -387,6 +556,7 @@ impl<T: Config> Module<T> {
     /// change the storage
     /// put this at the end of the `native_to_trade_input` function
     fn native_to_trade_swap(
+        exchange_id: T::ExchangeId,
         exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
         native_in: CurrencyOf<T>,
         trade_token_id: &T::AssetId,
@@ -395,17 +565,24 @@ impl<T: Config> Module<T> {
         recipient: &T::AccountId,
     ) -> DispatchResult {
         let this = Self::account_id();
+        let rates = Self::fee_rates(&exchange_id);
+        let (fee, _) = Self::split_fee(&rates, native_in);
         // transfer native token in
         <T as Config>::Currency::transfer(&buyer, &this, native_in, ExistenceRequirement::KeepAlive)?;
         // modify Exchange
-        exchange.native_token_amount += native_in;
+        Self::accrue_native_in(exchange, &rates, native_in);
         T::FungibleToken::transfer(&trade_token_id, &this, recipient, trade_out)?;
         exchange.trade_token_amount -= trade_out;
+        <Exchanges<T>>::insert(&exchange_id, &*exchange);
+
+        Self::deposit_event(RawEvent::NativeToTradeSwapped(
+            exchange_id, buyer.clone(), native_in, trade_out, fee, recipient.clone()));
         Ok(())
     }
 
     /// change the storage
     fn trade_to_native_swap(
+        exchange_id: T::ExchangeId,
         exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
         trade_in: BalanceOf<T>,
         trade_token_id: &T::AssetId,
@@ -414,30 +591,194 @@ impl<T: Config> Module<T> {
         recipient: &T::AccountId,
     ) -> DispatchResult {
         let this = Self::account_id();
+        let rates = Self::fee_rates(&exchange_id);
+        let (fee, _) = Self::split_fee(&rates, trade_in);
         // transfer native token in
         <T as Config>::Currency::transfer(&this, &buyer, native_out, ExistenceRequirement::KeepAlive)?;
         // modify Exchange
         exchange.native_token_amount -= native_out;
         T::FungibleToken::transfer(&trade_token_id, recipient, &this, trade_in)?;
-        exchange.trade_token_amount += trade_in;
+        Self::accrue_trade_in(exchange, &rates, trade_in);
+        <Exchanges<T>>::insert(&exchange_id, &*exchange);
+
+        Self::deposit_event(RawEvent::TradeToNativeSwapped(
+            exchange_id, buyer.clone(), trade_in, native_out, fee, recipient.clone()));
         Ok(())
     }
 
-    // TODO: not safe
-    fn input_price_u128(input_amount: u128, input_reserve: u128, output_reserve: u128) -> u128 {
-        let input_amount_with_fee = input_amount * 997;
-        let numerator = input_amount_with_fee * output_reserve;
-        let demonator = (input_reserve * 1000) + input_amount_with_fee;
-        numerator / demonator
+    /// Fees of an exchange, the default ones unless governance overrode them
+    pub fn fee_rates(exchange_id: &T::ExchangeId) -> FeeRates {
+        Self::exchange_fees(exchange_id).unwrap_or_else(|| FeeRates {
+            swap_fee: T::SwapFee::get(),
+            protocol_share: T::ProtocolFeeShare::get(),
+        })
+    }
+
+    /// (fee, protocol part of the fee) paid on the input `amount` of a swap
+    fn split_fee<N: AtLeast32BitUnsigned + Copy>(rates: &FeeRates, amount: N) -> (N, N) {
+        let fee = rates.swap_fee.mul_floor(amount);
+        (fee, rates.protocol_share.mul_floor(fee))
+    }
+
+    /// Add trade tokens sold to an exchange, keeping the protocol fee out of the reserves
+    fn accrue_trade_in(
+        exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
+        rates: &FeeRates,
+        trade_in: BalanceOf<T>,
+    ) {
+        let (_, protocol_fee) = Self::split_fee(rates, trade_in);
+        exchange.trade_token_amount = exchange.trade_token_amount.saturating_add(trade_in - protocol_fee);
+        exchange.trade_protocol_fees = exchange.trade_protocol_fees.saturating_add(protocol_fee);
+    }
+
+    /// Add native currency sold to an exchange, keeping the protocol fee out of the reserves
+    fn accrue_native_in(
+        exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
+        rates: &FeeRates,
+        native_in: CurrencyOf<T>,
+    ) {
+        let (_, protocol_fee) = Self::split_fee(rates, native_in);
+        exchange.native_token_amount = exchange.native_token_amount.saturating_add(native_in - protocol_fee);
+        exchange.native_protocol_fees = exchange.native_protocol_fees.saturating_add(protocol_fee);
+    }
+
+    /// Amount bought when selling exactly `amount_in` on an exchange
+    pub fn get_input_price(
+        exchange_id: T::ExchangeId,
+        amount_in: u128,
+        direction: SwapDirection,
+    ) -> Option<u128> {
+        let exchange = Self::exchanges(exchange_id)?;
+        let (input_reserve, output_reserve) = Self::reserves_u128(&exchange, direction);
+        if amount_in.is_zero() || input_reserve.is_zero() || output_reserve.is_zero() {
+            return None;
+        }
+        let rates = Self::fee_rates(&exchange_id);
+        Some(Self::input_price_u128(amount_in, input_reserve, output_reserve, rates.swap_fee))
+    }
+
+    /// Amount to sell on an exchange to buy exactly `amount_out`
+    pub fn get_output_price(
+        exchange_id: T::ExchangeId,
+        amount_out: u128,
+        direction: SwapDirection,
+    ) -> Option<u128> {
+        let exchange = Self::exchanges(exchange_id)?;
+        let (input_reserve, output_reserve) = Self::reserves_u128(&exchange, direction);
+        if amount_out.is_zero() || input_reserve.is_zero() || amount_out >= output_reserve {
+            return None;
+        }
+        let rates = Self::fee_rates(&exchange_id);
+        Some(Self::output_price_u128(amount_out, input_reserve, output_reserve, rates.swap_fee))
+    }
+
+    /// The exchange trading `asset_id`, along with its id
+    pub fn get_exchange_by_token(
+        asset_id: T::AssetId,
+    ) -> Option<(T::ExchangeId, Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>)> {
+        let exchange_id = Self::tt_to_exchange(asset_id)?;
+        Self::exchanges(exchange_id).map(|exchange| (exchange_id, exchange))
+    }
+
+    /// Native currency and trade tokens redeemed by burning `liquidity` lp tokens of an exchange
+    pub fn get_liquidity_value(
+        exchange_id: T::ExchangeId,
+        liquidity: BalanceOf<T>,
+    ) -> Option<(CurrencyOf<T>, BalanceOf<T>)> {
+        let exchange = Self::exchanges(exchange_id)?;
+        let total_liquidity = T::FungibleToken::total_supply(&exchange.lp_token);
+        if total_liquidity.is_zero() || liquidity > total_liquidity {
+            return None;
+        }
+        let native_amount: u128 = liquidity
+            .checked_mul(&T::Handler::convert(exchange.native_token_amount))?
+            .div(total_liquidity)
+            .unique_saturated_into();
+        let trade_amount = liquidity
+            .checked_mul(&exchange.trade_token_amount)?
+            .div(total_liquidity);
+        Some((native_amount.saturated_into(), trade_amount))
+    }
+
+    /// (input reserve, output reserve) of an exchange for a swap in `direction`
+    fn reserves_u128(
+        exchange: &Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
+        direction: SwapDirection,
+    ) -> (u128, u128) {
+        let native_reserve: u128 = exchange.native_token_amount.unique_saturated_into();
+        let trade_reserve: u128 = exchange.trade_token_amount.unique_saturated_into();
+        match direction {
+            SwapDirection::NativeToTrade => (native_reserve, trade_reserve),
+            SwapDirection::TradeToNative => (trade_reserve, native_reserve),
+        }
+    }
+
+    fn input_price_u128(
+        input_amount: u128,
+        input_reserve: u128,
+        output_reserve: u128,
+        fee: Permill,
+    ) -> u128 {
+        let fee_base = U256::from(Permill::one().deconstruct());
+        let input_amount_with_fee = U256::from(input_amount) * (fee_base - U256::from(fee.deconstruct()));
+        let numerator = input_amount_with_fee * U256::from(output_reserve);
+        let denominator = U256::from(input_reserve) * fee_base + input_amount_with_fee;
+        if denominator.is_zero() {
+            return 0;
+        }
+        Self::saturated_u128(numerator / denominator)
+    }
+
+    /// Rounded up, and `u128::MAX` when the whole output reserve is asked for
+    fn output_price_u128(
+        output_amount: u128,
+        input_reserve: u128,
+        output_reserve: u128,
+        fee: Permill,
+    ) -> u128 {
+        let fee_base = U256::from(Permill::one().deconstruct());
+        let numerator = U256::from(input_reserve) * U256::from(output_amount) * fee_base;
+        let denominator = U256::from(output_reserve.saturating_sub(output_amount))
+            * (fee_base - U256::from(fee.deconstruct()));
+        if denominator.is_zero() {
+            return u128::MAX;
+        }
+        Self::saturated_u128(numerator / denominator).saturating_add(1)
     }
 
-    fn output_price_u128(output_amount: u128, input_reserve: u128, output_reserve: u128) -> u128 {
-        let numerator = input_reserve * output_amount * 1000;
-        let denominator = (output_reserve - output_amount) * 997;
-        numerator / denominator + 1
+    fn saturated_u128(value: U256) -> u128 {
+        if value > U256::from(u128::MAX) {
+            u128::MAX
+        } else {
+            value.low_u128()
+        }
+    }
+
+    /// Adds the protocol fees, zero so far, to the exchanges created before they were collected.
+    fn migrate_v1_to_v2() -> Weight {
+        if StorageVersion::get() != Releases::V1 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut exchanges = 0u64;
+        <Exchanges<T>>::translate::<ExchangeV1<T::AssetId, BalanceOf<T>, CurrencyOf<T>>, _>(|_, old| {
+            exchanges += 1;
+            Some(Exchange {
+                lp_token: old.lp_token,
+                trade_token: old.trade_token,
+                trade_token_amount: old.trade_token_amount,
+                native_token_amount: old.native_token_amount,
+                trade_protocol_fees: Zero::zero(),
+                native_protocol_fees: Zero::zero(),
+            })
+        });
+        StorageVersion::put(Releases::V2);
+
+        T::DbWeight::get().reads_writes(1 + exchanges, 1 + exchanges)
     }
 
     fn native_to_trade_input(
+        exchange_id: T::ExchangeId,
         exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
         native_in: CurrencyOf<T>,
         min_trade_tokens: BalanceOf<T>,
@@ -446,7 +787,7 @@ impl<T: Config> Module<T> {
         recipient: &T::AccountId,
     ) -> DispatchResult {
         ensure!(
-            deadline >= <frame_system::Module<T>>::block_number(),
+            deadline > <frame_system::Module<T>>::block_number(),
             Error::<T>::TooLate
         );
         ensure!(
@@ -459,11 +800,13 @@ impl<T: Config> Module<T> {
             native_in.unique_saturated_into(),
             native_token_reserve.unique_saturated_into(),
             trade_token_reserve.unique_saturated_into(),
+            Self::fee_rates(&exchange_id).swap_fee,
         );
         let trade_token_bought = <BalanceOf<T>>::saturated_from(trade_token_bought);
 
         let trade_token_id = exchange.trade_token;
         Self::native_to_trade_swap(
+            exchange_id,
             exchange,
             native_in,
             &trade_token_id,
@@ -474,6 +817,7 @@ impl<T: Config> Module<T> {
     }
 
     fn trade_to_native_input(
+        exchange_id: T::ExchangeId,
         exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
         trade_in: BalanceOf<T>,
         min_native_tokens: CurrencyOf<T>,
@@ -482,7 +826,7 @@ impl<T: Config> Module<T> {
         recipient: &T::AccountId,
     ) -> DispatchResult {
         ensure!(
-            deadline >= <frame_system::Module<T>>::block_number(),
+            deadline > <frame_system::Module<T>>::block_number(),
             Error::<T>::TooLate
         );
         ensure!(
@@ -496,10 +840,12 @@ impl<T: Config> Module<T> {
             trade_in.unique_saturated_into(),
             trade_reserve.unique_saturated_into(),
             native_reserve.unique_saturated_into(),
+            Self::fee_rates(&exchange_id).swap_fee,
         );
         let native_token_bought = <CurrencyOf<T>>::saturated_from(native_token_bought);
         let trade_token_id = exchange.trade_token;
         Self::trade_to_native_swap(
+            exchange_id,
             exchange,
             trade_in,
             &trade_token_id,
@@ -511,6 +857,7 @@ impl<T: Config> Module<T> {
 
     /// evoked by dispatchble functions
     fn native_to_trade_output(
+        exchange_id: T::ExchangeId,
         exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
         trade_token_bought: BalanceOf<T>,
         deadline: T::BlockNumber,
@@ -518,7 +865,7 @@ impl<T: Config> Module<T> {
         recipient: &T::AccountId,
     ) -> DispatchResult {
         ensure!(
-            deadline >= <frame_system::Module<T>>::block_number(),
+            deadline > <frame_system::Module<T>>::block_number(),
             Error::<T>::TooLate
         );
         ensure!(trade_token_bought > Zero::zero(), Error::<T>::TooLow);
@@ -530,11 +877,13 @@ impl<T: Config> Module<T> {
             trade_token_bought.unique_saturated_into(),
             native_reserve.unique_saturated_into(),
             trade_token_reserve.unique_saturated_into(),
+            Self::fee_rates(&exchange_id).swap_fee,
         );
 
         let native_sold = <CurrencyOf<T>>::saturated_from(native_sold);
 
         Self::native_to_trade_swap(
+            exchange_id,
             exchange,
             native_sold,
             &trade_token_id,
@@ -546,6 +895,7 @@ impl<T: Config> Module<T> {
 
     /// evoked by dispatchable functions
     fn trade_to_native_output(
+        exchange_id: T::ExchangeId,
         exchange: &mut Exchange<T::AssetId, BalanceOf<T>, CurrencyOf<T>>,
         native_token_bought: CurrencyOf<T>,
         deadline: T::BlockNumber,
@@ -553,7 +903,7 @@ impl<T: Config> Module<T> {
         recipient: &T::AccountId,
     ) -> DispatchResult {
         ensure!(
-            deadline >= <frame_system::Module<T>>::block_number(),
+            deadline > <frame_system::Module<T>>::block_number(),
             Error::<T>::TooLate
         );
         ensure!(native_token_bought > Zero::zero(), Error::<T>::TooLow);
@@ -566,11 +916,13 @@ impl<T: Config> Module<T> {
             native_token_bought.unique_saturated_into(),
             trade_token_reserve.unique_saturated_into(),
             native_reserve.unique_saturated_into(),
+            Self::fee_rates(&exchange_id).swap_fee,
         );
 
         let trade_tokens_sold = <BalanceOf<T>>::saturated_from(trade_tokens_sold);
 
         Self::trade_to_native_swap(
+            exchange_id,
             exchange,
             trade_tokens_sold,
             &trade_token_id,
@@ -5,7 +5,7 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
 };
 use frame_system as system;
-use sp_runtime::ModuleId;
+use sp_runtime::{ModuleId, Permill};
 use sp_runtime::traits::Convert;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -75,6 +75,7 @@ parameter_types! {
 		pub const StringLimit: u32 = 50;
 		pub const MetadataDepositBase: u64 = 1;
 		pub const MetadataDepositPerByte: u64 = 1;
+		pub const ApprovalDeposit: u64 = 1;
 	}
 
 impl pallet_assets::Config for Test {
@@ -88,11 +89,16 @@ impl pallet_assets::Config for Test {
 	type StringLimit = StringLimit;
 	type MetadataDepositBase = MetadataDepositBase;
 	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
 	type WeightInfo = ();
 }
 
 parameter_types! {
 	pub const ExchangeModuleId: ModuleId = ModuleId(*b"exchange");
+	pub const SwapFee: Permill = Permill::from_parts(3_000);
+	pub const ProtocolFeeShare: Permill = Permill::zero();
+	pub const FeeTreasury: u64 = TREASURY;
+	pub const MaxPathLength: u32 = 3;
 }
 
 pub struct BalanceHandler;
@@ -109,10 +115,17 @@ impl pallet_social_swap::Config for Test {
 	type FungibleToken = Assets;
 	type Handler = BalanceHandler;
 	type ExchangeId = u64;
+	type SwapFee = SwapFee;
+	type ProtocolFeeShare = ProtocolFeeShare;
+	type FeeOrigin = frame_system::EnsureRoot<u64>;
+	type FeeTreasury = FeeTreasury;
+	type MaxPathLength = MaxPathLength;
 }
 
 pub const ASSET_ID:u32 = 2;
+pub const ASSET_ID_B:u32 = 3;
 pub const OWNER:u64 = 1;
+pub const TREASURY:u64 = 9;
 pub const MAX_ZOMBIES:u32 = 3;
 pub const MIN_BALANCE:u64 = 1;
 pub const INITIAL_BALANCE:u64 = 100_000_0;
@@ -124,7 +137,12 @@ pub const ETH_ADDED:u64 = 25*10^17;
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
-		balances: vec![(1, INITIAL_BALANCE), (2, INITIAL_BALANCE), (SocialSwap::account_id(), INITIAL_BALANCE)],
+		balances: vec![
+			(1, INITIAL_BALANCE),
+			(2, INITIAL_BALANCE),
+			(TREASURY, INITIAL_BALANCE),
+			(SocialSwap::account_id(), INITIAL_BALANCE),
+		],
 	}.assimilate_storage(&mut t).unwrap();
 
 	pallet_assets::GenesisConfig::<Test> {
@@ -0,0 +1,262 @@
+//! Routing of token to token swaps across the exchanges of this module.
+//!
+//! A route is a path of trade tokens. Every hop `path[i] -> path[i + 1]` sells
+//! `path[i]` for native currency on its exchange and buys `path[i + 1]` with that
+//! currency on the exchange of the next token. All exchanges share the same pot,
+//! so only the first input and the last output are actually transferred, the
+//! intermediate amounts only move between exchange reserves.
+
+use super::*;
+
+type ExchangeOf<T> = Exchange<
+    <T as pallet_assets::Config>::AssetId,
+    BalanceOf<T>,
+    CurrencyOf<T>,
+>;
+
+/// (amount of every token, native currency bought by every hop, exchanges after the swap)
+type RouteOf<T> = (
+    Vec<BalanceOf<T>>,
+    Vec<CurrencyOf<T>>,
+    Vec<(<T as Config>::ExchangeId, ExchangeOf<T>)>,
+);
+
+impl<T: Config> Module<T> {
+    /// Weight of a route through `path_len` tokens, capped at `MaxPathLength` tokens since
+    /// longer paths are rejected before any hop is priced.
+    pub(crate) fn route_weight(path_len: usize) -> Weight {
+        let tokens = (path_len as Weight).min(T::MaxPathLength::get() as Weight);
+        // every token reads its exchange id, exchange and fees and writes its exchange,
+        // the first and last token are transferred
+        (20_000_000 as Weight)
+            .saturating_add((15_000_000 as Weight).saturating_mul(tokens))
+            .saturating_add(T::DbWeight::get().reads_writes(4 + 3 * tokens, 4 + tokens))
+    }
+
+    /// Amounts of every token of `path` when selling exactly `amount_in` of the first one.
+    pub fn get_amounts_out(
+        amount_in: BalanceOf<T>,
+        path: &[T::AssetId],
+    ) -> Result<Vec<BalanceOf<T>>, DispatchError> {
+        Self::route_amounts_out(amount_in, path).map(|(amounts, _, _)| amounts)
+    }
+
+    /// Amounts of every token of `path` needed to buy exactly `amount_out` of the last one.
+    pub fn get_amounts_in(
+        amount_out: BalanceOf<T>,
+        path: &[T::AssetId],
+    ) -> Result<Vec<BalanceOf<T>>, DispatchError> {
+        Self::route_amounts_in(amount_out, path).map(|(amounts, _, _)| amounts)
+    }
+
+    /// evoked by `swap_exact_tokens_for_tokens`
+    pub(crate) fn swap_exact_in_along_path(
+        path: Vec<T::AssetId>,
+        amount_in: BalanceOf<T>,
+        min_amount_out: BalanceOf<T>,
+        deadline: T::BlockNumber,
+        buyer: &T::AccountId,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            deadline > <frame_system::Module<T>>::block_number(),
+            Error::<T>::TooLate
+        );
+        ensure!(amount_in > Zero::zero(), Error::<T>::TooLow);
+
+        let route = Self::route_amounts_out(amount_in, &path)?;
+        let amount_out = *route.0.last().ok_or(Error::<T>::InvalidPath)?;
+        ensure!(amount_out >= min_amount_out, Error::<T>::InsufficientOutputAmount);
+
+        Self::swap_along_path(path, route, buyer, recipient)
+    }
+
+    /// evoked by `swap_tokens_for_exact_tokens`
+    pub(crate) fn swap_exact_out_along_path(
+        path: Vec<T::AssetId>,
+        amount_out: BalanceOf<T>,
+        max_amount_in: BalanceOf<T>,
+        deadline: T::BlockNumber,
+        buyer: &T::AccountId,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            deadline > <frame_system::Module<T>>::block_number(),
+            Error::<T>::TooLate
+        );
+        ensure!(amount_out > Zero::zero(), Error::<T>::TooLow);
+
+        let route = Self::route_amounts_in(amount_out, &path)?;
+        let amount_in = *route.0.first().ok_or(Error::<T>::InvalidPath)?;
+        ensure!(amount_in <= max_amount_in, Error::<T>::ExcessiveInputAmount);
+
+        Self::swap_along_path(path, route, buyer, recipient)
+    }
+
+    /// change the storage
+    /// the exchanges of `route` already have their reserves updated
+    fn swap_along_path(
+        path: Vec<T::AssetId>,
+        route: RouteOf<T>,
+        buyer: &T::AccountId,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (amounts, natives, exchanges) = route;
+        let this = Self::account_id();
+        let token_in = path.first().ok_or(Error::<T>::InvalidPath)?;
+        let token_out = path.last().ok_or(Error::<T>::InvalidPath)?;
+        let amount_in = *amounts.first().ok_or(Error::<T>::InvalidPath)?;
+        let amount_out = *amounts.last().ok_or(Error::<T>::InvalidPath)?;
+
+        T::FungibleToken::transfer(token_in, buyer, &this, amount_in)?;
+        T::FungibleToken::transfer(token_out, &this, recipient, amount_out)?;
+        for (exchange_id, exchange) in exchanges.iter() {
+            <Exchanges<T>>::insert(exchange_id, exchange);
+        }
+
+        // every hop is reported as a swap on each of its two exchanges
+        for (i, native) in natives.into_iter().enumerate() {
+            let (from_id, to_id) = (exchanges[i].0, exchanges[i + 1].0);
+            let (trade_fee, _) = Self::split_fee(&Self::fee_rates(&from_id), amounts[i]);
+            let (native_fee, _) = Self::split_fee(&Self::fee_rates(&to_id), native);
+            Self::deposit_event(RawEvent::TradeToNativeSwapped(
+                from_id, buyer.clone(), amounts[i], native, trade_fee, recipient.clone()));
+            Self::deposit_event(RawEvent::NativeToTradeSwapped(
+                to_id, buyer.clone(), native, amounts[i + 1], native_fee, recipient.clone()));
+        }
+
+        Self::deposit_event(RawEvent::RouteSwapped(
+            buyer.clone(),
+            path,
+            amount_in,
+            amount_out,
+            recipient.clone(),
+        ));
+
+        Ok(())
+    }
+
+    /// Walk `path` forward from `amount_in`, returning the amount of every token and the
+    /// native currency of every hop together with the exchanges of the route as they will
+    /// be after the swap.
+    fn route_amounts_out(
+        amount_in: BalanceOf<T>,
+        path: &[T::AssetId],
+    ) -> Result<RouteOf<T>, DispatchError> {
+        let mut exchanges = Self::route_exchanges(path)?;
+        let mut amounts = Vec::with_capacity(path.len());
+        let mut natives = Vec::with_capacity(path.len() - 1);
+        amounts.push(amount_in);
+
+        for i in 0..exchanges.len() - 1 {
+            let trade_in = amounts[i];
+            let native = {
+                let rates = Self::fee_rates(&exchanges[i].0);
+                let from = &mut exchanges[i].1;
+                Self::ensure_liquidity(from)?;
+                let native = <CurrencyOf<T>>::saturated_from(Self::input_price_u128(
+                    trade_in.unique_saturated_into(),
+                    from.trade_token_amount.unique_saturated_into(),
+                    from.native_token_amount.unique_saturated_into(),
+                    rates.swap_fee,
+                ));
+                Self::accrue_trade_in(from, &rates, trade_in);
+                from.native_token_amount = from.native_token_amount.saturating_sub(native);
+                native
+            };
+
+            let rates = Self::fee_rates(&exchanges[i + 1].0);
+            let to = &mut exchanges[i + 1].1;
+            Self::ensure_liquidity(to)?;
+            let trade_out = <BalanceOf<T>>::saturated_from(Self::input_price_u128(
+                native.unique_saturated_into(),
+                to.native_token_amount.unique_saturated_into(),
+                to.trade_token_amount.unique_saturated_into(),
+                rates.swap_fee,
+            ));
+            Self::accrue_native_in(to, &rates, native);
+            to.trade_token_amount = to.trade_token_amount.saturating_sub(trade_out);
+            amounts.push(trade_out);
+            natives.push(native);
+        }
+
+        Ok((amounts, natives, exchanges))
+    }
+
+    /// Walk `path` backward from `amount_out`, returning the amount of every token together
+    /// with the exchanges of the route as they will be after the swap.
+    ///
+    /// The hops are priced last to first, which is as valid an order as any other since
+    /// the exchanges only settle with the shared pot.
+    fn route_amounts_in(
+        amount_out: BalanceOf<T>,
+        path: &[T::AssetId],
+    ) -> Result<RouteOf<T>, DispatchError> {
+        let mut exchanges = Self::route_exchanges(path)?;
+        let mut amounts: Vec<BalanceOf<T>> = vec![Zero::zero(); path.len()];
+        let mut natives: Vec<CurrencyOf<T>> = vec![Zero::zero(); path.len() - 1];
+        amounts[path.len() - 1] = amount_out;
+
+        for i in (1..exchanges.len()).rev() {
+            let trade_out = amounts[i];
+            let native = {
+                let rates = Self::fee_rates(&exchanges[i].0);
+                let to = &mut exchanges[i].1;
+                Self::ensure_liquidity(to)?;
+                ensure!(trade_out < to.trade_token_amount, Error::<T>::NotEnoughLiquidity);
+                let native = <CurrencyOf<T>>::saturated_from(Self::output_price_u128(
+                    trade_out.unique_saturated_into(),
+                    to.native_token_amount.unique_saturated_into(),
+                    to.trade_token_amount.unique_saturated_into(),
+                    rates.swap_fee,
+                ));
+                Self::accrue_native_in(to, &rates, native);
+                to.trade_token_amount = to.trade_token_amount.saturating_sub(trade_out);
+                native
+            };
+
+            let rates = Self::fee_rates(&exchanges[i - 1].0);
+            let from = &mut exchanges[i - 1].1;
+            Self::ensure_liquidity(from)?;
+            ensure!(native < from.native_token_amount, Error::<T>::NotEnoughLiquidity);
+            let trade_in = <BalanceOf<T>>::saturated_from(Self::output_price_u128(
+                native.unique_saturated_into(),
+                from.trade_token_amount.unique_saturated_into(),
+                from.native_token_amount.unique_saturated_into(),
+                rates.swap_fee,
+            ));
+            Self::accrue_trade_in(from, &rates, trade_in);
+            from.native_token_amount = from.native_token_amount.saturating_sub(native);
+            amounts[i - 1] = trade_in;
+            natives[i - 1] = native;
+        }
+
+        Ok((amounts, natives, exchanges))
+    }
+
+    /// The exchanges of every token of a path, which must hold at least two distinct tokens
+    fn route_exchanges(
+        path: &[T::AssetId],
+    ) -> Result<Vec<(T::ExchangeId, ExchangeOf<T>)>, DispatchError> {
+        ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+        ensure!(path.len() <= T::MaxPathLength::get() as usize, Error::<T>::PathTooLong);
+
+        let mut exchanges = Vec::with_capacity(path.len());
+        for (i, token_id) in path.iter().enumerate() {
+            ensure!(!path[..i].contains(token_id), Error::<T>::InvalidPath);
+            let exchange_id = Self::tt_to_exchange(token_id).ok_or(Error::<T>::ExchangeNotExists)?;
+            let exchange = Self::exchanges(&exchange_id).ok_or(Error::<T>::ExchangeNotExists)?;
+            exchanges.push((exchange_id, exchange));
+        }
+
+        Ok(exchanges)
+    }
+
+    fn ensure_liquidity(exchange: &ExchangeOf<T>) -> DispatchResult {
+        ensure!(
+            !exchange.trade_token_amount.is_zero() && !exchange.native_token_amount.is_zero(),
+            Error::<T>::NotEnoughLiquidity
+        );
+        Ok(())
+    }
+}
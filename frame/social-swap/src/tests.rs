@@ -1,5 +1,5 @@
 use crate::{mock::*, Error};
-use frame_support::assert_noop;
+use frame_support::{assert_noop, assert_ok};
 use pallet_assets::*;
 use super::*;
 
@@ -528,3 +528,241 @@ fn test_trade_to_native_token_output_should_work() {
 		);
 	});
 }
+
+fn create_route_test() -> (u64, u64) {
+	assert_ok!(pallet_assets::Module::<Test>::do_create(ASSET_ID_B, OWNER, OWNER, MAX_ZOMBIES, MIN_BALANCE));
+	let (exchange_a, _, _) = create_exchange_test();
+	assert_ok!(SocialSwap::create_exchange(Origin::signed(OWNER), ASSET_ID_B, MAX_ZOMBIES, 10));
+	let exchange_b = TradeTokenToExchange::<Test>::get(ASSET_ID_B).unwrap();
+	assert_ne!(exchange_a, exchange_b);
+
+	pallet_assets::Module::<Test>::issue(&ASSET_ID, &OWNER, 100_000);
+	pallet_assets::Module::<Test>::issue(&ASSET_ID_B, &OWNER, 100_000);
+	assert_ok!(SocialSwap::add_liquidity(Origin::signed(OWNER), exchange_a, 10_000, 0, 10_000, 1));
+	assert_ok!(SocialSwap::add_liquidity(Origin::signed(OWNER), exchange_b, 10_000, 0, 20_000, 1));
+	(exchange_a, exchange_b)
+}
+
+#[test]
+fn test_swap_exact_tokens_for_tokens_should_work() {
+	new_test_ext().execute_with(|| {
+		let (exchange_a, exchange_b) = create_route_test();
+		let recipient: u64 = 2;
+
+		assert_eq!(
+			SocialSwap::get_amounts_out(1000, &[ASSET_ID, ASSET_ID_B]),
+			Ok(vec![1000, 1656])
+		);
+		assert_ok!(SocialSwap::swap_exact_tokens_for_tokens(
+			Origin::signed(OWNER),
+			vec![ASSET_ID, ASSET_ID_B],
+			1000,
+			1656,
+			1,
+			recipient
+		));
+
+		assert_eq!(pallet_assets::Module::<Test>::balance(ASSET_ID_B, recipient), 1656);
+		let exchange = Exchanges::<Test>::get(exchange_a).unwrap();
+		assert_eq!((exchange.native_token_amount, exchange.trade_token_amount), (10_000 - 906, 11_000));
+		let exchange = Exchanges::<Test>::get(exchange_b).unwrap();
+		assert_eq!((exchange.native_token_amount, exchange.trade_token_amount), (10_000 + 906, 20_000 - 1656));
+	});
+}
+
+#[test]
+fn test_swap_exact_tokens_for_tokens_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_route_test();
+
+		assert_noop!(
+			SocialSwap::swap_exact_tokens_for_tokens(Origin::signed(OWNER), vec![ASSET_ID], 1000, 1, 1, 2),
+			Error::<Test>::InvalidPath
+		);
+		assert_noop!(
+			SocialSwap::swap_exact_tokens_for_tokens(Origin::signed(OWNER), vec![ASSET_ID, ASSET_ID], 1000, 1, 1, 2),
+			Error::<Test>::InvalidPath
+		);
+		assert_noop!(
+			SocialSwap::swap_exact_tokens_for_tokens(Origin::signed(OWNER), vec![ASSET_ID, 11], 1000, 1, 1, 2),
+			Error::<Test>::ExchangeNotExists
+		);
+		assert_noop!(
+			SocialSwap::swap_exact_tokens_for_tokens(Origin::signed(OWNER), vec![ASSET_ID, ASSET_ID_B, 11, 12], 1000, 1, 1, 2),
+			Error::<Test>::PathTooLong
+		);
+		assert_noop!(
+			SocialSwap::swap_exact_tokens_for_tokens(Origin::signed(OWNER), vec![ASSET_ID, ASSET_ID_B], 1000, 1657, 1, 2),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		// the deadline block itself is too late, as for liquidity
+		assert_noop!(
+			SocialSwap::swap_exact_tokens_for_tokens(Origin::signed(OWNER), vec![ASSET_ID, ASSET_ID_B], 1000, 1, 0, 2),
+			Error::<Test>::TooLate
+		);
+	});
+}
+
+#[test]
+fn test_swap_tokens_for_exact_tokens_should_work() {
+	new_test_ext().execute_with(|| {
+		let (exchange_a, exchange_b) = create_route_test();
+		let recipient: u64 = 2;
+
+		assert_eq!(
+			SocialSwap::get_amounts_in(1000, &[ASSET_ID, ASSET_ID_B]),
+			Ok(vec![560, 1000])
+		);
+		assert_ok!(SocialSwap::swap_tokens_for_exact_tokens(
+			Origin::signed(OWNER),
+			vec![ASSET_ID, ASSET_ID_B],
+			1000,
+			560,
+			1,
+			recipient
+		));
+
+		assert_eq!(pallet_assets::Module::<Test>::balance(ASSET_ID_B, recipient), 1000);
+		let exchange = Exchanges::<Test>::get(exchange_a).unwrap();
+		assert_eq!((exchange.native_token_amount, exchange.trade_token_amount), (10_000 - 528, 10_560));
+		let exchange = Exchanges::<Test>::get(exchange_b).unwrap();
+		assert_eq!((exchange.native_token_amount, exchange.trade_token_amount), (10_000 + 528, 19_000));
+	});
+}
+
+#[test]
+fn test_swap_tokens_for_exact_tokens_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_route_test();
+
+		assert_noop!(
+			SocialSwap::swap_tokens_for_exact_tokens(Origin::signed(OWNER), vec![ASSET_ID, ASSET_ID_B], 1000, 559, 1, 2),
+			Error::<Test>::ExcessiveInputAmount
+		);
+		assert_noop!(
+			SocialSwap::swap_tokens_for_exact_tokens(Origin::signed(OWNER), vec![ASSET_ID, ASSET_ID_B], 20_000, 100_000, 1, 2),
+			Error::<Test>::NotEnoughLiquidity
+		);
+	});
+}
+
+#[test]
+fn test_prices_should_not_overflow() {
+	let max = u64::MAX as u128;
+	assert_eq!(SocialSwap::input_price_u128(max, max, max, Permill::zero()), max / 2);
+	assert_eq!(SocialSwap::output_price_u128(max / 2, max, max, Permill::zero()), max - 1);
+	assert_eq!(SocialSwap::output_price_u128(max, max, max, Permill::zero()), u128::MAX);
+}
+
+#[test]
+fn test_runtime_upgrade_should_add_protocol_fees() {
+	new_test_ext().execute_with(|| {
+		let exchange_id: u64 = 7;
+		let old = ExchangeV1 {
+			lp_token: 10u32,
+			trade_token: ASSET_ID,
+			trade_token_amount: 300u64,
+			native_token_amount: 200u64,
+		};
+		frame_support::storage::migration::put_storage_value(
+			b"UniswapExchanges",
+			b"Exchanges",
+			&frame_support::Twox64Concat::hash(&exchange_id.encode()),
+			old,
+		);
+		StorageVersion::put(Releases::V1);
+
+		SocialSwap::migrate_v1_to_v2();
+
+		let exchange = SocialSwap::exchanges(exchange_id).unwrap();
+		assert_eq!((exchange.trade_token, exchange.trade_token_amount, exchange.native_token_amount), (ASSET_ID, 300, 200));
+		assert_eq!((exchange.trade_protocol_fees, exchange.native_protocol_fees), (0, 0));
+		assert_eq!(StorageVersion::get(), Releases::V2);
+	});
+}
+
+#[test]
+fn test_quotes_should_work() {
+	new_test_ext().execute_with(|| {
+		let (_, exchange_b) = create_route_test();
+
+		assert_eq!(SocialSwap::get_input_price(exchange_b, 1000, SwapDirection::NativeToTrade), Some(1813));
+		assert_eq!(SocialSwap::get_output_price(exchange_b, 1000, SwapDirection::TradeToNative), Some(2229));
+		assert_eq!(SocialSwap::get_output_price(exchange_b, 10_000, SwapDirection::TradeToNative), None);
+		assert_eq!(SocialSwap::get_input_price(42, 1000, SwapDirection::NativeToTrade), None);
+
+		let (exchange_id, exchange) = SocialSwap::get_exchange_by_token(ASSET_ID_B).unwrap();
+		assert_eq!(exchange_id, exchange_b);
+		assert_eq!((exchange.native_token_amount, exchange.trade_token_amount), (10_000, 20_000));
+
+		assert_eq!(SocialSwap::get_liquidity_value(exchange_b, 5000), Some((5000, 10_000)));
+		assert_eq!(SocialSwap::get_liquidity_value(exchange_b, 10_001), None);
+	});
+}
+
+#[test]
+fn test_set_exchange_fees_should_work() {
+	new_test_ext().execute_with(|| {
+		let (exchange_a, _) = create_route_test();
+		let rates = FeeRates { swap_fee: Permill::from_percent(1), protocol_share: Permill::from_percent(50) };
+
+		assert_eq!(SocialSwap::fee_rates(&exchange_a).swap_fee, Permill::from_parts(3_000));
+		assert_ok!(SocialSwap::set_exchange_fees(Origin::root(), exchange_a, Some(rates)));
+		assert_eq!(SocialSwap::fee_rates(&exchange_a), rates);
+		assert_ok!(SocialSwap::set_exchange_fees(Origin::root(), exchange_a, None));
+		assert_eq!(SocialSwap::exchange_fees(exchange_a), None);
+	});
+}
+
+#[test]
+fn test_set_exchange_fees_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let (exchange_a, _) = create_route_test();
+		let rates = FeeRates { swap_fee: Permill::one(), protocol_share: Permill::zero() };
+
+		assert_noop!(
+			SocialSwap::set_exchange_fees(Origin::signed(OWNER), exchange_a, None),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			SocialSwap::set_exchange_fees(Origin::root(), 42, None),
+			Error::<Test>::ExchangeNotExists
+		);
+		assert_noop!(
+			SocialSwap::set_exchange_fees(Origin::root(), exchange_a, Some(rates)),
+			Error::<Test>::InvalidFee
+		);
+	});
+}
+
+#[test]
+fn test_protocol_fees_should_be_swept() {
+	new_test_ext().execute_with(|| {
+		let (exchange_a, exchange_b) = create_route_test();
+		let rates = FeeRates { swap_fee: Permill::from_percent(1), protocol_share: Permill::from_percent(50) };
+		assert_ok!(SocialSwap::set_exchange_fees(Origin::root(), exchange_a, Some(rates)));
+		System::set_block_number(1);
+
+		assert_ok!(SocialSwap::swap_exact_tokens_for_tokens(
+			Origin::signed(OWNER),
+			vec![ASSET_ID, ASSET_ID_B],
+			1000,
+			1,
+			2,
+			2
+		));
+		assert_eq!(pallet_assets::Module::<Test>::balance(ASSET_ID_B, 2), 1646);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_swap(RawEvent::TradeToNativeSwapped(exchange_a, OWNER, 1000, 900, 10, 2))));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::pallet_social_swap(RawEvent::NativeToTradeSwapped(exchange_b, OWNER, 900, 1646, 2, 2))));
+
+		let exchange = Exchanges::<Test>::get(exchange_a).unwrap();
+		assert_eq!((exchange.trade_token_amount, exchange.trade_protocol_fees), (10_995, 5));
+
+		assert_ok!(SocialSwap::sweep_protocol_fees(Origin::signed(2), exchange_a));
+		assert_eq!(pallet_assets::Module::<Test>::balance(ASSET_ID, TREASURY), 5);
+		let exchange = Exchanges::<Test>::get(exchange_a).unwrap();
+		assert_eq!((exchange.trade_token_amount, exchange.trade_protocol_fees), (10_995, 0));
+	});
+}
@@ -0,0 +1,23 @@
+//! Runtime API definition of the Social Swap2 price oracle.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to expose the time weighted average prices
+//! of the Social Swap2 pairs.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the price oracle of the social swap2 module.
+	pub trait SocialSwap2OracleApi<AssetId> where
+		AssetId: Codec,
+	{
+		/// Average prices of the pair of `asset_a` and `asset_b` over at least the last
+		/// `window` seconds, as UQ64.64 fixed point numbers.
+		///
+		/// The first price is the one of the smaller asset in the bigger one, the second
+		/// its inverse. Returns `None` if the pair has no observation old enough.
+		fn consult(asset_a: AssetId, asset_b: AssetId, window: u32) -> Option<(u128, u128)>;
+	}
+}
@@ -1,14 +1,30 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+//! A Uniswap-V2-style factory of constant-product pairs between any two
+//! `pallet_assets` tokens.
+//!
+//! Every pair is identified by its sorted `(asset0, asset1)` tuple, holds its
+//! reserves in a deterministic pair account and mints its own LP asset.
+//!
+//! The single pool of the previous version is not carried over. It paired one social token
+//! with itself between two accounts set by root, which do keep their balances. The runtime
+//! upgrade only removes its bookkeeping, pairs have to be created anew with `create_pair`.
+
+use codec::{Decode, Encode};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, ensure,
-    traits::{Currency, ExistenceRequirement, Get},
+    storage::migration,
+    traits::{Currency, EnsureOrigin, Get},
+    transactional,
+    weights::Weight,
 };
 use frame_system::{ensure_root, ensure_signed};
-use pallet_assets::{Fungible, IssueAndBurn};
+use pallet_assets::{Fungible, IssueAndBurn, TokenDossier};
+use sp_core::U256;
+use sp_io::hashing::blake2_256;
 use sp_runtime::{
-    traits::{CheckedAdd, CheckedSub, IntegerSquareRoot, SaturatedConversion, Saturating, Scale},
-    DispatchError, DispatchResult,
+    traits::{AccountIdConversion, CheckedAdd, CheckedSub, IntegerSquareRoot, SaturatedConversion, Saturating, Scale},
+    DispatchError, DispatchResult, ModuleId, Permill, RuntimeDebug,
 };
 use sp_std::{convert::TryInto, prelude::*};
 
@@ -18,31 +34,122 @@ pub type BalanceOf<T> = <<T as Config>::FungibleToken as Fungible<
     <T as pallet_assets::Config>::AssetId,
     <T as frame_system::Config>::AccountId,
 >>::Balance;
+/// A pair is identified by its two assets, sorted in ascending order.
+pub type PairIdOf<T> = (
+    <T as pallet_assets::Config>::AssetId,
+    <T as pallet_assets::Config>::AssetId,
+);
+
+mod oracle;
 
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PairInfo<AccountId, AssetId> {
+    /// account holding the reserves of both assets
+    pub account: AccountId,
+    /// liquidity token minted to the providers of this pair
+    pub lp_token: AssetId,
+}
+
+/// Receiver of the outputs of a flash swap.
+///
+/// Implemented by other pallets, or by an adapter calling into EVM or ink! contracts.
+pub trait FlashSwapCallee<AccountId, AssetId, Balance> {
+    /// Called by `swap` with a non-empty `data`, once `amount0_out` and `amount1_out`
+    /// of the pair assets were sent to `to`. The pair must be paid back, fee included,
+    /// before returning or the whole swap is reverted.
+    fn on_flash_swap(
+        sender: &AccountId,
+        to: &AccountId,
+        pair_id: &(AssetId, AssetId),
+        amount0_out: Balance,
+        amount1_out: Balance,
+        data: &[u8],
+    ) -> DispatchResult;
+}
+
+impl<AccountId, AssetId, Balance> FlashSwapCallee<AccountId, AssetId, Balance> for () {
+    fn on_flash_swap(
+        _sender: &AccountId,
+        _to: &AccountId,
+        _pair_id: &(AssetId, AssetId),
+        _amount0_out: Balance,
+        _amount1_out: Balance,
+        _data: &[u8],
+    ) -> DispatchResult {
+        Err(DispatchError::Other("flash swaps are not supported"))
+    }
+}
+
+/// Cumulative prices of a pair at some point in time
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Observation {
+    /// seconds, truncated to 32 bits
+    pub timestamp: u32,
+    pub price0_cumulative: u128,
+    pub price1_cumulative: u128,
+}
+
+/// Fees charged on the input of every swap of a pair
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct FeeRates {
+    /// total fee, the part not taken by the protocol stays with the liquidity providers
+    pub swap_fee: Permill,
+    /// share of the fee growth minted as LP tokens to the protocol
+    pub protocol_share: Permill,
+}
+
 pub trait Config:
     frame_system::Config + pallet_assets::Config + pallet_timestamp::Config
 {
     type Currency: Currency<Self::AccountId>;
+    type ModuleId: Get<ModuleId>;
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
     type FungibleToken: IssueAndBurn<Self::AssetId, Self::AccountId>;
 
     type MinimumLiquidity: Get<Self::Balance>;
+    /// `max_zombies` of the LP asset created for every new pair
+    type PairMaxZombies: Get<u32>;
+    /// `min_balance` of the LP asset created for every new pair
+    type PairMinBalance: Get<u32>;
+    /// default fee charged on the input of a swap
+    type SwapFee: Get<Permill>;
+    /// default share of the swap fees accruing to the protocol
+    type ProtocolFeeShare: Get<Permill>;
+    /// origin allowed to override the fees of a pair
+    type FeeOrigin: EnsureOrigin<Self::Origin>;
+    /// account the protocol fees are swept to
+    type FeeTreasury: Get<Self::AccountId>;
+    /// number of price observations kept for every pair
+    type ObservationCapacity: Get<u32>;
+    /// minimum time between two price observations of a pair, in seconds
+    type ObservationPeriod: Get<u32>;
+    /// receiver of the flash swaps
+    type FlashSwapCallee: FlashSwapCallee<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
 }
 
 decl_event! {
     pub enum Event<T> where
         <T as frame_system::Config>::AccountId,
+        <T as pallet_assets::Config>::AssetId,
         Balance = BalanceOf<T>,
+        PairId = PairIdOf<T>,
     {
-        Mint(AccountId, Balance, Balance),
-        Burn(AccountId, Balance, Balance, AccountId),
-        SyncDone(Balance, Balance),
-        Swap(AccountId, Balance, Balance, Balance, Balance, AccountId),
+        /// A new pair created. [pair_id, pair_account, lp_token_id]
+        PairCreated(PairId, AccountId, AssetId),
+        Mint(PairId, AccountId, Balance, Balance),
+        Burn(PairId, AccountId, Balance, Balance, AccountId),
+        SyncDone(PairId, Balance, Balance),
+        /// Tokens swapped. [pair_id, sender, amount0_in, amount1_in, amount0_out, amount1_out, fee0, fee1, to]
+        Swap(PairId, AccountId, Balance, Balance, Balance, Balance, Balance, Balance, AccountId),
+        /// Fees of a pair changed. [pair_id, swap_fee, protocol_share]
+        FeesUpdated(PairId, Permill, Permill),
+        /// Protocol fees sent to the treasury. [pair_id, treasury, lp_token_amount]
+        ProtocolFeesSwept(PairId, AccountId, Balance),
     }
 }
 
@@ -60,29 +167,49 @@ decl_error! {
         InsufficientLiquidity,
         InvalidTo,
         InvalidK,
+        /// both assets of a pair are the same
+        IdenticalAssets,
+        /// one of the assets of a pair does not exist
+        AssetNotExists,
+        /// pair already exists
+        PairExists,
+        /// pair does not exist
+        PairNotExists,
+        /// swap fee must be below 100%
+        InvalidFee,
+        /// pair is in the middle of a flash swap
+        Locked,
     }
 }
 
 decl_storage! {
     trait Store for Module<T: Config> as UniswapExchanges {
 
-        pub AssetId get(fn social_token_id): T::AssetId = 1u32.into();
-
-        pub FeeTo get(fn fee_to): T::AccountId;
         pub Address0 get(fn address0): T::AccountId;
-        pub Treasury get(fn treasury): T::AccountId;
 
-        pub Token0 get(fn token0): T::AccountId;
-        pub Token1 get(fn token1): T::AccountId;
+        pub Pairs get(fn pairs): map hasher(blake2_128_concat) PairIdOf<T> => Option<PairInfo<T::AccountId, T::AssetId>>;
 
-        pub Reserve0 get(fn reserve0): BalanceOf<T>;
-        pub Reserve1 get(fn reserve1): BalanceOf<T>;
+        pub LPTokenToPair get(fn lp_to_pair): map hasher(twox_64_concat) T::AssetId => Option<PairIdOf<T>>;
 
-        pub Price0CumulativeLast get(fn price0_cumulative_last): u128;
-        pub Price1CumulativeLast get(fn price1_cumulative_last): u128;
+        pub Reserve0 get(fn reserve0): map hasher(blake2_128_concat) PairIdOf<T> => BalanceOf<T>;
+        pub Reserve1 get(fn reserve1): map hasher(blake2_128_concat) PairIdOf<T> => BalanceOf<T>;
 
-        pub KLast get(fn k_last): BalanceOf<T>;
-        pub BlockTimestampLast get(fn block_timestamp_last): u32;
+        pub Price0CumulativeLast get(fn price0_cumulative_last): map hasher(blake2_128_concat) PairIdOf<T> => u128;
+        pub Price1CumulativeLast get(fn price1_cumulative_last): map hasher(blake2_128_concat) PairIdOf<T> => u128;
+
+        pub KLast get(fn k_last): map hasher(blake2_128_concat) PairIdOf<T> => BalanceOf<T>;
+        pub BlockTimestampLast get(fn block_timestamp_last): map hasher(blake2_128_concat) PairIdOf<T> => u32;
+
+        /// Ring buffer of the price observations of every pair
+        pub Observations get(fn observations): map hasher(blake2_128_concat) PairIdOf<T> => Vec<Observation>;
+        /// Position of the most recent observation in `Observations`
+        pub ObservationIndex get(fn observation_index): map hasher(blake2_128_concat) PairIdOf<T> => u32;
+
+        /// Fees of the pairs which do not use the default ones
+        pub PairFees get(fn pair_fees): map hasher(blake2_128_concat) PairIdOf<T> => Option<FeeRates>;
+
+        /// Pairs in the middle of a flash swap, which cannot be minted, burnt or swapped
+        pub Locked get(fn is_locked): map hasher(blake2_128_concat) PairIdOf<T> => bool;
     }
 }
 
@@ -91,30 +218,100 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() -> Weight {
+            Self::remove_single_pool()
+        }
+
         #[weight = 10_000 + T::DbWeight::get().writes(1)]
-        fn initialize(origin, fee_to: T::AccountId, address0: T::AccountId, treasury: T::AccountId, token0: T::AccountId, token1: T::AccountId) {
+        fn initialize(origin, address0: T::AccountId) {
             ensure_root(origin)?;
-            <FeeTo<T>>::put(fee_to);
             <Address0<T>>::put(address0);
-            <Treasury<T>>::put(treasury);
-            <Token0<T>>::put(token0);
-            <Token1<T>>::put(token1);
         }
 
+        /// Override the fees of a pair, `None` restores the default ones.
         #[weight = 10_000 + T::DbWeight::get().writes(1)]
-        fn mint(origin, to: T::AccountId) -> Result<(), DispatchError> {
+        fn set_pair_fees(origin, pair_id: PairIdOf<T>, fees: Option<FeeRates>) -> DispatchResult {
+            T::FeeOrigin::ensure_origin(origin)?;
+            let pair = Self::pairs(&pair_id).ok_or(Error::<T>::PairNotExists)?;
+            if let Some(rates) = fees {
+                ensure!(rates.swap_fee < Permill::one(), Error::<T>::InvalidFee);
+            }
+
+            // settle the protocol fees accrued under the previous rates
+            let reserve0 = Self::reserve0(&pair_id);
+            let reserve1 = Self::reserve1(&pair_id);
+            Self::mint_fee(&pair_id, &pair, reserve0, reserve1)?;
+            <PairFees<T>>::mutate_exists(&pair_id, |f| *f = fees);
+            let rates = Self::fee_rates(&pair_id);
+            let k_last = if rates.protocol_share.is_zero() {
+                0u32.into()
+            } else {
+                reserve0.saturating_mul(reserve1)
+            };
+            <KLast<T>>::insert(&pair_id, k_last);
+
+            Self::deposit_event(RawEvent::FeesUpdated(pair_id, rates.swap_fee, rates.protocol_share));
+
+            Ok(())
+        }
+
+        /// Send the LP tokens minted as protocol fees of a pair to the treasury.
+        #[weight = 10_000 + T::DbWeight::get().writes(2)]
+        fn sweep_protocol_fees(origin, pair_id: PairIdOf<T>) -> DispatchResult {
+            let _ = ensure_signed(origin)?;
+            let pair = Self::pairs(&pair_id).ok_or(Error::<T>::PairNotExists)?;
+            let treasury = T::FeeTreasury::get();
+            let amount = T::FungibleToken::balances(&pair.lp_token, &Self::account_id());
+            if amount > 0u32.into() {
+                T::FungibleToken::transfer(&pair.lp_token, &Self::account_id(), &treasury, amount)?;
+            }
+
+            Self::deposit_event(RawEvent::ProtocolFeesSwept(pair_id, treasury, amount));
+
+            Ok(())
+        }
+
+        /// Create the pair of `asset_a` and `asset_b` together with its LP token.
+        /// The order of the assets does not matter, a pair is always stored sorted.
+        #[weight = 10_000 + T::DbWeight::get().writes(3)]
+        fn create_pair(origin, asset_a: T::AssetId, asset_b: T::AssetId) -> DispatchResult {
+            let _ = ensure_signed(origin)?;
+            ensure!(asset_a != asset_b, Error::<T>::IdenticalAssets);
+            ensure!(T::FungibleToken::exists(&asset_a) && T::FungibleToken::exists(&asset_b), Error::<T>::AssetNotExists);
+
+            let pair_id = Self::sort_assets(asset_a, asset_b);
+            ensure!(!<Pairs<T>>::contains_key(&pair_id), Error::<T>::PairExists);
+
+            let account = Self::pair_account_id(&pair_id);
+            let lp_token = T::FungibleToken::create_new_asset(
+                &Self::account_id(),
+                TokenDossier::new_lp_token(),
+                T::PairMaxZombies::get(),
+                T::PairMinBalance::get())?;
+
+            <Pairs<T>>::insert(&pair_id, PairInfo { account: account.clone(), lp_token });
+            <LPTokenToPair<T>>::insert(&lp_token, pair_id);
+
+            Self::deposit_event(RawEvent::PairCreated(pair_id, account, lp_token));
+
+            Ok(())
+        }
+
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        fn mint(origin, pair_id: PairIdOf<T>, to: T::AccountId) -> Result<(), DispatchError> {
             let sender = ensure_signed(origin)?;
-            let social_token_id = Self::social_token_id();
-            let reserve0 = Self::reserve0();
-            let reserve1 = Self::reserve1();
-            let balance0 = T::FungibleToken::balances(&social_token_id, &Self::token0());
-            let balance1 = T::FungibleToken::balances(&social_token_id, &Self::token1());
+            let pair = Self::pairs(&pair_id).ok_or(Error::<T>::PairNotExists)?;
+            ensure!(!Self::is_locked(&pair_id), Error::<T>::Locked);
+            let reserve0 = Self::reserve0(&pair_id);
+            let reserve1 = Self::reserve1(&pair_id);
+            let balance0 = T::FungibleToken::balances(&pair_id.0, &pair.account);
+            let balance1 = T::FungibleToken::balances(&pair_id.1, &pair.account);
 
             let amount0 = balance0.checked_sub(&reserve0).ok_or(Error::<T>::NotEnoughLiquidity)?;
             let amount1 = balance1.checked_sub(&reserve1).ok_or(Error::<T>::NotEnoughLiquidity)?;
 
-            let fee_on = Self::mint_fee(reserve0, reserve1)?;
-            let total_supply = T::FungibleToken::total_supply(&social_token_id);
+            let fee_on = Self::mint_fee(&pair_id, &pair, reserve0, reserve1)?;
+            let total_supply = T::FungibleToken::total_supply(&pair.lp_token);
             let liquidity = if total_supply == 0u32.into() {
                 let min_liquidity = T::MinimumLiquidity::get().saturated_into::<u128>().saturated_into();
                 amount0
@@ -128,79 +325,88 @@ decl_module! {
 
             if total_supply == 0u32.into() {
                 let min_liquidity = T::MinimumLiquidity::get().saturated_into::<u128>().saturated_into();
-                T::FungibleToken::issue(&social_token_id, &Self::address0(), min_liquidity)?;
+                T::FungibleToken::issue(&pair.lp_token, &Self::address0(), min_liquidity)?;
             }
-            T::FungibleToken::issue(&social_token_id, &to, liquidity)?;
+            T::FungibleToken::issue(&pair.lp_token, &to, liquidity)?;
 
-            let _ = Self::update(balance0, balance1, reserve0, reserve1);
+            let _ = Self::update(&pair_id, balance0, balance1, reserve0, reserve1);
             if fee_on {
-                <KLast<T>>::put(reserve0.saturating_mul(reserve1));
+                <KLast<T>>::insert(&pair_id, balance0.saturating_mul(balance1));
             }
 
-            Self::deposit_event(RawEvent::Mint(sender, amount0, amount1));
+            Self::deposit_event(RawEvent::Mint(pair_id, sender, amount0, amount1));
 
             Ok(())
         }
 
+        /// Burn the LP tokens previously transferred to the pair account and
+        /// send the underlying assets to `to`.
         #[weight = 10_000 + T::DbWeight::get().writes(1)]
-        fn burn(origin, to: T::AccountId) -> Result<(), DispatchError> {
+        fn burn(origin, pair_id: PairIdOf<T>, to: T::AccountId) -> Result<(), DispatchError> {
             let sender = ensure_signed(origin)?;
-            let social_token_id = Self::social_token_id();
-            let reserve0 = Self::reserve0();
-            let reserve1 = Self::reserve1();
-            let balance0 = T::FungibleToken::balances(&social_token_id, &Self::token0());
-            let balance1 = T::FungibleToken::balances(&social_token_id, &Self::token1());
-            let liquidity = <T as Config>::Currency::free_balance(&Self::treasury())
-                .saturated_into::<u128>()
-                .saturated_into::<BalanceOf<T>>();
-
-            let fee_on = Self::mint_fee(reserve0, reserve1)?;
-            let total_supply = T::FungibleToken::total_supply(&social_token_id);
-
+            let pair = Self::pairs(&pair_id).ok_or(Error::<T>::PairNotExists)?;
+            ensure!(!Self::is_locked(&pair_id), Error::<T>::Locked);
+            let reserve0 = Self::reserve0(&pair_id);
+            let reserve1 = Self::reserve1(&pair_id);
+            let balance0 = T::FungibleToken::balances(&pair_id.0, &pair.account);
+            let balance1 = T::FungibleToken::balances(&pair_id.1, &pair.account);
+            let liquidity = T::FungibleToken::balances(&pair.lp_token, &pair.account);
+
+            let fee_on = Self::mint_fee(&pair_id, &pair, reserve0, reserve1)?;
+            let total_supply = T::FungibleToken::total_supply(&pair.lp_token);
+            ensure!(total_supply > 0u32.into(), Error::<T>::InsufficientLiquidityBurned);
 
             let amount0 = liquidity.saturating_mul(balance0) / total_supply;
             let amount1 = liquidity.saturating_mul(balance1) / total_supply;
             ensure!(amount0 > 0u32.into() && amount1 > 0u32.into(),  Error::<T>::InsufficientLiquidityBurned);
-            T::FungibleToken::burn(&social_token_id, &to, liquidity)?;
+            T::FungibleToken::burn(&pair.lp_token, &pair.account, liquidity)?;
 
-			T::FungibleToken::transfer(&social_token_id, &Self::token0(), &to, amount0);
-			T::FungibleToken::transfer(&social_token_id, &Self::token1(), &to, amount1);
+            T::FungibleToken::transfer(&pair_id.0, &pair.account, &to, amount0)?;
+            T::FungibleToken::transfer(&pair_id.1, &pair.account, &to, amount1)?;
 
-            let balance0 = T::FungibleToken::balances(&social_token_id, &Self::token0());
-            let balance1 = T::FungibleToken::balances(&social_token_id, &Self::token1());
+            let balance0 = T::FungibleToken::balances(&pair_id.0, &pair.account);
+            let balance1 = T::FungibleToken::balances(&pair_id.1, &pair.account);
 
-            let _ = Self::update(balance0, balance1, reserve0, reserve1);
+            let _ = Self::update(&pair_id, balance0, balance1, reserve0, reserve1);
             if fee_on {
-                <KLast<T>>::put(reserve0.saturating_mul(reserve1));
+                <KLast<T>>::insert(&pair_id, balance0.saturating_mul(balance1));
             }
 
-            Self::deposit_event(RawEvent::Burn(sender, amount0, amount1, to));
+            Self::deposit_event(RawEvent::Burn(pair_id, sender, amount0, amount1, to));
 
             Ok(())
         }
 
+        /// Send `amount0_out` and `amount1_out` of the pair assets to `to`, in exchange of the
+        /// assets transferred to the pair account beforehand. With a non-empty `data` this is a
+        /// flash swap, `T::FlashSwapCallee` gets the outputs first and pays them back.
         #[weight = 10_000 + T::DbWeight::get().writes(1)]
-        fn swap(origin, amount0_out: BalanceOf<T>, amount1_out: BalanceOf<T>, to: T::AccountId, data: Vec<u8>) -> DispatchResult {
+        #[transactional]
+        fn swap(origin, pair_id: PairIdOf<T>, amount0_out: BalanceOf<T>, amount1_out: BalanceOf<T>, to: T::AccountId, data: Vec<u8>) -> DispatchResult {
             let sender = ensure_signed(origin)?;
+            let pair = Self::pairs(&pair_id).ok_or(Error::<T>::PairNotExists)?;
+            ensure!(!Self::is_locked(&pair_id), Error::<T>::Locked);
             ensure!(amount0_out > 0u32.into() || amount1_out > 0u32.into(), Error::<T>::InsufficientOutputAmount);
-            let social_token_id = Self::social_token_id();
-            let reserve0 = Self::reserve0();
-            let reserve1 = Self::reserve1();
-            ensure!(amount0_out < reserve0 || amount1_out < reserve1, Error::<T>::InsufficientLiquidity);
-            let token0 = Self::token0();
-            let token1 = Self::token1();
-            ensure!(to != token0 && to != token1, Error::<T>::InvalidTo);
+            let reserve0 = Self::reserve0(&pair_id);
+            let reserve1 = Self::reserve1(&pair_id);
+            ensure!(amount0_out < reserve0 && amount1_out < reserve1, Error::<T>::InsufficientLiquidity);
+            ensure!(to != pair.account, Error::<T>::InvalidTo);
 
             if amount0_out > 0u32.into() {
-				T::FungibleToken::transfer(&social_token_id, &token0, &to, amount0_out);
+                T::FungibleToken::transfer(&pair_id.0, &pair.account, &to, amount0_out)?;
             }
             if amount1_out > 0u32.into() {
-				T::FungibleToken::transfer(&social_token_id, &token1, &to, amount1_out);
+                T::FungibleToken::transfer(&pair_id.1, &pair.account, &to, amount1_out)?;
+            }
+            if !data.is_empty() {
+                // the callee must not reach the reserves read above through the pair
+                <Locked<T>>::insert(&pair_id, true);
+                let result = T::FlashSwapCallee::on_flash_swap(&sender, &to, &pair_id, amount0_out, amount1_out, &data);
+                <Locked<T>>::remove(&pair_id);
+                result?;
             }
-            // TODO:
-            // if (data.length > 0) IUniswapV2Callee(to).uniswapV2Call(msg.sender, amount0Out, amount1Out, data);
-            let balance0 = T::FungibleToken::balances(&social_token_id, &Self::token0());
-            let balance1 = T::FungibleToken::balances(&social_token_id, &Self::token1());
+            let balance0 = T::FungibleToken::balances(&pair_id.0, &pair.account);
+            let balance1 = T::FungibleToken::balances(&pair_id.1, &pair.account);
 
             let amount0_in = if balance0 > reserve0 - amount0_out {
                 balance0 - (reserve0 - amount0_out)
@@ -214,13 +420,23 @@ decl_module! {
             };
             ensure!(amount0_in > 0u32.into() || amount1_in > 0u32.into(), Error::<T>::InsufficientInputAmount);
 
-            let balance0_adjusted = balance0.saturating_mul(1000u32.into()).saturating_sub(amount0_in.saturating_mul(3u32.into()));
-            let balance1_adjusted = balance1.saturating_mul(1000u32.into()).saturating_sub(amount1_in.saturating_mul(3u32.into()));
-            ensure!(balance0_adjusted.saturating_mul(balance1_adjusted) >= reserve0.saturating_mul(reserve1).saturating_mul(1_000_000u32.into()), Error::<T>::InvalidK);
+            let swap_fee = Self::fee_rates(&pair_id).swap_fee;
+            let fee_base = U256::from(Permill::one().deconstruct());
+            let fee_parts = U256::from(swap_fee.deconstruct());
+            let balance0_adjusted = Self::to_u256(balance0) * fee_base - Self::to_u256(amount0_in) * fee_parts;
+            let balance1_adjusted = Self::to_u256(balance1) * fee_base - Self::to_u256(amount1_in) * fee_parts;
+            // the products need up to 2 * (128 + 20) bits
+            ensure!(
+                balance0_adjusted.full_mul(balance1_adjusted)
+                    >= (Self::to_u256(reserve0) * Self::to_u256(reserve1)).full_mul(fee_base * fee_base),
+                Error::<T>::InvalidK
+            );
 
-            let _ = Self::update(balance0, balance1, reserve0, reserve1);
+            let _ = Self::update(&pair_id, balance0, balance1, reserve0, reserve1);
 
-            Self::deposit_event(RawEvent::Swap(sender, amount0_in, amount1_in, amount0_out, amount1_out, to));
+            let fee0 = swap_fee.mul_floor(amount0_in);
+            let fee1 = swap_fee.mul_floor(amount1_in);
+            Self::deposit_event(RawEvent::Swap(pair_id, sender, amount0_in, amount1_in, amount0_out, amount1_out, fee0, fee1, to));
 
             Ok(())
         }
@@ -228,10 +444,49 @@ decl_module! {
 }
 
 impl<T: Config> Module<T> {
-    fn mint_fee(reserve0: BalanceOf<T>, reserve1: BalanceOf<T>) -> Result<bool, DispatchError> {
-        let fee_to = Self::fee_to();
-        let fee_on = fee_to != Self::address0();
-        let k_last = Self::k_last();
+    /// The account id of the factory, owner of all the LP tokens
+    pub fn account_id() -> T::AccountId {
+        T::ModuleId::get().into_account()
+    }
+
+    /// Sort two assets into the id of the pair they form
+    pub fn sort_assets(asset_a: T::AssetId, asset_b: T::AssetId) -> PairIdOf<T> {
+        if asset_a < asset_b {
+            (asset_a, asset_b)
+        } else {
+            (asset_b, asset_a)
+        }
+    }
+
+    /// The deterministic account holding the reserves of a pair
+    pub fn pair_account_id(pair_id: &PairIdOf<T>) -> T::AccountId {
+        let entropy = (T::ModuleId::get(), pair_id).using_encoded(blake2_256);
+        T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+    }
+
+    /// Fees of a pair, the default ones unless governance overrode them
+    pub fn fee_rates(pair_id: &PairIdOf<T>) -> FeeRates {
+        Self::pair_fees(pair_id).unwrap_or_else(|| FeeRates {
+            swap_fee: T::SwapFee::get(),
+            protocol_share: T::ProtocolFeeShare::get(),
+        })
+    }
+
+    fn to_u256(amount: BalanceOf<T>) -> U256 {
+        U256::from(amount.saturated_into::<u128>())
+    }
+
+    /// Mint the protocol share of the fees collected since the last liquidity event
+    /// to the factory account, where they wait to be swept to the treasury
+    fn mint_fee(
+        pair_id: &PairIdOf<T>,
+        pair: &PairInfo<T::AccountId, T::AssetId>,
+        reserve0: BalanceOf<T>,
+        reserve1: BalanceOf<T>,
+    ) -> Result<bool, DispatchError> {
+        let protocol_share = Self::fee_rates(pair_id).protocol_share;
+        let fee_on = !protocol_share.is_zero();
+        let k_last = Self::k_last(pair_id);
         if fee_on {
             if k_last != 0u32.into() {
                 let root_k = (reserve0 * reserve1)
@@ -239,28 +494,70 @@ impl<T: Config> Module<T> {
                     .integer_sqrt();
                 let root_k_last = k_last.saturated_into::<u128>().integer_sqrt();
                 if root_k > root_k_last {
-                    let total_supply = T::FungibleToken::total_supply(&Self::social_token_id())
+                    let total_supply = T::FungibleToken::total_supply(&pair.lp_token)
                         .saturated_into::<u128>();
-                    let numerator = total_supply * (root_k - root_k_last);
-                    let denominator = root_k * 5 + root_k_last;
-                    let liquidity = numerator / denominator;
+                    // share φ of the growth of sqrt(k), with φ = 1/6 this is the `5 * root_k` of uniswap
+                    let share = U256::from(protocol_share.deconstruct());
+                    let fee_base = U256::from(Permill::one().deconstruct());
+                    let numerator = U256::from(total_supply) * U256::from(root_k - root_k_last) * share;
+                    let denominator = U256::from(root_k) * (fee_base - share) + U256::from(root_k_last) * share;
+                    let liquidity = (numerator / denominator).low_u128();
                     if liquidity > 0 {
                         T::FungibleToken::issue(
-                            &Self::social_token_id(),
-                            &fee_to,
+                            &pair.lp_token,
+                            &Self::account_id(),
                             liquidity.saturated_into(),
                         )?;
                     }
                 }
             }
         } else if k_last != 0u32.into() {
-            <KLast<T>>::put(Into::<BalanceOf<T>>::into(0u32));
+            <KLast<T>>::insert(pair_id, Into::<BalanceOf<T>>::into(0u32));
         }
 
         Ok(fee_on)
     }
 
+    /// The current time in seconds, truncated to 32 bits like the timestamps of the pairs
+    fn current_timestamp() -> Result<u32, DispatchError> {
+        let timestamp = TryInto::<u32>::try_into(
+            (<pallet_timestamp::Module<T>>::now().saturated_into::<u64>() / 1000)
+                .rem(2u64.pow(32)),
+        )
+        .map_err(|_| Error::<T>::OverFlow)?;
+        Ok(timestamp)
+    }
+
+    /// Removes the storage values of the single pool of the previous version. `Address0`
+    /// is kept, the other values were replaced by maps keyed by pair.
+    fn remove_single_pool() -> Weight {
+        if !migration::have_storage_value(b"UniswapExchanges", b"Token0", &[]) {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let items: [&[u8]; 11] = [
+            b"AssetId",
+            b"FeeTo",
+            b"Treasury",
+            b"Token0",
+            b"Token1",
+            b"Reserve0",
+            b"Reserve1",
+            b"Price0CumulativeLast",
+            b"Price1CumulativeLast",
+            b"KLast",
+            b"BlockTimestampLast",
+        ];
+        for item in items.iter() {
+            // only the value itself, its key is the prefix of the map replacing it
+            migration::take_storage_value::<()>(b"UniswapExchanges", item, &[]);
+        }
+
+        T::DbWeight::get().reads_writes(1, items.len() as Weight)
+    }
+
     fn update(
+        pair_id: &PairIdOf<T>,
         balance0: BalanceOf<T>,
         balance1: BalanceOf<T>,
         reserve0: BalanceOf<T>,
@@ -280,53 +577,22 @@ impl<T: Config> Module<T> {
                 .is_some(),
             Error::<T>::OverFlow
         );
-        let block_timestamp = TryInto::<u32>::try_into(
-            <pallet_timestamp::Module<T>>::now()
-                .saturated_into::<u64>()
-                .rem(2u64.pow(32)),
-        )
-        .map_err(|_| Error::<T>::OverFlow)?;
-        let time_elapsed = block_timestamp - Self::block_timestamp_last();
-        if time_elapsed > 0u32 && reserve0 != 0u32.into() && reserve1 != 0u32.into() {
-            // * never overflows, and + overflow is desired
-            <Price0CumulativeLast>::mutate(|price| {
-                let new_price = (reserve1
-                    .saturated_into::<u128>()
-                    .saturating_mul(2u128.pow(64))
-                    / reserve0.saturated_into::<u128>())
-                .saturating_mul(Into::<u128>::into(time_elapsed));
-                *price = price.saturating_add(new_price);
-            });
-            <Price1CumulativeLast>::mutate(|price| {
-                let new_price = (reserve0
-                    .saturated_into::<u128>()
-                    .saturating_mul(2u128.pow(64))
-                    / reserve1.saturated_into::<u128>())
-                .saturating_mul(Into::<u128>::into(time_elapsed));
-                *price = price.saturating_add(new_price);
-            });
+        let block_timestamp = Self::current_timestamp()?;
+        let time_elapsed = block_timestamp.wrapping_sub(Self::block_timestamp_last(pair_id));
+        let (price0_growth, price1_growth) = Self::cumulative_price_growth(reserve0, reserve1, time_elapsed);
+        if price0_growth != 0 || price1_growth != 0 {
+            // overflow is desired, only differences of cumulative prices are meaningful
+            <Price0CumulativeLast<T>>::mutate(pair_id, |price| *price = price.wrapping_add(price0_growth));
+            <Price1CumulativeLast<T>>::mutate(pair_id, |price| *price = price.wrapping_add(price1_growth));
         }
 
-        <Reserve0<T>>::put(balance0);
-        <Reserve1<T>>::put(balance1);
-        <BlockTimestampLast>::put(block_timestamp);
+        <Reserve0<T>>::insert(pair_id, balance0);
+        <Reserve1<T>>::insert(pair_id, balance1);
+        <BlockTimestampLast<T>>::insert(pair_id, block_timestamp);
+        Self::record_observation(pair_id, block_timestamp);
 
-        Self::deposit_event(RawEvent::SyncDone(reserve0, reserve1));
+        Self::deposit_event(RawEvent::SyncDone(*pair_id, reserve0, reserve1));
 
         Ok(())
     }
-
-    fn safe_transfer(
-        _token: &T::AccountId,
-        to: &T::AccountId,
-        amount: BalanceOf<T>,
-    ) -> DispatchResult {
-        <T as Config>::Currency::transfer(
-            &Self::treasury(),
-            to,
-            amount.saturated_into::<u128>().saturated_into(),
-            ExistenceRequirement::AllowDeath,
-        )?;
-        Ok(())
-    }
 }
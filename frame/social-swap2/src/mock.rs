@@ -5,7 +5,9 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
 };
 use frame_system as system;
-use sp_runtime::traits::Convert;
+use sp_runtime::{DispatchResult, ModuleId, Permill};
+use pallet_assets::Fungible;
+use crate::FlashSwapCallee;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -85,6 +87,7 @@ parameter_types! {
 		pub const StringLimit: u32 = 50;
 		pub const MetadataDepositBase: u64 = 1;
 		pub const MetadataDepositPerByte: u64 = 1;
+		pub const ApprovalDeposit: u64 = 1;
 	}
 
 impl pallet_assets::Config for Test {
@@ -98,43 +101,99 @@ impl pallet_assets::Config for Test {
 	type StringLimit = StringLimit;
 	type MetadataDepositBase = MetadataDepositBase;
 	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
 	type WeightInfo = ();
 }
 pub const MINIMUM_LIQUIDITY:u64 = 1000;
 
 parameter_types! {
-    pub const MinimumLiquidity: u64 = MINIMUM_LIQUIDITY;
+	pub const MinimumLiquidity: u64 = MINIMUM_LIQUIDITY;
+	pub const SwapModuleId: ModuleId = ModuleId(*b"py/swap2");
+	pub const PairMaxZombies: u32 = 3;
+	pub const PairMinBalance: u32 = 1;
+	pub const SwapFee: Permill = Permill::from_parts(3_000);
+	pub const ProtocolFeeShare: Permill = Permill::zero();
+	pub const FeeTreasury: u64 = FEE_TO;
+	pub const ObservationCapacity: u32 = 3;
+	pub const ObservationPeriod: u32 = 10;
 }
 
 impl pallet_social_swap2::Config for Test {
 	type Currency = Balances;
+	type ModuleId = SwapModuleId;
 	type Event = Event;
 	type FungibleToken = Assets;
 	type MinimumLiquidity = MinimumLiquidity;
+	type PairMaxZombies = PairMaxZombies;
+	type PairMinBalance = PairMinBalance;
+	type SwapFee = SwapFee;
+	type ProtocolFeeShare = ProtocolFeeShare;
+	type FeeOrigin = frame_system::EnsureRoot<u64>;
+	type FeeTreasury = FeeTreasury;
+	type ObservationCapacity = ObservationCapacity;
+	type ObservationPeriod = ObservationPeriod;
+	type FlashSwapCallee = FlashBorrower;
 }
-pub const ASSET_ID:u32 = 1;
+
+/// Pays a flash swap back with its fee when `data` is `FLASH_REPAY`, mints on the same pair
+/// when it is `FLASH_REENTER`, does nothing otherwise
+pub struct FlashBorrower;
+impl FlashSwapCallee<u64, u32, u128> for FlashBorrower {
+	fn on_flash_swap(
+		_sender: &u64,
+		to: &u64,
+		pair_id: &(u32, u32),
+		amount0_out: u128,
+		amount1_out: u128,
+		data: &[u8],
+	) -> DispatchResult {
+		if data == FLASH_REENTER {
+			return SocialSwap2::mint(Origin::signed(*to), *pair_id, *to);
+		}
+		if data != FLASH_REPAY {
+			return Ok(());
+		}
+		let pair = SocialSwap2::pairs(pair_id).unwrap();
+		if amount0_out > 0 {
+			<Assets as Fungible<u32, u64>>::transfer(&pair_id.0, to, &pair.account, amount0_out * 1000 / 997 + 1)?;
+		}
+		if amount1_out > 0 {
+			<Assets as Fungible<u32, u64>>::transfer(&pair_id.1, to, &pair.account, amount1_out * 1000 / 997 + 1)?;
+		}
+		Ok(())
+	}
+}
+pub const ASSET_ID0:u32 = 10;
+pub const ASSET_ID1:u32 = 11;
+pub const PAIR_ID:(u32, u32) = (ASSET_ID0, ASSET_ID1);
 pub const ACCOUNT1:u64 = 1;
 pub const ACCOUNT2:u64 = 2;
 pub const ACCOUNT3:u64 = 3;
 pub const MAX_ZOMBIES:u32 = 3;
 pub const MIN_BALANCE:u128 = 1;
 pub const INITIAL_BALANCE:u128 = 100_000_0;
-pub const TOKEN0:u64 = 10;
-pub const TOKEN1:u64 = 11;
 pub const FEE_TO:u64 = 12;
+pub const FLASH_REPAY: &[u8] = b"repay";
+pub const FLASH_REENTER: &[u8] = b"reenter";
 pub const ADDRESS0:u64 = 13;
-pub const TREASURY:u64 = 14;
 pub const INITIAL_SUPPLY: u128  = 1_000_000_000_000_000_000_0000;
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
-		balances: vec![(ACCOUNT1, INITIAL_BALANCE), (ACCOUNT2, INITIAL_BALANCE)],
+		balances: vec![
+			(ACCOUNT1, INITIAL_BALANCE),
+			(ACCOUNT2, INITIAL_BALANCE),
+			(SocialSwap2::account_id(), INITIAL_BALANCE),
+		],
 	}.assimilate_storage(&mut t).unwrap();
 
 	pallet_assets::GenesisConfig::<Test> {
-		assets: vec![(ASSET_ID, ACCOUNT1, ACCOUNT1, MAX_ZOMBIES, MIN_BALANCE)],
-		accounts: vec![(ASSET_ID, ACCOUNT1, INITIAL_SUPPLY), (ASSET_ID, TREASURY, 0u128)],
+		assets: vec![
+			(ASSET_ID0, ACCOUNT1, ACCOUNT1, MAX_ZOMBIES, MIN_BALANCE),
+			(ASSET_ID1, ACCOUNT1, ACCOUNT1, MAX_ZOMBIES, MIN_BALANCE),
+		],
+		accounts: vec![(ASSET_ID0, ACCOUNT1, INITIAL_SUPPLY), (ASSET_ID1, ACCOUNT1, INITIAL_SUPPLY)],
 	}.assimilate_storage(&mut t).unwrap();
 
 	t.into()
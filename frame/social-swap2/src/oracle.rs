@@ -0,0 +1,109 @@
+//! Time weighted average prices of the pairs.
+//!
+//! Every `update` of a pair snapshots its cumulative prices into a ring buffer of
+//! `ObservationCapacity` observations, at most one every `ObservationPeriod`.
+//! The average price over a window is the growth of the cumulative price since the
+//! newest observation at least as old as the window, divided by the time elapsed.
+//! The cumulative prices accumulate the reserves of the end of the previous block,
+//! so trades within a single block cannot move them. Like in Uniswap they wrap around
+//! on overflow, which keeps the difference of two of them exact as long as the average
+//! price over the window fits in 128 bits.
+//!
+//! Timestamps are in seconds truncated to 32 bits, as in Uniswap, so they wrap around
+//! about every 136 years. Windows, and the time covered by the observations, must stay
+//! below 2^32 seconds for the elapsed times to be exact.
+
+use super::*;
+
+impl<T: Config> Module<T> {
+    /// Average prices of a pair over at least the last `window` seconds.
+    ///
+    /// Returns `(price0, price1)` as UQ64.64 fixed point numbers, where `price0` is the
+    /// price of the smaller asset of the pair in the bigger one and `price1` its inverse.
+    /// `None` if the pair has no observation old enough.
+    pub fn consult(pair_id: PairIdOf<T>, window: u32) -> Option<(u128, u128)> {
+        let pair_id = Self::sort_assets(pair_id.0, pair_id.1);
+        let now = Self::current_timestamp().ok()?;
+        let (price0_cumulative, price1_cumulative) = Self::current_cumulative_prices(&pair_id, now);
+
+        let observation = Self::observations(&pair_id)
+            .into_iter()
+            .filter(|o| now.wrapping_sub(o.timestamp) >= window)
+            .min_by_key(|o| now.wrapping_sub(o.timestamp))?;
+        let elapsed = now.wrapping_sub(observation.timestamp);
+        if elapsed == 0 {
+            return None;
+        }
+
+        Some((
+            price0_cumulative.wrapping_sub(observation.price0_cumulative) / u128::from(elapsed),
+            price1_cumulative.wrapping_sub(observation.price1_cumulative) / u128::from(elapsed),
+        ))
+    }
+
+    /// Cumulative prices of a pair as if it was updated at `now`
+    fn current_cumulative_prices(pair_id: &PairIdOf<T>, now: u32) -> (u128, u128) {
+        let (price0_growth, price1_growth) = Self::cumulative_price_growth(
+            Self::reserve0(pair_id),
+            Self::reserve1(pair_id),
+            now.wrapping_sub(Self::block_timestamp_last(pair_id)),
+        );
+        (
+            Self::price0_cumulative_last(pair_id).wrapping_add(price0_growth),
+            Self::price1_cumulative_last(pair_id).wrapping_add(price1_growth),
+        )
+    }
+
+    /// Growth of the cumulative prices over `time_elapsed` at constant reserves, truncated
+    /// to 128 bits since the cumulative prices wrap around anyway
+    pub(crate) fn cumulative_price_growth(
+        reserve0: BalanceOf<T>,
+        reserve1: BalanceOf<T>,
+        time_elapsed: u32,
+    ) -> (u128, u128) {
+        let reserve0 = Self::to_u256(reserve0);
+        let reserve1 = Self::to_u256(reserve1);
+        if time_elapsed == 0 || reserve0.is_zero() || reserve1.is_zero() {
+            return (0, 0);
+        }
+        let q64 = U256::from(2u128.pow(64));
+        let elapsed = U256::from(time_elapsed);
+        (
+            ((reserve1 * q64 / reserve0) * elapsed).low_u128(),
+            ((reserve0 * q64 / reserve1) * elapsed).low_u128(),
+        )
+    }
+
+    /// Snapshot the cumulative prices of a pair, overwriting its oldest observation once
+    /// the buffer is full
+    pub(crate) fn record_observation(pair_id: &PairIdOf<T>, timestamp: u32) {
+        let capacity = T::ObservationCapacity::get() as usize;
+        if capacity == 0 {
+            return;
+        }
+
+        let mut observations = Self::observations(pair_id);
+        let mut index = Self::observation_index(pair_id) as usize;
+        if let Some(last) = observations.get(index) {
+            if timestamp.wrapping_sub(last.timestamp) < T::ObservationPeriod::get() {
+                return;
+            }
+        }
+
+        let observation = Observation {
+            timestamp,
+            price0_cumulative: Self::price0_cumulative_last(pair_id),
+            price1_cumulative: Self::price1_cumulative_last(pair_id),
+        };
+        if observations.len() < capacity {
+            observations.push(observation);
+            index = observations.len() - 1;
+        } else {
+            index = (index + 1) % observations.len();
+            observations[index] = observation;
+        }
+
+        <Observations<T>>::insert(pair_id, observations);
+        <ObservationIndex<T>>::insert(pair_id, index as u32);
+    }
+}
@@ -1,15 +1,68 @@
 use crate::{mock::*, Error};
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
 use codec::Encode;
 
+#[test]
+fn test_create_pair_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID1, ASSET_ID0));
+
+		let pair = SocialSwap2::pairs(PAIR_ID).unwrap();
+		assert_eq!(pair.account, SocialSwap2::pair_account_id(&PAIR_ID));
+		assert_eq!(SocialSwap2::lp_to_pair(pair.lp_token), Some(PAIR_ID));
+		assert!(pallet_assets::Module::<Test>::exists(&pair.lp_token));
+	});
+}
+
+#[test]
+fn test_create_pair_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID0),
+			Error::<Test>::IdenticalAssets
+		);
+		assert_noop!(
+			SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, 99),
+			Error::<Test>::AssetNotExists
+		);
+
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+		assert_noop!(
+			SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID1, ASSET_ID0),
+			Error::<Test>::PairExists
+		);
+	});
+}
+
+#[test]
+fn test_pairs_have_distinct_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_ne!(
+			SocialSwap2::pair_account_id(&(ASSET_ID0, ASSET_ID1)),
+			SocialSwap2::pair_account_id(&(ASSET_ID0, 12))
+		);
+	});
+}
+
 #[test]
 fn test_mint_should_not_work() {
 	new_test_ext().execute_with(|| {
-		<Reserve0<Test>>::put(INITIAL_BALANCE);
 		assert_noop!(
 			SocialSwap2::mint(
 				Origin::signed(ACCOUNT1),
+				PAIR_ID,
+				ACCOUNT3
+			),
+			Error::<Test>::PairNotExists
+		);
+
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+		<Reserve0<Test>>::insert(PAIR_ID, INITIAL_BALANCE);
+		assert_noop!(
+			SocialSwap2::mint(
+				Origin::signed(ACCOUNT1),
+				PAIR_ID,
 				ACCOUNT3
 			),
 			Error::<Test>::NotEnoughLiquidity
@@ -21,51 +74,54 @@ fn test_mint_should_not_work() {
 fn test_mint_should_work() {
 	new_test_ext().execute_with(|| {
 
-		SocialSwap2::initialize(Origin::root(), FEE_TO, ADDRESS0, TREASURY, TOKEN0, TOKEN1);
+		SocialSwap2::initialize(Origin::root(), ADDRESS0);
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+		let pair = SocialSwap2::pairs(PAIR_ID).unwrap();
 		let token_0_amount: u128  = 1_000_000_000_000_000_000;
 		let token_1_amount: u128  = 4_000_000_000_000_000_000;
-		pallet_assets::Module::<Test>::transfer(&ASSET_ID, &ACCOUNT1, &TOKEN0, token_0_amount);
-		pallet_assets::Module::<Test>::transfer(&ASSET_ID, &ACCOUNT1, &TOKEN1, token_1_amount);
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, token_0_amount);
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID1, &ACCOUNT1, &pair.account, token_1_amount);
 		let expected_liquidity: u128 = 2_000_000_000_000_000_000u128;
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::total_supply(ASSET_ID),
+			pallet_assets::Module::<Test>::total_supply(pair.lp_token),
 			0
 		);
 		assert_ok!(
 			SocialSwap2::mint(
 				Origin::signed(ACCOUNT1),
+				PAIR_ID,
 				ACCOUNT2
 			)
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::total_supply(ASSET_ID),
+			pallet_assets::Module::<Test>::total_supply(pair.lp_token),
 			expected_liquidity
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, ACCOUNT2),
+			pallet_assets::Module::<Test>::balance(pair.lp_token, ACCOUNT2),
 			expected_liquidity - MINIMUM_LIQUIDITY as u128
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, TOKEN0),
+			pallet_assets::Module::<Test>::balance(ASSET_ID0, pair.account),
 			token_0_amount
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, TOKEN1),
+			pallet_assets::Module::<Test>::balance(ASSET_ID1, pair.account),
 			token_1_amount
 		);
 
 		assert_eq!(
-			SocialSwap2::reserve0(),
+			SocialSwap2::reserve0(PAIR_ID),
 			token_0_amount
 		);
 
 		assert_eq!(
-			SocialSwap2::reserve1(),
+			SocialSwap2::reserve1(PAIR_ID),
 			token_1_amount
 		);
 	});
@@ -74,9 +130,11 @@ fn test_mint_should_work() {
 #[test]
 fn test_burn_should_not_work() {
     new_test_ext().execute_with(|| {
+        assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
         assert_noop!(
             SocialSwap2::mint(
                 Origin::signed(ACCOUNT1),
+                PAIR_ID,
                 ACCOUNT3
             ),
             Error::<Test>::InsufficientLiquidityMinted
@@ -89,21 +147,22 @@ fn test_burn_should_work() {
 	new_test_ext().execute_with(|| {
 		let token_0_amount: u128  = 3_000_000_000_000_000_000;
 		let token_1_amount: u128  = 3_000_000_000_000_000_000;
-		add_liquidity(token_0_amount, token_1_amount);
+		let pair = add_liquidity(token_0_amount, token_1_amount);
 		let expected_liquidity: u128 = 3_000_000_000_000_000_000u128;
 
 		assert_noop!(
 			SocialSwap2::burn(
 				Origin::signed(ACCOUNT1),
+				PAIR_ID,
 				ACCOUNT2
 			),
 			Error::<Test>::InsufficientLiquidityBurned
 		);
 
-		pallet_balances::Module::<Test>::deposit_creating(&TREASURY, expected_liquidity - MINIMUM_LIQUIDITY as u128);
+		pallet_assets::Module::<Test>::transfer(&pair.lp_token, &ACCOUNT2, &pair.account, expected_liquidity - MINIMUM_LIQUIDITY as u128);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::total_supply(ASSET_ID),
+			pallet_assets::Module::<Test>::total_supply(pair.lp_token),
 			expected_liquidity
 		);
 
@@ -111,38 +170,44 @@ fn test_burn_should_work() {
 		assert_ok!(
 			SocialSwap2::burn(
 				Origin::signed(ACCOUNT1),
+				PAIR_ID,
 				ACCOUNT2
 			)
 		);
 
 		assert_eq!(
-			SocialSwap2::reserve0(),
+			SocialSwap2::reserve0(PAIR_ID),
 			MINIMUM_LIQUIDITY as u128
 		);
 
 		assert_eq!(
-			SocialSwap2::reserve1(),
+			SocialSwap2::reserve1(PAIR_ID),
 			MINIMUM_LIQUIDITY as u128
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::total_supply(ASSET_ID),
+			pallet_assets::Module::<Test>::total_supply(pair.lp_token),
 			MINIMUM_LIQUIDITY as u128
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, TOKEN0),
+			pallet_assets::Module::<Test>::balance(ASSET_ID0, pair.account),
 			MINIMUM_LIQUIDITY as u128
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, TOKEN1),
+			pallet_assets::Module::<Test>::balance(ASSET_ID1, pair.account),
 			MINIMUM_LIQUIDITY as u128
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, ACCOUNT2),
-			token_0_amount + token_1_amount - 2000u128
+			pallet_assets::Module::<Test>::balance(ASSET_ID0, ACCOUNT2),
+			token_0_amount - MINIMUM_LIQUIDITY as u128
+		);
+
+		assert_eq!(
+			pallet_assets::Module::<Test>::balance(ASSET_ID1, ACCOUNT2),
+			token_1_amount - MINIMUM_LIQUIDITY as u128
 		);
 
 	});
@@ -154,6 +219,19 @@ fn test_swap_should_not_work() {
 
 		assert_noop!(SocialSwap2::swap(
 			Origin::signed(ACCOUNT1),
+			PAIR_ID,
+			1,
+			0,
+			ACCOUNT3,
+			"0x".encode()
+		),
+		Error::<Test>::PairNotExists);
+
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+
+		assert_noop!(SocialSwap2::swap(
+			Origin::signed(ACCOUNT1),
+			PAIR_ID,
 			0,
 			0,
 			ACCOUNT3,
@@ -163,6 +241,7 @@ fn test_swap_should_not_work() {
 
 		assert_noop!(SocialSwap2::swap(
 			Origin::signed(ACCOUNT1),
+			PAIR_ID,
 			1,
 			2,
 			ACCOUNT3,
@@ -170,11 +249,12 @@ fn test_swap_should_not_work() {
 		),
 		Error::<Test>::InsufficientLiquidity);
 
-		<Reserve0<Test>>::put(2);
-		<Reserve1<Test>>::put(2);
+		<Reserve0<Test>>::insert(PAIR_ID, 2);
+		<Reserve1<Test>>::insert(PAIR_ID, 2);
 
 		assert_noop!(SocialSwap2::swap(
 			Origin::signed(ACCOUNT1),
+			PAIR_ID,
 			3,
 			3,
 			ACCOUNT3,
@@ -182,13 +262,12 @@ fn test_swap_should_not_work() {
 		),
 		Error::<Test>::InsufficientLiquidity);
 
-		SocialSwap2::initialize(Origin::root(), FEE_TO, ADDRESS0, TREASURY, TOKEN0, TOKEN0);
-
 		assert_noop!(SocialSwap2::swap(
 			Origin::signed(ACCOUNT1),
+			PAIR_ID,
 			1,
 			1,
-			TOKEN0,
+			SocialSwap2::pair_account_id(&PAIR_ID),
 			"0x".encode()
 		),
 		Error::<Test>::InvalidTo);
@@ -201,14 +280,15 @@ fn test_swap_should_work() {
 	new_test_ext().execute_with(|| {
 		let token_0_amount: u128  = 5_000_000_000_000_000_000;
 		let token_1_amount: u128  = 10_000_000_000_000_000_000;
-		add_liquidity(token_0_amount, token_1_amount);
+		let pair = add_liquidity(token_0_amount, token_1_amount);
 		let expected_output_amount: u128 = 1662497915624478906u128;
 		let swap_amount: u128  = 1_000_000_000_000_000_000;
 
-		pallet_assets::Module::<Test>::transfer(&ASSET_ID, &ACCOUNT1, &TOKEN0, swap_amount);
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, swap_amount);
 
 		assert_ok!(SocialSwap2::swap(
 			Origin::signed(ACCOUNT1),
+			PAIR_ID,
 			0,
 			expected_output_amount,
 			ACCOUNT2,
@@ -217,43 +297,286 @@ fn test_swap_should_work() {
 		);
 
 		assert_eq!(
-			SocialSwap2::reserve0(),
+			SocialSwap2::reserve0(PAIR_ID),
 			token_0_amount + swap_amount
 		);
 
 		assert_eq!(
-			SocialSwap2::reserve1(),
+			SocialSwap2::reserve1(PAIR_ID),
 			token_1_amount - expected_output_amount
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, TOKEN0),
+			pallet_assets::Module::<Test>::balance(ASSET_ID0, pair.account),
 			token_0_amount + swap_amount
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, TOKEN1),
+			pallet_assets::Module::<Test>::balance(ASSET_ID1, pair.account),
 			token_1_amount - expected_output_amount
 		);
 
 		assert_eq!(
-			pallet_assets::Module::<Test>::balance(ASSET_ID, ACCOUNT2),
-			pallet_assets::Module::<Test>::total_supply(ASSET_ID) + expected_output_amount
-			- MINIMUM_LIQUIDITY as u128
+			pallet_assets::Module::<Test>::balance(ASSET_ID1, ACCOUNT2),
+			expected_output_amount
+		);
+	});
+}
+
+
+#[test]
+fn test_swap_with_huge_reserves_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+		let pair = SocialSwap2::pairs(PAIR_ID).unwrap();
+		let reserve: u128 = u128::MAX / 4;
+		let swap_amount: u128 = 1_000_000;
+		assert_ok!(<Assets as IssueAndBurn<u32, u64>>::issue(&ASSET_ID0, &pair.account, reserve + swap_amount));
+		assert_ok!(<Assets as IssueAndBurn<u32, u64>>::issue(&ASSET_ID1, &pair.account, reserve));
+		<Reserve0<Test>>::insert(PAIR_ID, reserve);
+		<Reserve1<Test>>::insert(PAIR_ID, reserve);
+
+		assert_noop!(
+			SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, swap_amount, ACCOUNT2, vec![]),
+			Error::<Test>::InvalidK
+		);
+		assert_ok!(SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, swap_amount * 99 / 100, ACCOUNT2, vec![]));
+		assert_eq!(SocialSwap2::reserve0(PAIR_ID), reserve + swap_amount);
+	});
+}
+
+#[test]
+fn test_set_pair_fees_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+		let rates = FeeRates { swap_fee: Permill::from_percent(1), protocol_share: Permill::from_percent(10) };
+
+		assert_eq!(SocialSwap2::fee_rates(&PAIR_ID).swap_fee, Permill::from_parts(3_000));
+		assert_ok!(SocialSwap2::set_pair_fees(Origin::root(), PAIR_ID, Some(rates)));
+		assert_eq!(SocialSwap2::fee_rates(&PAIR_ID), rates);
+		assert_ok!(SocialSwap2::set_pair_fees(Origin::root(), PAIR_ID, None));
+		assert_eq!(SocialSwap2::pair_fees(PAIR_ID), None);
+
+		assert_noop!(
+			SocialSwap2::set_pair_fees(Origin::signed(ACCOUNT1), PAIR_ID, None),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			SocialSwap2::set_pair_fees(Origin::root(), (ASSET_ID0, 99), None),
+			Error::<Test>::PairNotExists
+		);
+		assert_noop!(
+			SocialSwap2::set_pair_fees(
+				Origin::root(),
+				PAIR_ID,
+				Some(FeeRates { swap_fee: Permill::one(), protocol_share: Permill::zero() })
+			),
+			Error::<Test>::InvalidFee
+		);
+	});
+}
+
+#[test]
+fn test_swap_with_higher_fee_should_not_work() {
+	new_test_ext().execute_with(|| {
+		let token_0_amount: u128  = 5_000_000_000_000_000_000;
+		let token_1_amount: u128  = 10_000_000_000_000_000_000;
+		let pair = add_liquidity(token_0_amount, token_1_amount);
+		let rates = FeeRates { swap_fee: Permill::from_percent(1), protocol_share: Permill::zero() };
+		assert_ok!(SocialSwap2::set_pair_fees(Origin::root(), PAIR_ID, Some(rates)));
+
+		// the output of the 0.3% fee is too much once the fee is 1%
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, 1_000_000_000_000_000_000);
+		assert_noop!(
+			SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, 1662497915624478906, ACCOUNT2, "0x".encode()),
+			Error::<Test>::InvalidK
+		);
+	});
+}
+
+#[test]
+fn test_protocol_fees_should_be_swept() {
+	new_test_ext().execute_with(|| {
+		SocialSwap2::initialize(Origin::root(), ADDRESS0);
+		assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+		let rates = FeeRates { swap_fee: Permill::from_parts(3_000), protocol_share: Permill::from_parts(166_667) };
+		assert_ok!(SocialSwap2::set_pair_fees(Origin::root(), PAIR_ID, Some(rates)));
+		let pair = SocialSwap2::pairs(PAIR_ID).unwrap();
+
+		let token_amount: u128 = 1_000_000_000;
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, token_amount);
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID1, &ACCOUNT1, &pair.account, token_amount);
+		assert_ok!(SocialSwap2::mint(Origin::signed(ACCOUNT1), PAIR_ID, ACCOUNT2));
+		assert_eq!(SocialSwap2::k_last(PAIR_ID), token_amount * token_amount);
+
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, 10_000_000);
+		assert_ok!(SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, 9_871_580, ACCOUNT3, "0x".encode()));
+
+		let liquidity = pallet_assets::Module::<Test>::balance(pair.lp_token, ACCOUNT2);
+		pallet_assets::Module::<Test>::transfer(&pair.lp_token, &ACCOUNT2, &pair.account, liquidity);
+		assert_ok!(SocialSwap2::burn(Origin::signed(ACCOUNT1), PAIR_ID, ACCOUNT2));
+		assert_eq!(pallet_assets::Module::<Test>::balance(pair.lp_token, SocialSwap2::account_id()), 2475);
+
+		assert_ok!(SocialSwap2::sweep_protocol_fees(Origin::signed(ACCOUNT3), PAIR_ID));
+		assert_eq!(pallet_assets::Module::<Test>::balance(pair.lp_token, SocialSwap2::account_id()), 0);
+		assert_eq!(pallet_assets::Module::<Test>::balance(pair.lp_token, FEE_TO), 2475);
+	});
+}
+
+#[test]
+fn test_consult_should_work() {
+	new_test_ext().execute_with(|| {
+		let q64 = 2u128.pow(64);
+		let pair = add_liquidity(1_000_000, 4_000_000);
+		assert_eq!(SocialSwap2::consult(PAIR_ID, 0), None);
+
+		Timestamp::set_timestamp(10_000);
+		assert_eq!(SocialSwap2::consult(PAIR_ID, 10), Some((4 * q64, q64 / 4)));
+		assert_eq!(SocialSwap2::consult((ASSET_ID1, ASSET_ID0), 10), Some((4 * q64, q64 / 4)));
+
+		// a swap does not move the average price of the block it happens in
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, 100_000);
+		assert_ok!(SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, 362_644, ACCOUNT2, "0x".encode()));
+		assert_eq!(SocialSwap2::consult(PAIR_ID, 10), Some((4 * q64, q64 / 4)));
+
+		Timestamp::set_timestamp(20_000);
+		assert_eq!(
+			SocialSwap2::consult(PAIR_ID, 10),
+			Some((3_637_356 * q64 / 1_100_000, 1_100_000 * q64 / 3_637_356))
+		);
+		assert_eq!(SocialSwap2::consult(PAIR_ID, 30), None);
+		assert_eq!(SocialSwap2::consult((ASSET_ID0, 99), 0), None);
+	});
+}
+
+#[test]
+fn test_consult_should_work_across_overflow() {
+	new_test_ext().execute_with(|| {
+		let q64 = 2u128.pow(64);
+		add_liquidity(1_000_000, 4_000_000);
+		let near_max = u128::MAX - q64 + 1;
+		<Price0CumulativeLast<Test>>::insert(PAIR_ID, near_max);
+		<Price1CumulativeLast<Test>>::insert(PAIR_ID, near_max);
+		<Observations<Test>>::insert(PAIR_ID, vec![Observation {
+			timestamp: 0,
+			price0_cumulative: near_max,
+			price1_cumulative: near_max,
+		}]);
+
+		Timestamp::set_timestamp(10_000);
+		assert_eq!(SocialSwap2::consult(PAIR_ID, 10), Some((4 * q64, q64 / 4)));
+	});
+}
+
+#[test]
+fn test_observations_should_be_a_ring_buffer() {
+	new_test_ext().execute_with(|| {
+		for timestamp in [0, 5, 10, 20, 30].iter() {
+			SocialSwap2::record_observation(&PAIR_ID, *timestamp);
+		}
+
+		let timestamps: Vec<u32> = SocialSwap2::observations(PAIR_ID).iter().map(|o| o.timestamp).collect();
+		assert_eq!(timestamps, vec![30, 10, 20]);
+		assert_eq!(SocialSwap2::observation_index(PAIR_ID), 0);
+	});
+}
+
+#[test]
+fn test_flash_swap_should_work() {
+	new_test_ext().execute_with(|| {
+		let pair = add_liquidity(1_000_000, 4_000_000);
+
+		// borrow without paying anything upfront, the callee pays back with the fee
+		assert_ok!(SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, 100_000, ACCOUNT1, FLASH_REPAY.to_vec()));
+
+		assert_eq!(SocialSwap2::reserve0(PAIR_ID), 1_000_000);
+		assert_eq!(SocialSwap2::reserve1(PAIR_ID), 4_000_302);
+		assert_eq!(pallet_assets::Module::<Test>::balance(ASSET_ID1, pair.account), 4_000_302);
+	});
+}
+
+#[test]
+fn test_flash_swap_without_repayment_should_not_work() {
+	new_test_ext().execute_with(|| {
+		add_liquidity(1_000_000, 4_000_000);
+
+		assert_noop!(
+			SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, 100_000, ACCOUNT1, b"keep".to_vec()),
+			Error::<Test>::InsufficientInputAmount
 		);
 	});
 }
 
+#[test]
+fn test_update_should_handle_timestamp_wrap() {
+	new_test_ext().execute_with(|| {
+		let q64 = 2u128.pow(64);
+		Timestamp::set_timestamp((2u64.pow(32) - 10) * 1000);
+		let pair = add_liquidity(1_000_000, 4_000_000);
+
+		Timestamp::set_timestamp((2u64.pow(32) + 10) * 1000);
+		pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, 100_000);
+		assert_ok!(SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, 362_644, ACCOUNT2, "0x".encode()));
 
-fn add_liquidity(token_0_amount: u128, token_1_amount: u128) {
-	SocialSwap2::initialize(Origin::root(), FEE_TO, ADDRESS0, TREASURY, TOKEN0, TOKEN1);
-	pallet_assets::Module::<Test>::transfer(&ASSET_ID, &ACCOUNT1, &TOKEN0, token_0_amount);
-	pallet_assets::Module::<Test>::transfer(&ASSET_ID, &ACCOUNT1, &TOKEN1, token_1_amount);
+		assert_eq!(SocialSwap2::block_timestamp_last(PAIR_ID), 10);
+		assert_eq!(SocialSwap2::price0_cumulative_last(PAIR_ID), 4 * q64 * 20);
+	});
+}
+
+#[test]
+fn test_flash_swap_should_not_reenter_the_pair() {
+	new_test_ext().execute_with(|| {
+		add_liquidity(1_000_000, 4_000_000);
+
+		assert_noop!(
+			SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 0, 100_000, ACCOUNT1, FLASH_REENTER.to_vec()),
+			Error::<Test>::Locked
+		);
+		assert!(!SocialSwap2::is_locked(PAIR_ID));
+	});
+}
+
+#[test]
+fn test_swap_of_both_reserves_should_not_work() {
+	new_test_ext().execute_with(|| {
+		add_liquidity(1_000_000, 4_000_000);
+
+		assert_noop!(
+			SocialSwap2::swap(Origin::signed(ACCOUNT1), PAIR_ID, 1_000_000, 1, ACCOUNT2, "0x".encode()),
+			Error::<Test>::InsufficientLiquidity
+		);
+	});
+}
+
+#[test]
+fn test_runtime_upgrade_should_remove_single_pool() {
+	new_test_ext().execute_with(|| {
+		let pair = add_liquidity(1_000_000, 4_000_000);
+		frame_support::storage::migration::put_storage_value(b"UniswapExchanges", b"Token0", &[], ACCOUNT1);
+		frame_support::storage::migration::put_storage_value(b"UniswapExchanges", b"Reserve0", &[], 500u128);
+
+		SocialSwap2::on_runtime_upgrade();
+
+		assert!(!frame_support::storage::migration::have_storage_value(b"UniswapExchanges", b"Token0", &[]));
+		assert!(!frame_support::storage::migration::have_storage_value(b"UniswapExchanges", b"Reserve0", &[]));
+		assert_eq!(SocialSwap2::reserve0(PAIR_ID), 1_000_000);
+		assert_eq!(SocialSwap2::pairs(PAIR_ID), Some(pair));
+		assert_eq!(SocialSwap2::address0(), ADDRESS0);
+	});
+}
+
+fn add_liquidity(token_0_amount: u128, token_1_amount: u128) -> PairInfo<u64, u32> {
+	SocialSwap2::initialize(Origin::root(), ADDRESS0);
+	assert_ok!(SocialSwap2::create_pair(Origin::signed(ACCOUNT1), ASSET_ID0, ASSET_ID1));
+	let pair = SocialSwap2::pairs(PAIR_ID).unwrap();
+	pallet_assets::Module::<Test>::transfer(&ASSET_ID0, &ACCOUNT1, &pair.account, token_0_amount);
+	pallet_assets::Module::<Test>::transfer(&ASSET_ID1, &ACCOUNT1, &pair.account, token_1_amount);
 	assert_ok!(
 		SocialSwap2::mint(
 			Origin::signed(ACCOUNT1),
+			PAIR_ID,
 			ACCOUNT2
 		)
 	);
+	pair
 }
-
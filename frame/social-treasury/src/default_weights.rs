@@ -136,4 +136,16 @@ impl crate::WeightInfo for () {
             .saturating_add(DbWeight::get().writes(2 as Weight))
             .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
     }
+    // Not benchmarked: an estimate with the storage accesses of the extrinsic.
+    fn set_reward_destination() -> Weight {
+        (21000000 as Weight)
+            .saturating_add(DbWeight::get().reads(1 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    // Not benchmarked: an estimate with the storage accesses of the extrinsic.
+    fn set_emission_policy() -> Weight {
+        (32000000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
 }
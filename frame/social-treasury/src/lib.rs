@@ -1,20 +1,114 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::traits::Get;
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, ensure};
+use codec::{Decode, Encode};
+use frame_support::traits::{EnsureOrigin, Get};
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, ensure, weights::Weight};
 use frame_system::ensure_signed;
 use pallet_assets::IssueAndBurn;
-use pallet_staking::{EraIndex, WeightInfo as StakingWeightInfo};
-use sp_runtime::traits::SaturatedConversion;
-use sp_runtime::{traits::Zero, DispatchResult, Perbill};
+use pallet_staking::{EraIndex, EraRewardPoints, RewardPoint, WeightInfo as StakingWeightInfo};
+use sp_runtime::traits::{AtLeast32BitUnsigned, SaturatedConversion};
+use sp_runtime::{traits::Zero, DispatchResult, Perbill, RuntimeDebug};
 use sp_std::prelude::*;
 pub use weights::WeightInfo;
 
 mod default_weights;
 pub mod weights;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 type BalanceOf<T> = <T as pallet_assets::Config>::Balance;
 
+/// Maximum number of points of a decay curve.
+pub const MAX_CURVE_POINTS: usize = 16;
+
+/// How much of a social token is minted for the rewards of an era.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum EmissionCurve<Balance> {
+    /// Mint the native value of the era payout.
+    EraPayout,
+    /// Mint a fixed amount per era.
+    Fixed(Balance),
+    /// Mint `initial` scaled by a piecewise linear function of the eras since the policy was
+    /// set, given as `(era offset, factor)` points in increasing era order.
+    Decay { initial: Balance, points: Vec<(EraIndex, Perbill)> },
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> EmissionCurve<Balance> {
+    /// Checks the points of a decay curve.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            EmissionCurve::Decay { points, .. } => {
+                !points.is_empty()
+                    && points.len() <= MAX_CURVE_POINTS
+                    && points.windows(2).all(|w| w[0].0 < w[1].0)
+            }
+            _ => true,
+        }
+    }
+
+    /// Amount minted `elapsed` eras after the policy was set, `era_payout` being the native
+    /// value of the era rewards.
+    pub fn emission(&self, elapsed: EraIndex, era_payout: u128) -> u128 {
+        match self {
+            EmissionCurve::EraPayout => era_payout,
+            EmissionCurve::Fixed(amount) => (*amount).saturated_into(),
+            EmissionCurve::Decay { initial, points } => {
+                Self::factor(points, elapsed) * (*initial).saturated_into::<u128>()
+            }
+        }
+    }
+
+    /// Linear interpolation of the points at `x`, flat before the first and after the last one.
+    fn factor(points: &[(EraIndex, Perbill)], x: EraIndex) -> Perbill {
+        match points.iter().position(|(era, _)| *era > x) {
+            None => points.last().map_or_else(Perbill::zero, |p| p.1),
+            Some(0) => points[0].1,
+            Some(i) => {
+                let ((x0, y0), (x1, y1)) = (points[i - 1], points[i]);
+                let (y0, y1) = (y0.deconstruct() as u64, y1.deconstruct() as u64);
+                let (dx, span) = ((x - x0) as u64, (x1 - x0) as u64);
+                let y = if y1 >= y0 {
+                    y0 + (y1 - y0) * dx / span
+                } else {
+                    y0 - (y0 - y1) * dx / span
+                };
+                Perbill::from_parts(y as u32)
+            }
+        }
+    }
+}
+
+/// Emission policy of a social token.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct EmissionPolicy<Balance> {
+    pub curve: EmissionCurve<Balance>,
+    /// Supply rewards never mint the token beyond.
+    pub cap: Option<Balance>,
+    /// Era the policy was set in.
+    pub start: EraIndex,
+}
+
+/// Where the social token rewards of a staker are minted.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum RewardDestination<AccountId> {
+    /// Mint to the stash account.
+    Stash,
+    /// Mint to another account.
+    Account(AccountId),
+    /// Don't mint any rewards.
+    None,
+}
+
+impl<AccountId> Default for RewardDestination<AccountId> {
+    fn default() -> Self {
+        RewardDestination::Stash
+    }
+}
+
 pub trait Config:
     frame_system::Config
     + pallet_staking::Config
@@ -26,6 +120,12 @@ pub trait Config:
 
     type FungibleToken: IssueAndBurn<Self::AssetId, Self::AccountId>;
 
+    /// Maximum number of queued payouts made in `on_initialize`.
+    type MaxPayoutsPerBlock: Get<u32>;
+
+    /// Origin allowed to set the emission policy of any token, besides its owner.
+    type EmissionOrigin: EnsureOrigin<Self::Origin>;
+
     /// Weight information for extrinsics in this pallet.
     type WeightInfo: WeightInfo;
 }
@@ -35,6 +135,18 @@ decl_storage! {
         /// List of eras for which the stakers behind a validator have claimed rewards. Only updated
         /// for validators.
         pub ClaimedRewards get(fn claimed_rewards): map hasher(blake2_128_concat) T::AccountId => Vec<EraIndex>;
+        /// Guardian stashes whose rewards for an era are paid out automatically.
+        pub PendingPayouts get(fn pending_payouts): double_map hasher(twox_64_concat) EraIndex, hasher(twox_64_concat) T::AccountId => ();
+        /// Last era whose payouts were queued. Without one, eras are queued from the oldest one
+        /// still in the staking history, so the eras ended before payouts were automatic get paid.
+        pub LastQueuedEra get(fn last_queued_era): Option<EraIndex>;
+        /// Where the rewards of a stash are minted, if not to the stash itself.
+        pub RewardDestinations get(fn reward_destination): map hasher(blake2_128_concat) T::AccountId => RewardDestination<T::AccountId>;
+        /// Emission policy of a social token, its rewards follow the era payout without one.
+        pub EmissionPolicies get(fn emission_policy): map hasher(blake2_128_concat) T::AssetId => Option<EmissionPolicy<BalanceOf<T>>>;
+        /// Reward points of the guardians of a token in an era, counted on its first payout
+        /// under an emission policy.
+        pub ErasTokenRewardPoints get(fn eras_token_reward_points): double_map hasher(twox_64_concat) EraIndex, hasher(blake2_128_concat) T::AssetId => Option<RewardPoint>;
     }
 }
 
@@ -47,6 +159,12 @@ decl_event!(
     {
         /// The staker has been rewarded by this amount. \[asset_id, stash, amount\]
         Reward(AssetId, AccountId, Balance),
+        /// The rewards of an ended era were queued for payout. \[era, guardians\]
+        PayoutsQueued(EraIndex, u32),
+        /// The stash changed where its rewards are minted. \[stash, destination\]
+        RewardDestinationSet(AccountId, RewardDestination<AccountId>),
+        /// The emission policy of a token changed. \[asset_id, policy\]
+        EmissionPolicySet(AssetId, Option<EmissionPolicy<Balance>>),
     }
 );
 
@@ -63,6 +181,10 @@ decl_error! {
         /// Rewards for this era have already been claimed for this validator.
         AlreadyClaimed,
         IsNotGuardian,
+        /// Not the owner of the asset.
+        NotAssetOwner,
+        /// Decay curve without points, with too many or unordered points.
+        InvalidEmissionCurve,
     }
 }
 
@@ -72,6 +194,18 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        const MaxPayoutsPerBlock: u32 = T::MaxPayoutsPerBlock::get();
+
+        /// Queue the guardians of the next ended era not queued yet, then pay out at most
+        /// `MaxPayoutsPerBlock` of the queued guardians.
+        fn on_initialize() -> Weight {
+            let mut weight = Self::queue_ended_era();
+            if <pallet_staking::Module<T>>::era_election_status().is_closed() {
+                weight = weight.saturating_add(Self::process_payouts());
+            }
+            weight
+        }
+
         /// Pay out all the stakers behind a single validator for a single era.
         ///
         /// - `validator_stash` is the stash account of the validator. Their nominators, up to
@@ -106,10 +240,120 @@ decl_module! {
             ensure_signed(origin)?;
             Self::do_payout_stakers(validator_stash, era)
         }
+
+        /// Redirect the social token rewards of the sender's stash to another account, or opt
+        /// out of them. The sender must be a stash, as payouts look the destination up by stash.
+        #[weight = <T as Config>::WeightInfo::set_reward_destination()]
+        fn set_reward_destination(origin, destination: RewardDestination<T::AccountId>) -> DispatchResult {
+            let stash = ensure_signed(origin)?;
+            ensure!(<pallet_staking::Bonded<T>>::contains_key(&stash), Error::<T>::NotStash);
+            if destination == RewardDestination::Stash {
+                <RewardDestinations<T>>::remove(&stash);
+            } else {
+                <RewardDestinations<T>>::insert(&stash, &destination);
+            }
+            Self::deposit_event(RawEvent::RewardDestinationSet(stash, destination));
+            Ok(())
+        }
+
+        /// Set or clear the emission policy of a social token. Called by the token owner or
+        /// `EmissionOrigin`.
+        #[weight = <T as Config>::WeightInfo::set_emission_policy()]
+        fn set_emission_policy(
+            origin,
+            asset_id: T::AssetId,
+            curve: Option<EmissionCurve<BalanceOf<T>>>,
+            cap: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            if let Err(origin) = T::EmissionOrigin::try_origin(origin) {
+                let who = ensure_signed(origin)?;
+                ensure!(<pallet_assets::Module<T>>::owner(asset_id) == Some(who), Error::<T>::NotAssetOwner);
+            }
+            <pallet_assets::Module<T>>::validate_asset_id(asset_id)?;
+
+            let policy = match curve {
+                Some(curve) => {
+                    ensure!(curve.is_valid(), Error::<T>::InvalidEmissionCurve);
+                    let start = pallet_staking::CurrentEra::get().unwrap_or(0);
+                    Some(EmissionPolicy { curve, cap, start })
+                }
+                None => None,
+            };
+            match &policy {
+                Some(policy) => <EmissionPolicies<T>>::insert(asset_id, policy),
+                None => <EmissionPolicies<T>>::remove(asset_id),
+            }
+            Self::deposit_event(RawEvent::EmissionPolicySet(asset_id, policy));
+            Ok(())
+        }
     }
 }
 
 impl<T: Config> Module<T> {
+    /// Queues a payout for every guardian with unclaimed reward points in the next ended era,
+    /// one era per block so that skipped eras are caught up with.
+    fn queue_ended_era() -> Weight {
+        let db_weight = T::DbWeight::get();
+        let ended_era = match pallet_staking::ActiveEra::get() {
+            Some(active_era) if active_era.index > 0 => active_era.index - 1,
+            _ => return db_weight.reads(1),
+        };
+        let history_depth = <pallet_staking::Module<T>>::history_depth();
+        let oldest_era = pallet_staking::CurrentEra::get().unwrap_or(0).saturating_sub(history_depth);
+        let era = match Self::last_queued_era() {
+            Some(last_era) => last_era.saturating_add(1).max(oldest_era),
+            None => oldest_era,
+        };
+        if era > ended_era {
+            return db_weight.reads(4);
+        }
+
+        let mut queued = 0u32;
+        let era_reward_points = <pallet_staking::ErasRewardPoints<T>>::get(&era);
+        for stash in era_reward_points.individual.keys() {
+            let is_unclaimed_guardian = <pallet_staking::Module<T>>::bonded(stash).map_or(false, |controller| {
+                <pallet_social_guardians::Module<T>>::guardian_at(era, &controller).is_some()
+                    && Self::claimed_rewards(&controller).binary_search(&era).is_err()
+            });
+            if is_unclaimed_guardian {
+                <PendingPayouts<T>>::insert(era, stash, ());
+                queued += 1;
+            }
+        }
+        <LastQueuedEra>::put(era);
+        Self::deposit_event(RawEvent::PayoutsQueued(era, queued));
+        // the points of eras out of the history can't be paid out anymore
+        if let Some(stale_era) = era.checked_sub(history_depth + 1) {
+            <ErasTokenRewardPoints<T>>::remove_prefix(stale_era);
+        }
+
+        let validators = era_reward_points.individual.len() as u64;
+        db_weight.reads_writes(5 + validators * 3, 2 + queued as u64)
+    }
+
+    /// Pays out queued guardians until `MaxPayoutsPerBlock` is reached. Failed payouts, e.g.
+    /// already claimed through `payout_stakers`, are dropped.
+    fn process_payouts() -> Weight {
+        let max_payouts = T::MaxPayoutsPerBlock::get();
+        let payouts: Vec<_> = <PendingPayouts<T>>::iter()
+            .take(max_payouts as usize)
+            .map(|(era, stash, _)| (era, stash))
+            .collect();
+
+        let count = payouts.len() as u32;
+        for (era, stash) in payouts {
+            <PendingPayouts<T>>::remove(era, &stash);
+            let _ = Self::do_payout_stakers(stash, era);
+        }
+
+        let payout_weight = <T as pallet_staking::Config>::WeightInfo::payout_stakers_alive_staked(
+            <T as pallet_staking::Config>::MaxNominatorRewardedPerValidator::get(),
+        );
+        T::DbWeight::get()
+            .reads_writes(1 + count as u64, count as u64)
+            .saturating_add(payout_weight.saturating_mul(count as Weight))
+    }
+
     fn do_payout_stakers(validator_stash: T::AccountId, era: EraIndex) -> DispatchResult {
         // Validate input data
         let current_era =
@@ -130,9 +374,8 @@ impl<T: Config> Module<T> {
             <pallet_staking::Module<T>>::bonded(&validator_stash).ok_or(Error::<T>::NotStash)?;
         let ledger = <pallet_staking::Ledger<T>>::get(&controller)
             .ok_or_else(|| Error::<T>::NotController)?;
-        let asset_id =
-            <pallet_social_guardians::GuardianDetailHistory<T>>::try_get(&era, &controller)
-                .map_err(|_| Error::<T>::IsNotGuardian)?;
+        let asset_id = <pallet_social_guardians::Module<T>>::guardian_at(era, &controller)
+            .ok_or(Error::<T>::IsNotGuardian)?;
 
         let mut claimed_rewards = Self::claimed_rewards(&controller);
         match claimed_rewards.binary_search(&era) {
@@ -166,10 +409,24 @@ impl<T: Config> Module<T> {
             return Ok(());
         }
 
-        // This is the fraction of the total reward that the validator and the
-        // nominators will get.
+        // The token minted for the era follows its emission policy instead of the raw payout.
+        // The era payout is shared by all the validators, while the emission of a token is
+        // shared by the guardians of that token only.
+        let policy = Self::emission_policy(asset_id);
+        let (era_payout, reward_points) = match &policy {
+            None | Some(EmissionPolicy { curve: EmissionCurve::EraPayout, .. }) => {
+                (era_payout.saturated_into::<u128>(), total_reward_points)
+            }
+            Some(policy) => (
+                policy.curve.emission(era.saturating_sub(policy.start), era_payout.saturated_into()),
+                Self::token_reward_points(era, asset_id, &era_reward_points),
+            ),
+        };
+        let cap = policy.and_then(|policy| policy.cap).map(|cap| cap.saturated_into::<u128>());
+
+        // This is the fraction of the reward that the validator and the nominators will get.
         let validator_total_reward_part =
-            Perbill::from_rational_approximation(validator_reward_points, total_reward_points);
+            Perbill::from_rational_approximation(validator_reward_points, reward_points);
 
         // This is how much validator + nominators are entitled to.
         let validator_total_payout = validator_total_reward_part * era_payout;
@@ -186,42 +443,67 @@ impl<T: Config> Module<T> {
         let validator_staking_payout = validator_exposure_part * validator_leftover_payout;
 
         // We can now make total validator payout:
-        let total_validator_payout =
-            (validator_staking_payout + validator_commission_payout).saturated_into::<u128>();
-        if let Ok(()) = T::FungibleToken::issue(
-            &asset_id,
-            &ledger.stash,
-            total_validator_payout.saturated_into(),
-        ) {
-            Self::deposit_event(RawEvent::Reward(
-                asset_id,
-                ledger.stash,
-                total_validator_payout.saturated_into(),
-            ));
-        }
+        let total_validator_payout = validator_staking_payout + validator_commission_payout;
+        Self::make_payout(asset_id, &ledger.stash, total_validator_payout, cap);
 
         // Lets now calculate how this is split to the nominators.
         // Reward only the clipped exposures. Note this is not necessarily sorted.
-        for nominator in exposure.others.iter() {
+        let max_nominators =
+            <T as pallet_staking::Config>::MaxNominatorRewardedPerValidator::get() as usize;
+        for nominator in exposure.others.iter().take(max_nominators) {
             let nominator_exposure_part =
                 Perbill::from_rational_approximation(nominator.value, exposure.total);
 
-            let nominator_reward =
-                (nominator_exposure_part * validator_leftover_payout).saturated_into::<u128>();
+            let nominator_reward = nominator_exposure_part * validator_leftover_payout;
             // We can now make nominator payout:
-            if let Ok(()) = T::FungibleToken::issue(
-                &asset_id,
-                &nominator.who,
-                nominator_reward.saturated_into(),
-            ) {
-                Self::deposit_event(RawEvent::Reward(
-                    asset_id,
-                    nominator.who.clone(),
-                    nominator_reward.saturated_into(),
-                ));
-            }
+            Self::make_payout(asset_id, &nominator.who, nominator_reward, cap);
         }
 
         Ok(())
     }
+
+    /// Reward points of the validators whose controllers were guardians of `asset_id` in `era`.
+    /// Counted over all the validators of the era on the first call, and stored for later payouts.
+    fn token_reward_points(
+        era: EraIndex,
+        asset_id: T::AssetId,
+        era_reward_points: &EraRewardPoints<T::AccountId>,
+    ) -> RewardPoint {
+        if let Some(points) = Self::eras_token_reward_points(era, asset_id) {
+            return points;
+        }
+        let points = era_reward_points
+            .individual
+            .iter()
+            .filter(|(stash, _)| {
+                <pallet_staking::Module<T>>::bonded(stash).map_or(false, |controller| {
+                    <pallet_social_guardians::Module<T>>::guardian_at(era, &controller) == Some(asset_id)
+                })
+            })
+            .fold(0 as RewardPoint, |total, (_, points)| total.saturating_add(*points));
+        <ErasTokenRewardPoints<T>>::insert(era, asset_id, points);
+        points
+    }
+
+    /// Mints the reward of a stash to its reward destination, without exceeding the supply `cap`.
+    fn make_payout(asset_id: T::AssetId, stash: &T::AccountId, amount: u128, cap: Option<u128>) {
+        let amount = match cap {
+            Some(cap) => {
+                let supply = <pallet_assets::Module<T>>::total_supply(asset_id).saturated_into::<u128>();
+                amount.min(cap.saturating_sub(supply))
+            }
+            None => amount,
+        };
+        if amount.is_zero() {
+            return;
+        }
+        let dest = match Self::reward_destination(stash) {
+            RewardDestination::Stash => stash.clone(),
+            RewardDestination::Account(account) => account,
+            RewardDestination::None => return,
+        };
+        if let Ok(()) = T::FungibleToken::issue(&asset_id, &dest, amount.saturated_into()) {
+            Self::deposit_event(RawEvent::Reward(asset_id, dest, amount.saturated_into()));
+        }
+    }
 }
@@ -0,0 +1,278 @@
+use crate as pallet_social_treasury;
+use codec::{Decode, Encode};
+use frame_support::parameter_types;
+use pallet_staking::{ActiveEraInfo, EraIndex};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+type AccountId = u64;
+type Balance = u64;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Module, Call, Config, Storage, Event<T>},
+        Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+        Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
+        Staking: pallet_staking::{Module, Call, Storage, Config<T>, Event<T>},
+        SocialGuardians: pallet_social_guardians::{Module, Call, Storage, Event<T>},
+        SocialTreasury: pallet_social_treasury::{Module, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Call = Call;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const AssetDepositBase: u64 = 1;
+    pub const AssetDepositPerZombie: u64 = 1;
+    pub const StringLimit: u32 = 50;
+    pub const MetadataDepositBase: u64 = 1;
+    pub const MetadataDepositPerByte: u64 = 1;
+    pub const ApprovalDeposit: u64 = 1;
+}
+
+impl pallet_assets::Config for Test {
+    type Currency = Balances;
+    type Event = Event;
+    type Balance = u64;
+    type AssetId = u32;
+    type ForceOrigin = frame_system::EnsureRoot<u64>;
+    type AssetDepositBase = AssetDepositBase;
+    type AssetDepositPerZombie = AssetDepositPerZombie;
+    type StringLimit = StringLimit;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type ApprovalDeposit = ApprovalDeposit;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 5;
+}
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+impl pallet_session::historical::Config for Test {
+    type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
+    type FullIdentificationOf = pallet_staking::ExposureOf<Test>;
+}
+
+sp_runtime::impl_opaque_keys! {
+    pub struct SessionKeys {
+        pub foo: sp_runtime::testing::UintAuthorityId,
+    }
+}
+
+pub struct TestSessionHandler;
+impl pallet_session::SessionHandler<AccountId> for TestSessionHandler {
+    const KEY_TYPE_IDS: &'static [sp_runtime::KeyTypeId] = &[];
+
+    fn on_genesis_session<Ks: sp_runtime::traits::OpaqueKeys>(_validators: &[(AccountId, Ks)]) {}
+
+    fn on_new_session<Ks: sp_runtime::traits::OpaqueKeys>(
+        _: bool,
+        _: &[(AccountId, Ks)],
+        _: &[(AccountId, Ks)],
+    ) {
+    }
+
+    fn on_disabled(_: usize) {}
+}
+
+impl pallet_session::Config for Test {
+    type SessionManager = pallet_session::historical::NoteHistoricalRoot<Test, Staking>;
+    type Keys = SessionKeys;
+    type ShouldEndSession = pallet_session::PeriodicSessions<(), ()>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<(), ()>;
+    type SessionHandler = TestSessionHandler;
+    type Event = Event;
+    type ValidatorId = AccountId;
+    type ValidatorIdOf = pallet_staking::StashOf<Test>;
+    type DisabledValidatorsThreshold = ();
+    type WeightInfo = ();
+}
+
+pallet_staking_reward_curve::build! {
+    const I_NPOS: sp_runtime::curve::PiecewiseLinear<'static> = curve!(
+        min_inflation: 0_025_000,
+        max_inflation: 0_100_000,
+        ideal_stake: 0_500_000,
+        falloff: 0_050_000,
+        max_piece_count: 40,
+        test_precision: 0_005_000,
+    );
+}
+parameter_types! {
+    pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
+    pub const MaxNominatorRewardedPerValidator: u32 = 2;
+    pub const UnsignedPriority: u64 = 1 << 20;
+}
+
+pub type Extrinsic = sp_runtime::testing::TestXt<Call, ()>;
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = Extrinsic;
+}
+
+impl pallet_staking::Config for Test {
+    type Currency = Balances;
+    type UnixTime = pallet_timestamp::Module<Self>;
+    type CurrencyToVote = frame_support::traits::SaturatingCurrencyToVote;
+    type RewardRemainder = ();
+    type Event = Event;
+    type Slash = ();
+    type Reward = ();
+    type SessionsPerEra = ();
+    type SlashDeferDuration = ();
+    type SlashCancelOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    type BondingDuration = ();
+    type SessionInterface = Self;
+    type RewardCurve = RewardCurve;
+    type NextNewSession = Session;
+    type ElectionLookahead = ();
+    type Call = Call;
+    type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+    type UnsignedPriority = UnsignedPriority;
+    type MaxIterations = ();
+    type MinSolutionScoreBump = ();
+    type OffchainSolutionWeightLimit = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const GuardianDeposit: u64 = 10;
+    pub const MaxGuardians: u32 = 3;
+    pub const MaxPrunedPerBlock: u32 = 3;
+}
+
+impl pallet_social_guardians::Config for Test {
+    type Event = Event;
+    type Currency = Balances;
+    type GuardianDeposit = GuardianDeposit;
+    type MaxGuardians = MaxGuardians;
+    type MaxPrunedPerBlock = MaxPrunedPerBlock;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MaxPayoutsPerBlock: u32 = 2;
+}
+
+impl pallet_social_treasury::Config for Test {
+    type Event = Event;
+    type FungibleToken = Assets;
+    type MaxPayoutsPerBlock = MaxPayoutsPerBlock;
+    type EmissionOrigin = frame_system::EnsureRoot<u64>;
+    type WeightInfo = ();
+}
+
+/// Owner of the social tokens
+pub const OWNER: u64 = 1;
+/// Social token of the guardians
+pub const TOKEN: u32 = 1;
+
+/// Makes `era` the active and current era, so that the eras before it ended
+pub fn start_era(era: EraIndex) {
+    let active_era = ActiveEraInfo::decode(&mut &(era, Option::<u64>::None).encode()[..])
+        .expect("an era index and no start decode as the active era");
+    pallet_staking::ActiveEra::put(active_era);
+    pallet_staking::CurrentEra::put(era);
+}
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+    fn default() -> Self {
+        Self
+    }
+}
+
+impl ExtBuilder {
+    pub fn build(self) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Test> {
+            balances: vec![1, 2, 10, 11, 20, 21, 30, 31, 101, 102, 103]
+                .into_iter()
+                .map(|account| (account, 100))
+                .collect(),
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        let (admin, max_zombies, min_balance) = (OWNER, 10, 1);
+        pallet_assets::GenesisConfig::<Test> {
+            assets: vec![
+                (TOKEN, OWNER, admin, max_zombies, min_balance),
+                (TOKEN + 1, OWNER, admin, max_zombies, min_balance),
+            ],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    ExtBuilder::default().build()
+}
@@ -0,0 +1,227 @@
+use crate::{mock::*, EmissionCurve, Error, PendingPayouts, RawEvent, RewardDestination};
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use pallet_staking::{EraIndex, EraRewardPoints, Exposure, IndividualExposure, StakingLedger};
+
+/// Bonds `stash` to `controller`
+fn bond(stash: u64, controller: u64) {
+    pallet_staking::Bonded::<Test>::insert(stash, controller);
+    pallet_staking::Ledger::<Test>::insert(controller, StakingLedger {
+        stash,
+        total: 100,
+        active: 100,
+        unlocking: vec![],
+        claimed_rewards: vec![],
+    });
+}
+
+/// Bonds `stash` to `controller` and makes the controller a guardian of `token` in `eras`
+fn guardian(stash: u64, controller: u64, token: u32, eras: Vec<EraIndex>) {
+    bond(stash, controller);
+    for era in eras {
+        pallet_social_guardians::GuardianDetailHistory::<Test>::insert(era, controller, token);
+    }
+}
+
+/// Sets the payout and the reward points of the validator stashes of `era`
+fn reward_era(era: EraIndex, payout: u64, points: Vec<(u64, u32)>) {
+    pallet_staking::ErasValidatorReward::<Test>::insert(era, payout);
+    pallet_staking::ErasRewardPoints::<Test>::insert(era, EraRewardPoints {
+        total: points.iter().map(|(_, points)| points).sum(),
+        individual: points.into_iter().collect(),
+    });
+}
+
+/// Sets the own stake of a validator stash and the stakes of its nominators in `era`
+fn expose(era: EraIndex, stash: u64, own: u64, others: Vec<(u64, u64)>) {
+    pallet_staking::ErasStakersClipped::<Test>::insert(era, stash, Exposure {
+        total: own + others.iter().map(|(_, value)| value).sum::<u64>(),
+        own,
+        others: others.into_iter().map(|(who, value)| IndividualExposure { who, value }).collect(),
+    });
+}
+
+fn pending_payouts() -> usize {
+    PendingPayouts::<Test>::iter().count()
+}
+
+fn treasury_event_exists(event: RawEvent<u64, u32, u64>) {
+    let event = Event::pallet_social_treasury(event);
+    assert!(System::events().iter().any(|record| record.event == event));
+}
+
+#[test]
+fn payouts_should_be_queued_at_era_change() {
+    new_test_ext().execute_with(|| {
+        guardian(11, 10, TOKEN, vec![0]);
+        bond(21, 20);
+        reward_era(0, 1000, vec![(11, 50), (21, 50)]);
+        expose(0, 11, 100, vec![]);
+        expose(0, 21, 100, vec![]);
+
+        // no era ended yet
+        SocialTreasury::on_initialize(1);
+        assert_eq!(SocialTreasury::last_queued_era(), None);
+
+        start_era(1);
+        SocialTreasury::on_initialize(2);
+        assert_eq!(SocialTreasury::last_queued_era(), Some(0));
+        treasury_event_exists(RawEvent::PayoutsQueued(0, 1));
+        treasury_event_exists(RawEvent::Reward(TOKEN, 11, 500));
+        assert_eq!(Assets::balance(TOKEN, 11), 500);
+        assert_eq!(Assets::balance(TOKEN, 21), 0);
+        assert_eq!(SocialTreasury::claimed_rewards(10), vec![0]);
+        assert_eq!(pending_payouts(), 0);
+
+        // an era is queued once
+        SocialTreasury::on_initialize(3);
+        assert_eq!(Assets::balance(TOKEN, 11), 500);
+    })
+}
+
+#[test]
+fn payouts_should_be_capped_per_block() {
+    new_test_ext().execute_with(|| {
+        for (stash, controller) in vec![(11, 10), (21, 20), (31, 30)] {
+            guardian(stash, controller, TOKEN, vec![0]);
+            expose(0, stash, 100, vec![]);
+        }
+        reward_era(0, 900, vec![(11, 30), (21, 30), (31, 30)]);
+
+        start_era(1);
+        SocialTreasury::on_initialize(2);
+        treasury_event_exists(RawEvent::PayoutsQueued(0, 3));
+        assert_eq!(pending_payouts(), 1);
+        let paid = |stash| Assets::balance(TOKEN, stash);
+        assert_eq!(paid(11) + paid(21) + paid(31), 600);
+
+        SocialTreasury::on_initialize(3);
+        assert_eq!(pending_payouts(), 0);
+        assert_eq!((paid(11), paid(21), paid(31)), (300, 300, 300));
+    })
+}
+
+#[test]
+fn claimed_eras_should_not_be_queued() {
+    new_test_ext().execute_with(|| {
+        guardian(11, 10, TOKEN, vec![0]);
+        reward_era(0, 1000, vec![(11, 100)]);
+        expose(0, 11, 100, vec![]);
+
+        start_era(1);
+        assert_ok!(SocialTreasury::payout_stakers(Origin::signed(2), 11, 0));
+        assert_eq!(Assets::balance(TOKEN, 11), 1000);
+
+        SocialTreasury::on_initialize(2);
+        treasury_event_exists(RawEvent::PayoutsQueued(0, 0));
+        assert_eq!(pending_payouts(), 0);
+        assert_eq!(Assets::balance(TOKEN, 11), 1000);
+        assert_noop!(
+            SocialTreasury::payout_stakers(Origin::signed(2), 11, 0),
+            Error::<Test>::AlreadyClaimed
+        );
+    })
+}
+
+#[test]
+fn skipped_eras_should_be_queued() {
+    new_test_ext().execute_with(|| {
+        guardian(11, 10, TOKEN, vec![0, 1, 2]);
+        for era in 0..3 {
+            reward_era(era, 1000, vec![(11, 100)]);
+            expose(era, 11, 100, vec![]);
+        }
+
+        // eras ended while nothing was queued, as before the upgrade, are queued one per block
+        start_era(3);
+        SocialTreasury::on_initialize(2);
+        assert_eq!(SocialTreasury::last_queued_era(), Some(0));
+        assert_eq!(Assets::balance(TOKEN, 11), 1000);
+        SocialTreasury::on_initialize(3);
+        SocialTreasury::on_initialize(4);
+        assert_eq!(SocialTreasury::last_queued_era(), Some(2));
+        assert_eq!(Assets::balance(TOKEN, 11), 3000);
+
+        SocialTreasury::on_initialize(5);
+        assert_eq!(SocialTreasury::last_queued_era(), Some(2));
+        assert_eq!(SocialTreasury::claimed_rewards(10), vec![0, 1, 2]);
+    })
+}
+
+#[test]
+fn nominators_should_be_paid_up_to_the_cap() {
+    new_test_ext().execute_with(|| {
+        guardian(11, 10, TOKEN, vec![0]);
+        reward_era(0, 1000, vec![(11, 100)]);
+        expose(0, 11, 100, vec![(101, 100), (102, 100), (103, 100)]);
+
+        start_era(1);
+        assert_ok!(SocialTreasury::payout_stakers(Origin::signed(2), 11, 0));
+        assert_eq!(Assets::balance(TOKEN, 11), 250);
+        assert_eq!(Assets::balance(TOKEN, 101), 250);
+        assert_eq!(Assets::balance(TOKEN, 102), 250);
+        // beyond `MaxNominatorRewardedPerValidator`
+        assert_eq!(Assets::balance(TOKEN, 103), 0);
+    })
+}
+
+#[test]
+fn reward_destination_should_redirect_or_opt_out() {
+    new_test_ext().execute_with(|| {
+        guardian(11, 10, TOKEN, vec![0]);
+        bond(101, 201);
+        bond(102, 202);
+        reward_era(0, 1000, vec![(11, 100)]);
+        expose(0, 11, 200, vec![(101, 100), (102, 100)]);
+
+        // only stashes have a reward destination
+        assert_noop!(
+            SocialTreasury::set_reward_destination(Origin::signed(201), RewardDestination::None),
+            Error::<Test>::NotStash
+        );
+        assert_ok!(SocialTreasury::set_reward_destination(Origin::signed(101), RewardDestination::Account(2)));
+        treasury_event_exists(RawEvent::RewardDestinationSet(101, RewardDestination::Account(2)));
+        assert_ok!(SocialTreasury::set_reward_destination(Origin::signed(102), RewardDestination::None));
+        assert_ok!(SocialTreasury::set_reward_destination(Origin::signed(11), RewardDestination::Account(2)));
+        assert_ok!(SocialTreasury::set_reward_destination(Origin::signed(11), RewardDestination::Stash));
+        assert_eq!(SocialTreasury::reward_destination(11), RewardDestination::Stash);
+
+        start_era(1);
+        assert_ok!(SocialTreasury::payout_stakers(Origin::signed(2), 11, 0));
+        assert_eq!(Assets::balance(TOKEN, 11), 500);
+        assert_eq!(Assets::balance(TOKEN, 2), 250);
+        assert_eq!(Assets::balance(TOKEN, 101), 0);
+        assert_eq!(Assets::balance(TOKEN, 102), 0);
+        assert_eq!(Assets::total_supply(TOKEN), 750);
+    })
+}
+
+#[test]
+fn fixed_emission_should_be_shared_by_the_token_guardians() {
+    new_test_ext().execute_with(|| {
+        guardian(11, 10, TOKEN, vec![0]);
+        guardian(21, 20, TOKEN, vec![0]);
+        guardian(31, 30, TOKEN + 1, vec![0]);
+        reward_era(0, 1000, vec![(11, 20), (21, 20), (31, 60)]);
+        for stash in vec![11, 21, 31] {
+            expose(0, stash, 100, vec![]);
+        }
+
+        assert_noop!(
+            SocialTreasury::set_emission_policy(Origin::signed(2), TOKEN, Some(EmissionCurve::Fixed(600)), None),
+            Error::<Test>::NotAssetOwner
+        );
+        assert_ok!(SocialTreasury::set_emission_policy(Origin::signed(OWNER), TOKEN, Some(EmissionCurve::Fixed(600)), None));
+
+        start_era(1);
+        assert_ok!(SocialTreasury::payout_stakers(Origin::signed(2), 11, 0));
+        assert_eq!(SocialTreasury::eras_token_reward_points(0, TOKEN), Some(40));
+        assert_ok!(SocialTreasury::payout_stakers(Origin::signed(2), 21, 0));
+        assert_eq!(Assets::balance(TOKEN, 11), 300);
+        assert_eq!(Assets::balance(TOKEN, 21), 300);
+        assert_eq!(Assets::total_supply(TOKEN), 600);
+
+        // without a policy the token follows its share of the era payout
+        assert_ok!(SocialTreasury::payout_stakers(Origin::signed(2), 31, 0));
+        assert_eq!(Assets::balance(TOKEN + 1, 31), 600);
+    })
+}
@@ -45,6 +45,8 @@ pub trait WeightInfo {
     fn extend_bounty_expiry() -> Weight;
     fn on_initialize_proposals(p: u32) -> Weight;
     fn on_initialize_bounties(b: u32) -> Weight;
+    fn set_reward_destination() -> Weight;
+    fn set_emission_policy() -> Weight;
 }
 
 /// Weights for pallet_social_treasury using the Substrate node and recommended hardware.
@@ -163,4 +165,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(DbWeight::get().writes(2 as Weight))
             .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
     }
+    // Not benchmarked: an estimate with the storage accesses of the extrinsic.
+    fn set_reward_destination() -> Weight {
+        (21000000 as Weight)
+            .saturating_add(DbWeight::get().reads(1 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    // Not benchmarked: an estimate with the storage accesses of the extrinsic.
+    fn set_emission_policy() -> Weight {
+        (32000000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
 }